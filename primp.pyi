@@ -1,6 +1,7 @@
 from __future__ import annotations
 
-from typing import Literal, Any
+import os
+from typing import Any, Callable, Iterator, Literal, Mapping
 
 IMPERSONATE = Literal[
     "chrome_100",
@@ -56,7 +57,61 @@ IMPERSONATE = Literal[
     "firefox_117",
     "firefox_128",
     "firefox_133",
+    "brave_1.73",
+    "samsung_internet_27",
+    "android_webview_131",
+    "tor_14",
+    "curl_8",
+    "requests_2",
+    "googlebot",
+    "bingbot",
+    "chrome",
+    "chrome_latest",
+    "edge",
+    "edge_latest",
+    "firefox",
+    "firefox_latest",
+    "firefox_esr",
+    "safari",
+    "safari_latest",
+    "safari_ios",
+    "okhttp",
+    "brave",
+    "tor",
 ]
+REDIRECT_POLICY = Literal["same_origin", "same_host", "https_upgrade_only"] | Callable[[str, str, int], bool]
+IMPERSONATE_OS = Literal["windows", "macos", "linux", "android", "ios"]
+
+class PrimpError(Exception):
+    request_id: int | None
+    method: str | None
+    url: str | None
+    timeout: float | None
+    attempt: int | None
+    errno: int | None
+    os_error: OSError | None
+
+class HTTPError(PrimpError):
+    status_code: int
+    response: Response
+
+class InvalidRequest(PrimpError): ...
+class RequestError(PrimpError): ...
+class ConnectError(RequestError): ...
+class SSLError(ConnectError): ...
+class Timeout(RequestError): ...
+class ConnectTimeout(Timeout): ...
+class ReadTimeout(Timeout): ...
+class TooManyRedirects(RequestError): ...
+class BodyError(RequestError): ...
+
+class Headers(Mapping[str, str]):
+    def __getitem__(self, key: str) -> str: ...
+    def __contains__(self, key: object) -> bool: ...
+    def __len__(self) -> int: ...
+    def __iter__(self) -> Iterator[str]: ...
+    def get_all(self, key: str) -> list[str]: ...
+    def grouped_items(self) -> list[tuple[str, str]]: ...
 
 class Response:
     @property
@@ -64,16 +119,24 @@ class Response:
     @property
     def cookies(self) -> dict[str, str]: ...
     @property
-    def headers(self) -> dict[str, str]: ...
+    def headers(self) -> Headers: ...
     @property
     def status_code(self) -> int: ...
     @property
+    def ok(self) -> bool: ...
+    @property
+    def reason(self) -> str | None: ...
+    @property
     def url(self) -> str: ...
     @property
+    def history(self) -> list[Response]: ...
+    @property
     def encoding(self) -> str: ...
     @property
     def text(self) -> str: ...
     def json(self) -> Any: ...
+    def raise_for_status(self) -> None: ...
+    def save(self, dir_or_path: str | os.PathLike[str] | None = None) -> str: ...
     @property
     def text_markdown(self) -> str: ...
     @property
@@ -81,6 +144,56 @@ class Response:
     @property
     def text_rich(self) -> str: ...
 
+class Retry:
+    def __init__(
+        self,
+        total: int = 3,
+        backoff_factor: float = 0.0,
+        status_forcelist: list[int] | None = None,
+        allowed_methods: list[str] | None = None,
+        respect_retry_after: bool = True,
+        retry_after_max: float = 120,
+        throttle_callback: Callable[[str, int, float], Any] | None = None,
+    ) -> None: ...
+    @property
+    def total(self) -> int: ...
+    @property
+    def backoff_factor(self) -> float: ...
+    @property
+    def status_forcelist(self) -> list[int]: ...
+    @property
+    def allowed_methods(self) -> list[str] | None: ...
+    @property
+    def respect_retry_after(self) -> bool: ...
+    @property
+    def retry_after_max(self) -> float: ...
+
+class MockTransport:
+    def __init__(self) -> None: ...
+    def add(
+        self,
+        url: str,
+        method: str | None = None,
+        status_code: int = 200,
+        headers: dict[str, str] | None = None,
+        content: str | bytes | None = None,
+        json: Any | None = None,
+        delay: float | None = None,
+    ) -> None: ...
+    @property
+    def calls(self) -> list[tuple[str, str]]: ...
+    def reset(self) -> None: ...
+
+class PreparedRequest:
+    method: str
+    url: str
+    headers: dict[str, str]
+    content: bytes | None
+    timeout: float | None
+    http_version: str | None
+    @property
+    def body_length(self) -> int: ...
+
 class Client:
     def __init__(
         self,
@@ -93,13 +206,66 @@ class Client:
         cookie_store: bool | None = True,
         referer: bool | None = True,
         proxy: str | None = None,
-        impersonate: IMPERSONATE | None = None,
+        impersonate: IMPERSONATE | dict[str, IMPERSONATE] | None = None,
         follow_redirects: bool | None = True,
         max_redirects: int | None = 20,
-        verify: bool | None = True,
+        verify: bool | Literal["system"] | str | None = True,
         ca_cert_file: str | None = None,
         https_only: bool | None = False,
         http2_only: bool | None = False,
+        ip_version: Literal["auto", "ipv4", "ipv6"] | None = None,
+        local_address: str | None = None,
+        interface: str | None = None,
+        tcp_nodelay: bool | None = True,
+        tcp_keepalive: float | None = None,
+        pool_idle_timeout: float | None = None,
+        pool_max_idle_per_host: int | None = None,
+        http1_only: bool | None = False,
+        alpn: list[str] | None = None,
+        ca_cert: str | bytes | None = None,
+        verify_hostname: bool | None = True,
+        session_resumption: bool | None = None,
+        ech_grease: bool | None = None,
+        cert_compression: list[Literal["brotli", "zstd", "zlib"]] | None = None,
+        permute_extensions: bool | None = None,
+        tls_ciphers: list[str] | None = None,
+        tls_sigalgs: list[str] | None = None,
+        tls_curves: list[str] | None = None,
+        tls_extension_order: list[int] | None = None,
+        http2_fingerprint: str | None = None,
+        impersonate_profile: dict[str, Any] | None = None,
+        impersonate_profile_file: str | None = None,
+        locale: str | None = None,
+        client_hints: bool | None = False,
+        mobile: bool | None = False,
+        request_context: Literal["document", "xhr", "fetch", "image"] | None = None,
+        track_navigation: bool | None = False,
+        http2_priorities: list[tuple[int, int, int, bool]] | None = None,
+        http2_headers_priority: tuple[int, int, bool] | None = None,
+        http2_settings: dict[str, int | bool | list[int] | None] | None = None,
+        http2_keep_alive_interval: float | None = None,
+        http2_keep_alive_timeout: float | None = None,
+        http2_keep_alive_while_idle: bool | None = False,
+        hpack_never_index: list[str] | None = None,
+        http1_title_case: bool | None = None,
+        retry_stale_connections: bool | None = True,
+        retries: Retry | None = None,
+        error_body_snippet: int | None = None,
+        hooks: dict[Literal["request", "response"], list[Callable[[Any], Any]]] | None = None,
+        transport: MockTransport | None = None,
+        debug: bool | None = False,
+        on_metrics: Callable[[dict[str, Any]], Any] | None = None,
+        normalize_urls: bool | None = True,
+        redirect_policy: REDIRECT_POLICY | list[REDIRECT_POLICY] | None = None,
+        redirect_forward_headers: list[str] | None = None,
+        redirect_strip_headers: list[str] | None = None,
+        redirect_method_policy: Literal["browser", "strict"] | None = None,
+        warn_body_methods: bool | None = True,
+        impersonate_skip_headers: bool | None = False,
+        impersonate_skip_http2: bool | None = False,
+        impersonate_os: IMPERSONATE_OS | None = None,
+        default_encoding: str | None = None,
+        local_urls: bool | None = False,
     ) -> None: ...
     @property
     def headers(self) -> dict[str, str]: ...
@@ -113,6 +279,32 @@ class Client:
     def proxy(self) -> str | None: ...
     @proxy.setter
     def proxy(self, proxy: str) -> None: ...
+    @property
+    def impersonate(self) -> str | None: ...
+    @impersonate.setter
+    def impersonate(self, impersonate: IMPERSONATE) -> None: ...
+    def batch(self, requests: list[dict[str, Any]]) -> list[Response | Exception]: ...
+    def stats(self) -> dict[str, int]: ...
+    def build_request(
+        self,
+        method: str,
+        url: str,
+        params: dict[str, str] | None = None,
+        headers: dict[str, str] | None = None,
+        cookies: dict[str, str] | None = None,
+        content: bytes | None = None,
+        data: dict[str, Any] | None = None,
+        json: Any | None = None,
+        files: dict[str, str] | None = None,
+        auth: tuple[str, str | None] | None = None,
+        auth_bearer: str | None = None,
+        timeout: float | None = 30,
+        http_version: Literal["1.1", "2"] | None = None,
+    ) -> PreparedRequest: ...
+    def send(self, request: PreparedRequest) -> Response: ...
+    def start_har_recording(self) -> None: ...
+    def export_har(self, path: str) -> None: ...
+    def expected_fingerprints(self) -> dict[str, str | None]: ...
     def request(
         self,
         method: str,
@@ -127,6 +319,7 @@ class Client:
         auth: tuple[str, str | None] | None = None,
         auth_bearer: str | None = None,
         timeout: float | None = None,
+        http_version: Literal["1.1", "2"] | None = None,
     ) -> Response: ...
     def get(
         self,
@@ -137,6 +330,10 @@ class Client:
         auth: tuple[str, str | None] | None = None,
         auth_bearer: str | None = None,
         timeout: float | None = None,
+        http_version: Literal["1.1", "2"] | None = None,
+        content: bytes | None = None,
+        data: dict[str, Any] | None = None,
+        json: Any | None = None,
     ) -> Response: ...
     def head(
         self,
@@ -147,6 +344,7 @@ class Client:
         auth: tuple[str, str | None] | None = None,
         auth_bearer: str | None = None,
         timeout: float | None = None,
+        http_version: Literal["1.1", "2"] | None = None,
     ) -> Response: ...
     def options(
         self,
@@ -157,6 +355,7 @@ class Client:
         auth: tuple[str, str | None] | None = None,
         auth_bearer: str | None = None,
         timeout: float | None = None,
+        http_version: Literal["1.1", "2"] | None = None,
     ) -> Response: ...
     def delete(
         self,
@@ -167,6 +366,10 @@ class Client:
         auth: tuple[str, str | None] | None = None,
         auth_bearer: str | None = None,
         timeout: float | None = None,
+        http_version: Literal["1.1", "2"] | None = None,
+        content: bytes | None = None,
+        data: dict[str, Any] | None = None,
+        json: Any | None = None,
     ) -> Response: ...
     def post(
         self,
@@ -181,6 +384,7 @@ class Client:
         auth: tuple[str, str | None] | None = None,
         auth_bearer: str | None = None,
         timeout: float | None = None,
+        http_version: Literal["1.1", "2"] | None = None,
     ) -> Response: ...
     def put(
         self,
@@ -195,6 +399,7 @@ class Client:
         auth: tuple[str, str | None] | None = None,
         auth_bearer: str | None = None,
         timeout: float | None = None,
+        http_version: Literal["1.1", "2"] | None = None,
     ) -> Response: ...
     def patch(
         self,
@@ -209,6 +414,7 @@ class Client:
         auth: tuple[str, str | None] | None = None,
         auth_bearer: str | None = None,
         timeout: float | None = None,
+        http_version: Literal["1.1", "2"] | None = None,
     ) -> Response: ...
 
 def request(
@@ -224,9 +430,10 @@ def request(
     auth: tuple[str, str | None] | None = None,
     auth_bearer: str | None = None,
     timeout: float | None = None,
-    impersonate: IMPERSONATE | None = None,
-    verify: bool | None = None,
+    impersonate: IMPERSONATE | dict[str, IMPERSONATE] | None = None,
+    verify: bool | Literal["system"] | str | None = None,
     ca_cert_file: str | None = None,
+    http_version: Literal["1.1", "2"] | None = None,
 ) -> Response: ...
 def get(
     url: str,
@@ -236,9 +443,13 @@ def get(
     auth: tuple[str, str | None] | None = None,
     auth_bearer: str | None = None,
     timeout: float | None = None,
-    impersonate: IMPERSONATE | None = None,
-    verify: bool | None = None,
+    impersonate: IMPERSONATE | dict[str, IMPERSONATE] | None = None,
+    verify: bool | Literal["system"] | str | None = None,
     ca_cert_file: str | None = None,
+    http_version: Literal["1.1", "2"] | None = None,
+    content: bytes | None = None,
+    data: dict[str, Any] | None = None,
+    json: Any | None = None,
 ) -> Response: ...
 def head(
     url: str,
@@ -248,9 +459,10 @@ def head(
     auth: tuple[str, str | None] | None = None,
     auth_bearer: str | None = None,
     timeout: float | None = None,
-    impersonate: IMPERSONATE | None = None,
-    verify: bool | None = None,
+    impersonate: IMPERSONATE | dict[str, IMPERSONATE] | None = None,
+    verify: bool | Literal["system"] | str | None = None,
     ca_cert_file: str | None = None,
+    http_version: Literal["1.1", "2"] | None = None,
 ) -> Response: ...
 def options(
     url: str,
@@ -260,9 +472,10 @@ def options(
     auth: tuple[str, str | None] | None = None,
     auth_bearer: str | None = None,
     timeout: float | None = None,
-    impersonate: IMPERSONATE | None = None,
-    verify: bool | None = None,
+    impersonate: IMPERSONATE | dict[str, IMPERSONATE] | None = None,
+    verify: bool | Literal["system"] | str | None = None,
     ca_cert_file: str | None = None,
+    http_version: Literal["1.1", "2"] | None = None,
 ) -> Response: ...
 def delete(
     url: str,
@@ -272,9 +485,13 @@ def delete(
     auth: tuple[str, str | None] | None = None,
     auth_bearer: str | None = None,
     timeout: float | None = None,
-    impersonate: IMPERSONATE | None = None,
-    verify: bool | None = None,
+    impersonate: IMPERSONATE | dict[str, IMPERSONATE] | None = None,
+    verify: bool | Literal["system"] | str | None = None,
     ca_cert_file: str | None = None,
+    http_version: Literal["1.1", "2"] | None = None,
+    content: bytes | None = None,
+    data: dict[str, Any] | None = None,
+    json: Any | None = None,
 ) -> Response: ...
 def post(
     url: str,
@@ -288,9 +505,10 @@ def post(
     auth: tuple[str, str | None] | None = None,
     auth_bearer: str | None = None,
     timeout: float | None = None,
-    impersonate: IMPERSONATE | None = None,
-    verify: bool | None = None,
+    impersonate: IMPERSONATE | dict[str, IMPERSONATE] | None = None,
+    verify: bool | Literal["system"] | str | None = None,
     ca_cert_file: str | None = None,
+    http_version: Literal["1.1", "2"] | None = None,
 ) -> Response: ...
 def put(
     url: str,
@@ -304,9 +522,10 @@ def put(
     auth: tuple[str, str | None] | None = None,
     auth_bearer: str | None = None,
     timeout: float | None = None,
-    impersonate: IMPERSONATE | None = None,
-    verify: bool | None = None,
+    impersonate: IMPERSONATE | dict[str, IMPERSONATE] | None = None,
+    verify: bool | Literal["system"] | str | None = None,
     ca_cert_file: str | None = None,
+    http_version: Literal["1.1", "2"] | None = None,
 ) -> Response: ...
 def patch(
     url: str,
@@ -320,7 +539,16 @@ def patch(
     auth: tuple[str, str | None] | None = None,
     auth_bearer: str | None = None,
     timeout: float | None = None,
-    impersonate: IMPERSONATE | None = None,
-    verify: bool | None = None,
+    impersonate: IMPERSONATE | dict[str, IMPERSONATE] | None = None,
+    verify: bool | Literal["system"] | str | None = None,
     ca_cert_file: str | None = None,
+    http_version: Literal["1.1", "2"] | None = None,
 ) -> Response: ...
+def from_ja3(ja3: str) -> dict[str, list[str]]: ...
+def profile_from_har(har_file: str, client_hello_file: str | None = None) -> dict[str, Any]: ...
+def impersonate_aliases() -> dict[str, str]: ...
+def impersonate_list() -> list[str]: ...
+def impersonate_os_list() -> list[IMPERSONATE_OS]: ...
+def default_headers(impersonate: IMPERSONATE, impersonate_os: IMPERSONATE_OS | None = None) -> dict[str, str]: ...
+def configure_runtime(worker_threads: int) -> None: ...
+def set_log_level(level: Literal["off", "error", "warning", "info", "debug"] = "debug") -> None: ...
@@ -0,0 +1,203 @@
+use pyo3::{
+    prelude::*,
+    types::{PyBytes, PyDict, PyString, PyTuple},
+};
+use rquest::StatusCode;
+
+use crate::error::{
+    BodyError, ConnectError, ConnectTimeout, PrimpError, ReadTimeout, SSLError, Timeout,
+    TooManyRedirects,
+};
+use crate::response::Response;
+use crate::Client;
+
+/// Transport adapter for `requests`, to send the requests of a `requests.Session` with primp:
+/// `session.mount("https://", PrimpAdapter(impersonate="chrome_131"))`.
+///
+/// Implements the `requests.adapters.BaseAdapter` interface (`send` and `close`) with a `Client`
+/// created from the keyword arguments. Redirects are left to the session (`follow_redirects=False`
+/// unless given). The client settings are used for TLS and proxies, the `verify`, `cert` and
+/// `proxies` arguments of a request are ignored.
+#[pyclass(module = "primp.adapters")]
+pub struct PrimpAdapter {
+    #[pyo3(get)]
+    client: Py<Client>,
+}
+
+#[pymethods]
+impl PrimpAdapter {
+    #[new]
+    #[pyo3(signature = (**kwargs))]
+    fn new(py: Python, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let kwargs = match kwargs {
+            Some(kwargs) => kwargs.copy()?,
+            None => PyDict::new(py),
+        };
+        if !kwargs.contains("follow_redirects")? {
+            kwargs.set_item("follow_redirects", false)?;
+        }
+        let client = py
+            .get_type::<Client>()
+            .call((), Some(&kwargs))?
+            .downcast_into::<Client>()?
+            .unbind();
+        Ok(PrimpAdapter { client })
+    }
+
+    /// Send a `requests.PreparedRequest` and return a `requests.Response`.
+    #[pyo3(signature = (request, stream=false, timeout=None, verify=None, cert=None, proxies=None))]
+    fn send<'py>(
+        slf: &Bound<'py, Self>,
+        request: &Bound<'py, PyAny>,
+        stream: bool,
+        timeout: Option<&Bound<'py, PyAny>>,
+        verify: Option<&Bound<'py, PyAny>>,
+        cert: Option<&Bound<'py, PyAny>>,
+        proxies: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let _ = (stream, verify, cert, proxies);
+        let py = slf.py();
+        let kwargs = PyDict::new(py);
+        let headers = PyDict::new(py);
+        for item in request
+            .getattr("headers")?
+            .call_method0("items")?
+            .try_iter()?
+        {
+            let (name, value): (String, String) = item?.extract()?;
+            headers.set_item(name, value)?;
+        }
+        kwargs.set_item("headers", headers)?;
+        if let Some(content) = request_body(&request.getattr("body")?)? {
+            kwargs.set_item("content", PyBytes::new(py, &content))?;
+        }
+        kwargs.set_item("timeout", timeout.map(parse_timeout).transpose()?.flatten())?;
+
+        let method = request.getattr("method")?;
+        let url = request.getattr("url")?;
+        let response = slf
+            .borrow()
+            .client
+            .bind(py)
+            .call_method("request", (method, url), Some(&kwargs))
+            .map_err(|e| requests_error(py, e, request))?
+            .downcast_into::<Response>()?;
+        requests_response(slf, &response.borrow(), request)
+    }
+
+    fn close(&self) {}
+}
+
+/// Body of a `requests.PreparedRequest`: None, str, bytes, a file-like object or chunks.
+fn request_body(body: &Bound<'_, PyAny>) -> PyResult<Option<Vec<u8>>> {
+    if body.is_none() {
+        return Ok(None);
+    }
+    if let Ok(body) = body.downcast::<PyString>() {
+        return Ok(Some(body.to_str()?.as_bytes().to_vec()));
+    }
+    if let Ok(body) = body.downcast::<PyBytes>() {
+        return Ok(Some(body.as_bytes().to_vec()));
+    }
+    if body.hasattr("read")? {
+        return request_body(&body.call_method0("read")?);
+    }
+    let mut content = Vec::new();
+    for chunk in body.try_iter()? {
+        content.extend(request_body(&chunk?)?.unwrap_or_default());
+    }
+    Ok(Some(content))
+}
+
+/// Total timeout of a `requests` timeout: seconds or a (connect, read) tuple.
+fn parse_timeout(timeout: &Bound<'_, PyAny>) -> PyResult<Option<f64>> {
+    match timeout.downcast::<PyTuple>() {
+        Ok(timeouts) => {
+            let timeouts: Vec<Option<f64>> = timeouts.extract()?;
+            Ok(timeouts.into_iter().flatten().reduce(|a, b| a + b))
+        }
+        Err(_) => timeout.extract(),
+    }
+}
+
+fn requests_response<'py>(
+    adapter: &Bound<'py, PrimpAdapter>,
+    response: &Response,
+    request: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py = adapter.py();
+    let headers = py
+        .import("requests.structures")?
+        .getattr("CaseInsensitiveDict")?
+        .call1((response.headers.bind(py).call_method0("items")?,))?;
+    let encoding = py
+        .import("requests.utils")?
+        .getattr("get_encoding_from_headers")?
+        .call1((&headers,))?;
+    let reason = StatusCode::from_u16(response.status_code)
+        .ok()
+        .and_then(|status| status.canonical_reason());
+    let raw = py
+        .import("io")?
+        .getattr("BytesIO")?
+        .call1((response.content.bind(py),))?;
+
+    let resp = py.import("requests.models")?.getattr("Response")?.call0()?;
+    resp.setattr("status_code", response.status_code)?;
+    resp.setattr("headers", headers)?;
+    resp.setattr("_content", response.content.bind(py))?;
+    resp.setattr("_content_consumed", true)?;
+    resp.setattr("raw", raw)?;
+    resp.setattr("url", &response.url)?;
+    resp.setattr("encoding", encoding)?;
+    resp.setattr("reason", reason)?;
+    resp.setattr("request", request)?;
+    resp.setattr("connection", adapter)?;
+    Ok(resp)
+}
+
+/// The `requests.exceptions` counterpart of a primp exception, other exceptions are kept.
+fn requests_error(py: Python, err: PyErr, request: &Bound<'_, PyAny>) -> PyErr {
+    let name = if err.is_instance_of::<ConnectTimeout>(py) {
+        "ConnectTimeout"
+    } else if err.is_instance_of::<ReadTimeout>(py) {
+        "ReadTimeout"
+    } else if err.is_instance_of::<Timeout>(py) {
+        "Timeout"
+    } else if err.is_instance_of::<SSLError>(py) {
+        "SSLError"
+    } else if err.is_instance_of::<ConnectError>(py) {
+        "ConnectionError"
+    } else if err.is_instance_of::<TooManyRedirects>(py) {
+        "TooManyRedirects"
+    } else if err.is_instance_of::<BodyError>(py) {
+        "ChunkedEncodingError"
+    } else if err.is_instance_of::<PrimpError>(py) {
+        "RequestException"
+    } else {
+        return err;
+    };
+    let result = (|| -> PyResult<PyErr> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("request", request)?;
+        let exception = py
+            .import("requests.exceptions")?
+            .getattr(name)?
+            .call((err.value(py).str()?,), Some(&kwargs))?;
+        let requests_err = PyErr::from_value(exception);
+        requests_err.set_cause(py, Some(err.clone_ref(py)));
+        Ok(requests_err)
+    })();
+    result.unwrap_or_else(|e| e)
+}
+
+/// Add the `primp.adapters` submodule.
+pub fn register_adapters(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    let adapters = PyModule::new(py, "adapters")?;
+    adapters.add_class::<PrimpAdapter>()?;
+    m.add_submodule(&adapters)?;
+    py.import("sys")?
+        .getattr("modules")?
+        .set_item("primp.adapters", adapters)
+}
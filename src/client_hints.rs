@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use rquest::header::{HeaderMap, HeaderName, HeaderValue};
+
+/// High-entropy client hints of a Chromium impersonation, sent to the origins that ask for them with `Accept-CH`.
+#[derive(Debug)]
+pub struct ClientHints {
+    hints: Vec<(HeaderName, HeaderValue)>,
+    origins: Mutex<HashMap<String, Vec<HeaderName>>>,
+}
+
+/// `sec-ch-ua-platform-version`, `sec-ch-ua-arch` and `sec-ch-ua-bitness` of the user agent's OS.
+fn platform(user_agent: &str) -> (String, &'static str, &'static str) {
+    if user_agent.contains("Windows NT") {
+        // Windows 11 reports 13.0.0 and above
+        ("15.0.0".to_string(), "x86", "64")
+    } else if user_agent.contains("Macintosh") {
+        ("14.5.0".to_string(), "x86", "64")
+    } else if let Some(version) = user_agent
+        .split("Android ")
+        .nth(1)
+        .and_then(|rest| rest.split([';', ')']).next())
+    {
+        let mut parts: Vec<&str> = version.trim().split('.').collect();
+        parts.resize(3, "0");
+        (parts.join("."), "", "")
+    } else {
+        ("6.5.0".to_string(), "x86", "64")
+    }
+}
+
+impl ClientHints {
+    /// Derive the hints from the impersonation headers, consistent with its user agent and brands.
+    pub fn new(headers: Option<&HeaderMap>) -> Result<Self> {
+        let header = |name| {
+            headers
+                .and_then(|headers| headers.get::<&str>(name))
+                .and_then(|value| value.to_str().ok())
+        };
+        let (Some(user_agent), Some(sec_ch_ua)) = (header("user-agent"), header("sec-ch-ua"))
+        else {
+            return Err(anyhow!(
+                "client_hints requires a Chromium-based impersonation (with sec-ch-ua)"
+            ));
+        };
+        let full_version = user_agent
+            .split("Chrome/")
+            .nth(1)
+            .and_then(|rest| rest.split(' ').next())
+            .ok_or_else(|| anyhow!("client_hints: no Chrome version in the user agent"))?;
+        // The user agent is reduced to MAJOR.0.0.0, the brand versions follow it
+        let full_version_list = sec_ch_ua
+            .split(", ")
+            .map(|brand| match brand.rsplit_once(";v=\"") {
+                Some((name, version)) => {
+                    format!("{name};v=\"{}.0.0.0\"", version.trim_end_matches('"'))
+                }
+                None => brand.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let (platform_version, arch, bitness) = platform(user_agent);
+        let model = if user_agent.contains("Android") {
+            user_agent
+                .split("; ")
+                .nth(2)
+                .and_then(|model| model.split(')').next())
+                .unwrap_or_default()
+        } else {
+            ""
+        };
+
+        let (viewport_width, dpr) = if user_agent.contains("Mobile") {
+            ("412", "2.625")
+        } else if user_agent.contains("Macintosh") {
+            ("1440", "2")
+        } else {
+            ("1920", "1")
+        };
+
+        let hints = [
+            ("sec-ch-ua-arch", format!("\"{arch}\"")),
+            ("sec-ch-ua-bitness", format!("\"{bitness}\"")),
+            ("sec-ch-ua-full-version", format!("\"{full_version}\"")),
+            ("sec-ch-ua-full-version-list", full_version_list),
+            ("sec-ch-ua-model", format!("\"{model}\"")),
+            (
+                "sec-ch-ua-platform-version",
+                format!("\"{platform_version}\""),
+            ),
+            ("sec-ch-ua-wow64", "?0".to_string()),
+            ("sec-ch-viewport-width", viewport_width.to_string()),
+            ("sec-ch-dpr", dpr.to_string()),
+        ]
+        .into_iter()
+        .map(|(name, value)| {
+            Ok((
+                HeaderName::from_static(name),
+                HeaderValue::from_str(&value)?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+        Ok(ClientHints {
+            hints,
+            origins: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Hints the origin asked for in a previous response.
+    pub fn headers(&self, origin: &str) -> HeaderMap {
+        let origins = self.origins.lock().unwrap();
+        let mut headers = HeaderMap::new();
+        if let Some(requested) = origins.get(origin) {
+            for (name, value) in &self.hints {
+                if requested.contains(name) {
+                    headers.insert(name.clone(), value.clone());
+                }
+            }
+        }
+        headers
+    }
+
+    /// Remember the hints requested by the `Accept-CH` response header of the origin.
+    pub fn update(&self, origin: &str, accept_ch: Option<&HeaderValue>) {
+        let Some(accept_ch) = accept_ch.and_then(|value| value.to_str().ok()) else {
+            return;
+        };
+        let requested = accept_ch
+            .split(',')
+            .filter_map(|name| HeaderName::from_bytes(name.trim().as_bytes()).ok())
+            .collect();
+        self.origins
+            .lock()
+            .unwrap()
+            .insert(origin.to_string(), requested);
+    }
+}
+
+#[cfg(test)]
+mod client_hints_tests {
+    use super::*;
+
+    fn chrome_headers(user_agent: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("user-agent", HeaderValue::from_static(user_agent));
+        headers.insert(
+            "sec-ch-ua",
+            HeaderValue::from_static(
+                r#""Google Chrome";v="131", "Chromium";v="131", "Not_A Brand";v="24""#,
+            ),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_client_hints_windows() {
+        let hints = ClientHints::new(Some(&chrome_headers(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36",
+        )))
+        .unwrap();
+        let origin = "https://example.com";
+        assert!(hints.headers(origin).is_empty());
+
+        hints.update(
+            origin,
+            Some(&HeaderValue::from_static(
+                "Sec-CH-UA-Platform-Version, sec-ch-ua-full-version-list, sec-ch-unknown",
+            )),
+        );
+        let headers = hints.headers(origin);
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["sec-ch-ua-platform-version"], "\"15.0.0\"");
+        assert_eq!(
+            headers["sec-ch-ua-full-version-list"],
+            r#""Google Chrome";v="131.0.0.0", "Chromium";v="131.0.0.0", "Not_A Brand";v="24.0.0.0""#
+        );
+        assert!(hints.headers("https://other.example.com").is_empty());
+    }
+
+    #[test]
+    fn test_client_hints_android() {
+        let hints = ClientHints::new(Some(&chrome_headers(
+            "Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Mobile Safari/537.36",
+        )))
+        .unwrap();
+        hints.update(
+            "https://example.com",
+            Some(&HeaderValue::from_static(
+                "sec-ch-ua-model, sec-ch-ua-platform-version, sec-ch-viewport-width",
+            )),
+        );
+        let headers = hints.headers("https://example.com");
+        assert_eq!(headers["sec-ch-viewport-width"], "412");
+        assert_eq!(headers["sec-ch-ua-model"], "\"K\"");
+        assert_eq!(headers["sec-ch-ua-platform-version"], "\"10.0.0\"");
+    }
+
+    #[test]
+    fn test_client_hints_not_chromium() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "user-agent",
+            HeaderValue::from_static("Mozilla/5.0 (rv:133.0) Gecko/20100101 Firefox/133.0"),
+        );
+        assert!(ClientHints::new(Some(&headers)).is_err());
+        assert!(ClientHints::new(None).is_err());
+    }
+}
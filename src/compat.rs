@@ -0,0 +1,397 @@
+use pyo3::{
+    prelude::*,
+    types::{PyBytes, PyDict, PyString, PyTuple},
+};
+
+use crate::profile::{IMPERSONATE_ALIASES, IMPERSONATE_LIST};
+use crate::Client;
+
+/// Client arguments, a request with any of them is sent with a separate client.
+const CLIENT_KWARGS: [&str; 6] = [
+    "impersonate",
+    "proxies",
+    "proxy",
+    "verify",
+    "allow_redirects",
+    "max_redirects",
+];
+
+/// Arguments of `Client.request()`, the other arguments of the module functions go to the client.
+const REQUEST_KWARGS: [&str; 11] = [
+    "params",
+    "headers",
+    "cookies",
+    "content",
+    "data",
+    "json",
+    "files",
+    "auth",
+    "auth_bearer",
+    "timeout",
+    "http_version",
+];
+
+/// curl_cffi style session: `Session(impersonate="chrome131")`, then `session.get(url)`.
+///
+/// The arguments are those of `primp.Client`, with the curl_cffi names `proxies` and
+/// `allow_redirects`, a CA bundle path as `verify`, a (connect, read) tuple as `timeout`
+/// and the curl_cffi impersonation names.
+#[pyclass(module = "primp.requests")]
+pub struct Session {
+    #[pyo3(get)]
+    client: Py<Client>,
+    config: Py<PyDict>,
+}
+
+#[pymethods]
+impl Session {
+    #[new]
+    #[pyo3(signature = (**kwargs))]
+    fn new(py: Python, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let config = match kwargs {
+            Some(kwargs) => kwargs.copy()?,
+            None => PyDict::new(py),
+        };
+        client_kwargs(&config)?;
+        let client = py
+            .get_type::<Client>()
+            .call((), Some(&config))?
+            .downcast_into::<Client>()?
+            .unbind();
+        Ok(Session {
+            client,
+            config: config.unbind(),
+        })
+    }
+
+    /// Send a request, the arguments are those of `Client.request()` and the curl_cffi ones.
+    #[pyo3(signature = (method, url, **kwargs))]
+    fn request<'py>(
+        &self,
+        py: Python<'py>,
+        method: &str,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        send(py, Some(self), method, url, kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn get<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        send(py, Some(self), "GET", url, kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn head<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        send(py, Some(self), "HEAD", url, kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn options<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        send(py, Some(self), "OPTIONS", url, kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn delete<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        send(py, Some(self), "DELETE", url, kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn post<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        send(py, Some(self), "POST", url, kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn put<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        send(py, Some(self), "PUT", url, kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn patch<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        send(py, Some(self), "PATCH", url, kwargs)
+    }
+
+    fn close(&self) {}
+
+    fn __enter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, _args: &Bound<'_, PyTuple>) {}
+}
+
+/// Send a request with the session client, or with a new client if client arguments are given.
+fn send<'py>(
+    py: Python<'py>,
+    session: Option<&Session>,
+    method: &str,
+    url: &str,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let kwargs = match kwargs {
+        Some(kwargs) => kwargs.copy()?,
+        None => PyDict::new(py),
+    };
+    let overrides = PyDict::new(py);
+    for (name, value) in kwargs.copy()?.iter() {
+        let name: String = name.extract()?;
+        let client_kwarg = match session {
+            Some(_) => CLIENT_KWARGS.contains(&name.as_str()),
+            None => !REQUEST_KWARGS.contains(&name.as_str()),
+        };
+        if client_kwarg {
+            kwargs.del_item(&name)?;
+            if !value.is_none() {
+                overrides.set_item(name, value)?;
+            }
+        }
+    }
+    if let Some(data) = kwargs.get_item("data")? {
+        if data.is_instance_of::<PyString>() || data.is_instance_of::<PyBytes>() {
+            kwargs.del_item("data")?;
+            let content = match data.extract::<String>() {
+                Ok(text) => text.into_bytes(),
+                Err(_) => data.downcast::<PyBytes>()?.as_bytes().to_vec(),
+            };
+            kwargs.set_item("content", PyBytes::new(py, &content))?;
+        }
+    }
+    total_timeout(&kwargs)?;
+
+    let client = match session {
+        Some(session) if overrides.is_empty() => session.client.bind(py).clone().into_any(),
+        _ => {
+            let config = match session {
+                Some(session) => session.config.bind(py).copy()?,
+                None => PyDict::new(py),
+            };
+            client_kwargs(&overrides)?;
+            config.update(overrides.as_mapping())?;
+            py.get_type::<Client>().call((), Some(&config))?
+        }
+    };
+    client.call_method("request", (method, url), Some(&kwargs))
+}
+
+/// Translate the curl_cffi client arguments to the `primp.Client` ones, in place.
+fn client_kwargs(kwargs: &Bound<'_, PyDict>) -> PyResult<()> {
+    if let Some(proxies) = kwargs.get_item("proxies")? {
+        kwargs.del_item("proxies")?;
+        if !proxies.is_none() {
+            let proxies = proxies.downcast::<PyDict>()?;
+            let mut proxy = None;
+            for scheme in ["https", "all", "http"] {
+                if let Some(value) = proxies.get_item(scheme)? {
+                    proxy = Some(value);
+                    break;
+                }
+            }
+            kwargs.set_item("proxy", proxy)?;
+        }
+    }
+    if let Some(allow_redirects) = kwargs.get_item("allow_redirects")? {
+        kwargs.del_item("allow_redirects")?;
+        kwargs.set_item("follow_redirects", allow_redirects)?;
+    }
+    if let Some(verify) = kwargs.get_item("verify")? {
+        if verify.is_instance_of::<PyString>() {
+            kwargs.set_item("ca_cert_file", verify)?;
+            kwargs.set_item("verify", true)?;
+        }
+    }
+    if let Some(impersonate) = kwargs.get_item("impersonate")? {
+        if let Ok(name) = impersonate.extract::<String>() {
+            kwargs.set_item("impersonate", impersonate_name(&name))?;
+        }
+    }
+    total_timeout(kwargs)
+}
+
+/// Replace a (connect, read) `timeout` tuple with the total in seconds.
+fn total_timeout(kwargs: &Bound<'_, PyDict>) -> PyResult<()> {
+    if let Some(timeout) = kwargs.get_item("timeout")? {
+        if let Ok(timeouts) = timeout.downcast::<PyTuple>() {
+            let timeouts: Vec<Option<f64>> = timeouts.extract()?;
+            let total = timeouts.into_iter().flatten().reduce(|a, b| a + b);
+            kwargs.set_item("timeout", total)?;
+        }
+    }
+    Ok(())
+}
+
+/// primp name of a curl_cffi impersonation, e.g. "chrome131" -> "chrome_131" and
+/// "safari17_0" -> "safari_17.0". Unknown names are returned as is.
+fn impersonate_name(name: &str) -> String {
+    let known = |name: &str| {
+        IMPERSONATE_LIST.contains(&name) || IMPERSONATE_ALIASES.iter().any(|(a, _)| *a == name)
+    };
+    if known(name) {
+        return name.to_string();
+    }
+    let Some(index) = name.find(|c: char| c.is_ascii_digit()) else {
+        return name.to_string();
+    };
+    let (family, version) = name.split_at(index);
+    let candidate = format!(
+        "{}_{}",
+        family.trim_end_matches('_'),
+        version.replace('_', ".")
+    );
+    if known(&candidate) {
+        candidate
+    } else {
+        name.to_string()
+    }
+}
+
+/// Send a request with a new client, the arguments are those of `Session()` and `Session.request()`.
+#[pyfunction]
+#[pyo3(signature = (method, url, **kwargs))]
+fn request<'py>(
+    py: Python<'py>,
+    method: &str,
+    url: &str,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    send(py, None, method, url, kwargs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (url, **kwargs))]
+fn get<'py>(
+    py: Python<'py>,
+    url: &str,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    send(py, None, "GET", url, kwargs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (url, **kwargs))]
+fn head<'py>(
+    py: Python<'py>,
+    url: &str,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    send(py, None, "HEAD", url, kwargs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (url, **kwargs))]
+fn options<'py>(
+    py: Python<'py>,
+    url: &str,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    send(py, None, "OPTIONS", url, kwargs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (url, **kwargs))]
+fn delete<'py>(
+    py: Python<'py>,
+    url: &str,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    send(py, None, "DELETE", url, kwargs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (url, **kwargs))]
+fn post<'py>(
+    py: Python<'py>,
+    url: &str,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    send(py, None, "POST", url, kwargs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (url, **kwargs))]
+fn put<'py>(
+    py: Python<'py>,
+    url: &str,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    send(py, None, "PUT", url, kwargs)
+}
+
+#[pyfunction]
+#[pyo3(signature = (url, **kwargs))]
+fn patch<'py>(
+    py: Python<'py>,
+    url: &str,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    send(py, None, "PATCH", url, kwargs)
+}
+
+/// Add the `primp.requests` submodule.
+pub fn register_compat(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    let requests = PyModule::new(py, "requests")?;
+    requests.add_class::<Session>()?;
+    requests.add_function(wrap_pyfunction!(request, &requests)?)?;
+    requests.add_function(wrap_pyfunction!(get, &requests)?)?;
+    requests.add_function(wrap_pyfunction!(head, &requests)?)?;
+    requests.add_function(wrap_pyfunction!(options, &requests)?)?;
+    requests.add_function(wrap_pyfunction!(delete, &requests)?)?;
+    requests.add_function(wrap_pyfunction!(post, &requests)?)?;
+    requests.add_function(wrap_pyfunction!(put, &requests)?)?;
+    requests.add_function(wrap_pyfunction!(patch, &requests)?)?;
+    m.add_submodule(&requests)?;
+    py.import("sys")?
+        .getattr("modules")?
+        .set_item("primp.requests", requests)
+}
+
+#[cfg(test)]
+mod compat_tests {
+    use super::*;
+
+    #[test]
+    fn test_impersonate_name() {
+        assert_eq!(impersonate_name("chrome131"), "chrome_131");
+        assert_eq!(impersonate_name("safari17_0"), "safari_17.0");
+        assert_eq!(impersonate_name("edge101"), "edge_101");
+        assert_eq!(impersonate_name("chrome"), "chrome");
+        assert_eq!(impersonate_name("firefox_133"), "firefox_133");
+        assert_eq!(impersonate_name("chrome99_android"), "chrome99_android");
+    }
+}
@@ -0,0 +1,104 @@
+use std::error::Error as StdError;
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
+use rquest::dns::{Addrs, Name, Resolve, Resolving};
+
+/// IP address family used for outgoing connections.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IpVersion {
+    /// Use every address returned by the resolver (happy eyeballs between families).
+    #[default]
+    Auto,
+    /// Connect over IPv4 only.
+    Ipv4,
+    /// Connect over IPv6 only.
+    Ipv6,
+}
+
+impl FromStr for IpVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(IpVersion::Auto),
+            "ipv4" => Ok(IpVersion::Ipv4),
+            "ipv6" => Ok(IpVersion::Ipv6),
+            _ => Err(anyhow!(
+                "Invalid ip_version: {s}. Expected one of: auto, ipv4, ipv6"
+            )),
+        }
+    }
+}
+
+impl IpVersion {
+    /// Keep only the addresses that match the selected family, preserving resolver order.
+    pub fn filter_addrs<I: IntoIterator<Item = SocketAddr>>(&self, addrs: I) -> Vec<SocketAddr> {
+        addrs
+            .into_iter()
+            .filter(|addr| match self {
+                IpVersion::Auto => true,
+                IpVersion::Ipv4 => addr.is_ipv4(),
+                IpVersion::Ipv6 => addr.is_ipv6(),
+            })
+            .collect()
+    }
+}
+
+/// System resolver (`getaddrinfo`) that returns only addresses of the configured IP family.
+/// With `debug`, the resolved addresses are logged.
+#[derive(Clone, Debug)]
+pub struct FamilyResolver {
+    ip_version: IpVersion,
+    debug: bool,
+}
+
+impl FamilyResolver {
+    pub fn new(ip_version: IpVersion, debug: bool) -> Self {
+        FamilyResolver { ip_version, debug }
+    }
+}
+
+impl Resolve for FamilyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let (ip_version, debug) = (self.ip_version, self.debug);
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let resolved = tokio::net::lookup_host((host.as_str(), 0)).await?;
+            let addrs = ip_version.filter_addrs(resolved);
+            if addrs.is_empty() {
+                let err: Box<dyn StdError + Send + Sync> =
+                    format!("No {ip_version:?} addresses found for {host}").into();
+                return Err(err);
+            }
+            if debug {
+                log::debug!(target: crate::wire::TARGET, "* Resolved {host} to {addrs:?}");
+            }
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod dns_tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_version_from_str() {
+        assert_eq!(IpVersion::from_str("auto").unwrap(), IpVersion::Auto);
+        assert_eq!(IpVersion::from_str("IPv4").unwrap(), IpVersion::Ipv4);
+        assert_eq!(IpVersion::from_str("ipv6").unwrap(), IpVersion::Ipv6);
+        assert!(IpVersion::from_str("ipv5").is_err());
+    }
+
+    #[test]
+    fn test_ip_version_filter_addrs() {
+        let v4: SocketAddr = "192.0.2.1:0".parse().unwrap();
+        let v6: SocketAddr = "[2001:db8::1]:0".parse().unwrap();
+        assert_eq!(IpVersion::Auto.filter_addrs([v6, v4]), vec![v6, v4]);
+        assert_eq!(IpVersion::Ipv4.filter_addrs([v6, v4]), vec![v4]);
+        assert_eq!(IpVersion::Ipv6.filter_addrs([v6, v4]), vec![v6]);
+    }
+}
@@ -0,0 +1,176 @@
+use std::fmt;
+
+use anyhow::Error;
+use pyo3::{
+    create_exception,
+    exceptions::{PyException, PyOSError},
+    prelude::*,
+};
+
+create_exception!(
+    primp,
+    PrimpError,
+    PyException,
+    "Base class of the exceptions raised by primp."
+);
+create_exception!(
+    primp,
+    HTTPError,
+    PrimpError,
+    "Response with a 4xx or 5xx status code, raised by `Response.raise_for_status()`."
+);
+create_exception!(
+    primp,
+    InvalidRequest,
+    PrimpError,
+    "Invalid request parameters, e.g. the URL, method or headers."
+);
+create_exception!(
+    primp,
+    RequestError,
+    PrimpError,
+    "The request could not be sent or its response could not be read."
+);
+create_exception!(
+    primp,
+    ConnectError,
+    RequestError,
+    "Failed to connect to the server or the proxy."
+);
+create_exception!(
+    primp,
+    SSLError,
+    ConnectError,
+    "TLS handshake or certificate verification failed."
+);
+create_exception!(primp, Timeout, RequestError, "The request timed out.");
+create_exception!(
+    primp,
+    ConnectTimeout,
+    Timeout,
+    "Timed out while connecting."
+);
+create_exception!(
+    primp,
+    ReadTimeout,
+    Timeout,
+    "Timed out waiting for or reading the response."
+);
+create_exception!(
+    primp,
+    TooManyRedirects,
+    RequestError,
+    "Exceeded `max_redirects`."
+);
+create_exception!(
+    primp,
+    BodyError,
+    RequestError,
+    "Failed to read or decode the response body."
+);
+
+/// Add the exception classes to the module.
+pub fn register_exceptions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("PrimpError", py.get_type::<PrimpError>())?;
+    m.add("HTTPError", py.get_type::<HTTPError>())?;
+    m.add("InvalidRequest", py.get_type::<InvalidRequest>())?;
+    m.add("RequestError", py.get_type::<RequestError>())?;
+    m.add("ConnectError", py.get_type::<ConnectError>())?;
+    m.add("SSLError", py.get_type::<SSLError>())?;
+    m.add("Timeout", py.get_type::<Timeout>())?;
+    m.add("ConnectTimeout", py.get_type::<ConnectTimeout>())?;
+    m.add("ReadTimeout", py.get_type::<ReadTimeout>())?;
+    m.add("TooManyRedirects", py.get_type::<TooManyRedirects>())?;
+    m.add("BodyError", py.get_type::<BodyError>())?;
+    Ok(())
+}
+
+/// Request details attached as context to the errors of a request future.
+#[derive(Clone, Debug)]
+pub struct RequestContext {
+    pub request_id: u64,
+    pub method: String,
+    pub url: String,
+    pub timeout: Option<f64>,
+    pub attempt: u32,
+}
+
+impl fmt::Display for RequestContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.url)
+    }
+}
+
+/// Convert an error of a request into the matching primp exception, with the `request_id`, `method`,
+/// `url`, `timeout`, `attempt`, `errno` and `os_error` attributes (None when unknown).
+/// Python exceptions (e.g. raised by a callback) are kept as they are.
+pub fn request_error(py: Python, error: Error) -> PyErr {
+    let context = error.downcast_ref::<RequestContext>().cloned();
+    let url = error
+        .downcast_ref::<rquest::Error>()
+        .and_then(|err| err.url())
+        .map(|url| url.to_string());
+    let io_error = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .map(|e| (e.raw_os_error(), e.to_string()));
+    let err = match error.downcast::<PyErr>() {
+        Ok(err) if !err.is_instance_of::<PrimpError>(py) => return err,
+        Ok(err) => err,
+        Err(error) => exception(error),
+    };
+
+    let url = context.as_ref().map(|c| c.url.clone()).or(url);
+    let errno = io_error.as_ref().and_then(|(errno, _)| *errno);
+    let os_error = io_error.map(|(errno, message)| match errno {
+        Some(errno) => PyOSError::new_err((errno, message)).into_value(py),
+        None => PyOSError::new_err(message).into_value(py),
+    });
+    let value = err.value(py);
+    let result = (|| -> PyResult<()> {
+        value.setattr("request_id", context.as_ref().map(|c| c.request_id))?;
+        value.setattr("method", context.as_ref().map(|c| c.method.clone()))?;
+        value.setattr("url", url)?;
+        value.setattr("timeout", context.as_ref().and_then(|c| c.timeout))?;
+        value.setattr("attempt", context.as_ref().map(|c| c.attempt))?;
+        value.setattr("errno", errno)?;
+        value.setattr("os_error", os_error)
+    })();
+    if let Err(e) = result {
+        log::warn!("Failed to set the exception attributes: {e}");
+    }
+    err
+}
+
+/// The primp exception matching the kind of error.
+fn exception(error: Error) -> PyErr {
+    let message = format!("{error:#}");
+    let Some(err) = error.downcast_ref::<rquest::Error>() else {
+        return RequestError::new_err(message);
+    };
+    if err.is_builder() {
+        InvalidRequest::new_err(message)
+    } else if err.is_timeout() && err.is_connect() {
+        ConnectTimeout::new_err(message)
+    } else if err.is_timeout() {
+        ReadTimeout::new_err(message)
+    } else if err.is_redirect() {
+        TooManyRedirects::new_err(message)
+    } else if is_ssl_error(&error) {
+        SSLError::new_err(message)
+    } else if err.is_connect() {
+        ConnectError::new_err(message)
+    } else if err.is_body() || err.is_decode() {
+        BodyError::new_err(message)
+    } else {
+        RequestError::new_err(message)
+    }
+}
+
+/// Whether a BoringSSL error caused the error.
+fn is_ssl_error(error: &Error) -> bool {
+    error.chain().any(|cause| {
+        cause.is::<rquest::boring::ssl::Error>() || cause.is::<rquest::boring::error::ErrorStack>()
+    })
+}
@@ -0,0 +1,1188 @@
+use anyhow::{anyhow, Result};
+use bytes::BytesMut;
+use rquest::boring::{sha::sha256, ssl::SslCurve};
+use rquest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT_ENCODING, HOST};
+use rquest::mimic::ImpersonateSettings;
+use rquest::{
+    http2::Http2Settings, AlpnProtos, AlpsProto, Priority, PseudoOrder, SettingsOrder,
+    StreamDependency, StreamId, TlsSettings, TlsVersion,
+};
+use serde_json::Value;
+
+use crate::utils::{http2_settings_mut, impersonate_settings_mut};
+
+/// TLS options parsed from a JA3 fingerprint, named as the `Client` arguments expect them.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Ja3 {
+    pub tls_ciphers: Vec<String>,
+    pub tls_curves: Vec<String>,
+}
+
+/// GREASE values (RFC 8701) are random per connection and not part of the fingerprint.
+fn is_grease(value: u16) -> bool {
+    value & 0x0f0f == 0x0a0a && value >> 8 == value & 0xff
+}
+
+/// BoringSSL name of a cipher suite by its IANA id.
+fn cipher_name(id: u16) -> Option<&'static str> {
+    Some(match id {
+        0x1301 => "TLS_AES_128_GCM_SHA256",
+        0x1302 => "TLS_AES_256_GCM_SHA384",
+        0x1303 => "TLS_CHACHA20_POLY1305_SHA256",
+        0xc02b => "ECDHE-ECDSA-AES128-GCM-SHA256",
+        0xc02f => "ECDHE-RSA-AES128-GCM-SHA256",
+        0xc02c => "ECDHE-ECDSA-AES256-GCM-SHA384",
+        0xc030 => "ECDHE-RSA-AES256-GCM-SHA384",
+        0xcca9 => "ECDHE-ECDSA-CHACHA20-POLY1305",
+        0xcca8 => "ECDHE-RSA-CHACHA20-POLY1305",
+        0xc009 => "ECDHE-ECDSA-AES128-SHA",
+        0xc00a => "ECDHE-ECDSA-AES256-SHA",
+        0xc013 => "ECDHE-RSA-AES128-SHA",
+        0xc014 => "ECDHE-RSA-AES256-SHA",
+        0xc023 => "ECDHE-ECDSA-AES128-SHA256",
+        0xc024 => "ECDHE-ECDSA-AES256-SHA384",
+        0xc027 => "ECDHE-RSA-AES128-SHA256",
+        0xc028 => "ECDHE-RSA-AES256-SHA384",
+        0xc008 => "ECDHE-ECDSA-DES-CBC3-SHA",
+        0xc012 => "ECDHE-RSA-DES-CBC3-SHA",
+        0x009c => "AES128-GCM-SHA256",
+        0x009d => "AES256-GCM-SHA384",
+        0x002f => "AES128-SHA",
+        0x0035 => "AES256-SHA",
+        0x003c => "AES128-SHA256",
+        0x003d => "AES256-SHA256",
+        0x000a => "DES-CBC3-SHA",
+        _ => return None,
+    })
+}
+
+/// Curve name (as accepted by `tls_curves`) of a named group by its IANA id.
+fn curve_name(id: u16) -> Option<&'static str> {
+    Some(match id {
+        29 => "X25519",
+        23 => "P-256",
+        24 => "P-384",
+        25 => "P-521",
+        256 => "ffdhe2048",
+        257 => "ffdhe3072",
+        4588 => "X25519MLKEM768",
+        25497 => "X25519Kyber768Draft00",
+        _ => return None,
+    })
+}
+
+/// Parse a dash separated list of ids, skipping GREASE values.
+fn parse_ids(field: &str, name: &str) -> Result<Vec<u16>> {
+    field
+        .split('-')
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            id.parse::<u16>()
+                .map_err(|_| anyhow!("Invalid JA3 {name}: {id}"))
+        })
+        .filter(|id| !matches!(id, Ok(id) if is_grease(*id)))
+        .collect()
+}
+
+/// Parse a JA3 text fingerprint: `TLSVersion,Ciphers,Extensions,EllipticCurves,EllipticCurvePointFormats`.
+///
+/// Ciphers and curves are mapped to BoringSSL names. The extension list is validated
+/// but not reproduced: BoringSSL picks the extensions from the other settings.
+pub fn parse_ja3(ja3: &str) -> Result<Ja3> {
+    let fields: Vec<&str> = ja3.trim().split(',').collect();
+    let [version, ciphers, extensions, curves, _point_formats] = fields[..] else {
+        return Err(anyhow!(
+            "Invalid JA3: expected 5 comma separated fields, got {}",
+            fields.len()
+        ));
+    };
+    if version != "771" {
+        return Err(anyhow!(
+            "Unsupported JA3 TLS version: {version}. Only 771 (TLS 1.2 record version) is supported"
+        ));
+    }
+    parse_ids(extensions, "extension")?;
+
+    let tls_ciphers = parse_ids(ciphers, "cipher")?
+        .into_iter()
+        .map(|id| {
+            cipher_name(id)
+                .map(String::from)
+                .ok_or_else(|| anyhow!("Unsupported JA3 cipher: {id}"))
+        })
+        .collect::<Result<Vec<String>>>()?;
+    let tls_curves = parse_ids(curves, "curve")?
+        .into_iter()
+        .map(|id| {
+            curve_name(id)
+                .map(String::from)
+                .ok_or_else(|| anyhow!("Unsupported JA3 curve: {id}"))
+        })
+        .collect::<Result<Vec<String>>>()?;
+
+    Ok(Ja3 {
+        tls_ciphers,
+        tls_curves,
+    })
+}
+
+/// BoringSSL name of a signature algorithm by its IANA id.
+fn sigalg_name(id: u16) -> Option<&'static str> {
+    Some(match id {
+        0x0403 => "ecdsa_secp256r1_sha256",
+        0x0503 => "ecdsa_secp384r1_sha384",
+        0x0603 => "ecdsa_secp521r1_sha512",
+        0x0203 => "ecdsa_sha1",
+        0x0804 => "rsa_pss_rsae_sha256",
+        0x0805 => "rsa_pss_rsae_sha384",
+        0x0806 => "rsa_pss_rsae_sha512",
+        0x0401 => "rsa_pkcs1_sha256",
+        0x0501 => "rsa_pkcs1_sha384",
+        0x0601 => "rsa_pkcs1_sha512",
+        0x0201 => "rsa_pkcs1_sha1",
+        0x0807 => "ed25519",
+        _ => return None,
+    })
+}
+
+/// TLS settings read from a captured ClientHello, named as the profile `tls` keys expect them.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ClientHello {
+    pub ciphers: Vec<String>,
+    pub sigalgs: Vec<String>,
+    pub curves: Vec<String>,
+    pub alpn: Vec<String>,
+    pub cert_compression: Vec<String>,
+    pub ech_grease: bool,
+}
+
+/// Big-endian reader over the ClientHello bytes.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(anyhow!("Invalid ClientHello: truncated"));
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn vec8(&mut self) -> Result<Reader<'a>> {
+        let len = self.u8()? as usize;
+        Ok(Reader(self.take(len)?))
+    }
+
+    fn vec16(&mut self) -> Result<Reader<'a>> {
+        let len = self.u16()? as usize;
+        Ok(Reader(self.take(len)?))
+    }
+
+    /// The remaining bytes as a list of u16 ids, skipping GREASE values.
+    fn ids(mut self) -> Result<Vec<u16>> {
+        let mut ids = Vec::new();
+        while !self.0.is_empty() {
+            let id = self.u16()?;
+            if !is_grease(id) {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+}
+
+fn names(ids: Vec<u16>, name: fn(u16) -> Option<&'static str>, kind: &str) -> Result<Vec<String>> {
+    ids.into_iter()
+        .map(|id| {
+            name(id)
+                .map(String::from)
+                .ok_or_else(|| anyhow!("Unsupported ClientHello {kind}: {id:#06x}"))
+        })
+        .collect()
+}
+
+/// Parse a captured ClientHello, either the TLS record or the bare handshake message
+/// (e.g. copied from Wireshark as raw bytes).
+///
+/// The extension order is not reproduced: BoringSSL permutation indices don't map to extension ids.
+pub fn parse_client_hello(data: &[u8]) -> Result<ClientHello> {
+    let mut reader = Reader(data);
+    if data.first() == Some(&0x16) {
+        // Record header: content type, legacy version, length
+        reader.take(3)?;
+        reader = reader.vec16()?;
+    }
+    if reader.u8()? != 0x01 {
+        return Err(anyhow!(
+            "Invalid ClientHello: not a ClientHello handshake message"
+        ));
+    }
+    let len = reader.take(3)?;
+    let mut reader = Reader(reader.take(u32::from_be_bytes([0, len[0], len[1], len[2]]) as usize)?);
+    // Legacy version, random, session id
+    reader.take(2 + 32)?;
+    reader.vec8()?;
+
+    let mut client_hello = ClientHello {
+        ciphers: names(reader.vec16()?.ids()?, cipher_name, "cipher")?,
+        ..Default::default()
+    };
+    reader.vec8()?;
+
+    let mut extensions = if reader.0.is_empty() {
+        Reader(&[])
+    } else {
+        reader.vec16()?
+    };
+    while !extensions.0.is_empty() {
+        let extension = extensions.u16()?;
+        let mut data = extensions.vec16()?;
+        match extension {
+            10 => client_hello.curves = names(data.vec16()?.ids()?, curve_name, "curve")?,
+            13 => client_hello.sigalgs = names(data.vec16()?.ids()?, sigalg_name, "sigalg")?,
+            16 => {
+                let mut protocols = data.vec16()?;
+                while !protocols.0.is_empty() {
+                    let protocol = protocols.vec8()?;
+                    client_hello
+                        .alpn
+                        .push(String::from_utf8_lossy(protocol.0).into_owned());
+                }
+            }
+            27 => {
+                let mut algorithms = data.vec8()?.ids()?;
+                algorithms.retain(|&id| (1..=3).contains(&id));
+                client_hello.cert_compression = algorithms
+                    .into_iter()
+                    .map(|id| ["zlib", "brotli", "zstd"][id as usize - 1].to_string())
+                    .collect();
+            }
+            0xfe0d => client_hello.ech_grease = true,
+            _ => {}
+        }
+    }
+    Ok(client_hello)
+}
+
+/// IANA name of a cipher suite by its id, as in the cipher lists of the impersonations.
+fn iana_cipher_name(id: u16) -> Option<&'static str> {
+    Some(match id {
+        0x1301 => "TLS_AES_128_GCM_SHA256",
+        0x1302 => "TLS_AES_256_GCM_SHA384",
+        0x1303 => "TLS_CHACHA20_POLY1305_SHA256",
+        0xc02b => "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256",
+        0xc02f => "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+        0xc02c => "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384",
+        0xc030 => "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384",
+        0xcca9 => "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256",
+        0xcca8 => "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256",
+        0xc009 => "TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA",
+        0xc00a => "TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA",
+        0xc013 => "TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA",
+        0xc014 => "TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA",
+        0xc023 => "TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA256",
+        0xc024 => "TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA384",
+        0xc027 => "TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA256",
+        0xc028 => "TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA384",
+        0xc008 => "TLS_ECDHE_ECDSA_WITH_3DES_EDE_CBC_SHA",
+        0xc012 => "TLS_ECDHE_RSA_WITH_3DES_EDE_CBC_SHA",
+        0x009c => "TLS_RSA_WITH_AES_128_GCM_SHA256",
+        0x009d => "TLS_RSA_WITH_AES_256_GCM_SHA384",
+        0x002f => "TLS_RSA_WITH_AES_128_CBC_SHA",
+        0x0035 => "TLS_RSA_WITH_AES_256_CBC_SHA",
+        0x003c => "TLS_RSA_WITH_AES_128_CBC_SHA256",
+        0x003d => "TLS_RSA_WITH_AES_256_CBC_SHA256",
+        0x000a => "TLS_RSA_WITH_3DES_EDE_CBC_SHA",
+        _ => return None,
+    })
+}
+
+/// Ids of the cipher suites named by `cipher_name` and `iana_cipher_name`.
+const CIPHER_IDS: [u16; 26] = [
+    0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f, 0xc02c, 0xc030, 0xcca9, 0xcca8, 0xc009, 0xc00a, 0xc013,
+    0xc014, 0xc023, 0xc024, 0xc027, 0xc028, 0xc008, 0xc012, 0x009c, 0x009d, 0x002f, 0x0035, 0x003c,
+    0x003d, 0x000a,
+];
+
+/// Ids of the signature algorithms named by `sigalg_name`.
+const SIGALG_IDS: [u16; 12] = [
+    0x0403, 0x0503, 0x0603, 0x0203, 0x0804, 0x0805, 0x0806, 0x0401, 0x0501, 0x0601, 0x0201, 0x0807,
+];
+
+/// Ids of a BoringSSL colon separated list of names, None when a name is unknown.
+fn list_ids(
+    list: &str,
+    ids: &[u16],
+    names: &[fn(u16) -> Option<&'static str>],
+) -> Option<Vec<u16>> {
+    list.split(':')
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            ids.iter()
+                .copied()
+                .find(|&id| names.iter().any(|name_of| name_of(id) == Some(name)))
+        })
+        .collect()
+}
+
+/// Comma separated 4 digit hex ids, as hashed in JA4.
+fn hex_ids(ids: &[u16]) -> String {
+    ids.iter()
+        .map(|id| format!("{id:04x}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Truncated SHA-256 (12 hex digits) of a JA4 list, zeros for an empty list.
+fn ja4_hash(list: &str) -> String {
+    if list.is_empty() {
+        return "000000000000".to_string();
+    }
+    sha256(list.as_bytes())[..6]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// JA4 fingerprint of the first ClientHello sent with the TLS settings to a domain name.
+///
+/// The extensions are the ones BoringSSL sends for the settings, without `pre_shared_key` that
+/// is only sent when resuming a session. JA4 sorts them, so `permute_extensions` doesn't change it.
+/// None when the settings don't fix the ClientHello: default or unknown cipher and signature
+/// algorithm lists, or no post-quantum key share first, as BoringSSL then pads a ClientHello
+/// of 256-511 bytes, a size that depends on the host.
+pub fn ja4(tls: &TlsSettings) -> Option<String> {
+    let tls13 = tls
+        .max_tls_version
+        .is_none_or(|version| version == TlsVersion::TLS_1_3);
+    let post_quantum = tls
+        .curves
+        .as_deref()
+        .and_then(<[SslCurve]>::first)
+        .is_some_and(|curve| {
+            *curve == SslCurve::X25519_MLKEM768 || *curve == SslCurve::X25519_KYBER768_DRAFT00
+        });
+    if !tls13 || !post_quantum {
+        return None;
+    }
+
+    let mut ciphers = list_ids(
+        tls.cipher_list.as_deref()?,
+        &CIPHER_IDS,
+        &[cipher_name, iana_cipher_name],
+    )?;
+    // BoringSSL always offers the TLS 1.3 cipher suites
+    for id in [0x1301, 0x1302, 0x1303] {
+        if !ciphers.contains(&id) {
+            ciphers.push(id);
+        }
+    }
+    ciphers.sort_unstable();
+    let sigalgs = list_ids(tls.sigalgs_list.as_deref()?, &SIGALG_IDS, &[sigalg_name])?;
+
+    let alpn: &[&str] = if tls.alpn_protos == AlpnProtos::Http1 {
+        &["http/1.1"]
+    } else if tls.alpn_protos == AlpnProtos::Http2 {
+        &["h2"]
+    } else {
+        &["h2", "http/1.1"]
+    };
+    let alps = tls.alps_proto.is_some_and(|alps| {
+        (alps == AlpsProto::Http2 && alpn.contains(&"h2"))
+            || (alps == AlpsProto::Http1 && alpn.contains(&"http/1.1"))
+    });
+    // Extensions of TLS 1.2 and below, unnecessary when TLS 1.3 is the minimum
+    let legacy = tls.min_tls_version != Some(TlsVersion::TLS_1_3);
+    let extensions: Vec<u16> = [
+        (0x0000, tls.tls_sni),
+        (0x0005, tls.enable_ocsp_stapling),
+        (0x000a, true),
+        (0x000b, legacy),
+        (0x000d, true),
+        (0x0010, true),
+        (0x0012, tls.enable_signed_cert_timestamps),
+        (0x0017, legacy),
+        (
+            0x001b,
+            tls.cert_compression_algorithm
+                .as_deref()
+                .is_some_and(|algorithms| !algorithms.is_empty()),
+        ),
+        (0x001c, tls.record_size_limit.is_some()),
+        (0x0022, tls.delegated_credentials.is_some()),
+        (0x0023, legacy && tls.session_ticket),
+        (0x002b, true),
+        (0x002d, tls.psk_dhe_ke),
+        (0x0033, true),
+        (0x4469, alps),
+        (0xfe0d, tls.enable_ech_grease),
+        (0xff01, legacy),
+    ]
+    .into_iter()
+    .filter_map(|(id, sent)| sent.then_some(id))
+    .collect();
+    // SNI and ALPN are counted but not hashed
+    let hashed: Vec<u16> = extensions
+        .iter()
+        .copied()
+        .filter(|&id| id != 0x0000 && id != 0x0010)
+        .collect();
+
+    let first_alpn = alpn[0];
+    Some(format!(
+        "t13{}{:02}{:02}{}{}_{}_{}",
+        if tls.tls_sni { 'd' } else { 'i' },
+        ciphers.len().min(99),
+        extensions.len().min(99),
+        &first_alpn[..1],
+        &first_alpn[first_alpn.len() - 1..],
+        ja4_hash(&hex_ids(&ciphers)),
+        ja4_hash(&format!("{}_{}", hex_ids(&hashed), hex_ids(&sigalgs))),
+    ))
+}
+
+/// Headers of a GET request with the default `headers` of a client, as rquest and hyper send them:
+/// `Accept-Encoding` is added when missing, the headers are sorted in the impersonation header
+/// `order`, then over HTTP/1.1 `Host` is added last and the names are Title-Case with `title_case`.
+/// Cookies of the cookie store are not known without the URL.
+pub fn request_headers(
+    mut headers: HeaderMap,
+    order: Option<&[HeaderName]>,
+    http2: bool,
+    title_case: bool,
+) -> Vec<(String, HeaderValue)> {
+    if !headers.contains_key(ACCEPT_ENCODING) {
+        headers.insert(
+            ACCEPT_ENCODING,
+            HeaderValue::from_static("gzip, br, zstd, deflate"),
+        );
+    }
+    let mut sent: Vec<(HeaderName, HeaderValue)> = Vec::with_capacity(headers.len() + 1);
+    match order {
+        // Like rquest, a sorted header keeps only its first value
+        Some(order) => {
+            for name in order {
+                if let Some(value) = headers.remove(name) {
+                    sent.push((name.clone(), value));
+                }
+            }
+            sent.extend(
+                headers
+                    .drain()
+                    .filter_map(|(name, value)| name.map(|name| (name, value))),
+            );
+        }
+        None => sent.extend(
+            headers
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone())),
+        ),
+    }
+    if http2 {
+        return sent
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+    }
+    if !sent.iter().any(|(name, _)| name == HOST) {
+        sent.push((HOST, HeaderValue::from_static("")));
+    }
+    sent.into_iter()
+        .map(|(name, value)| {
+            let name = if title_case {
+                title_case_name(name.as_str())
+            } else {
+                name.to_string()
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+/// Header name in Title-Case, like hyper sends it: `user-agent` becomes `User-Agent`.
+fn title_case_name(name: &str) -> String {
+    name.split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or(String::new(), |first| {
+                first.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Values of the headers named `wanted` (case-insensitive) that are valid strings.
+fn header_values<'a>(
+    headers: &'a [(String, HeaderValue)],
+    wanted: &'a str,
+) -> impl Iterator<Item = &'a str> {
+    headers
+        .iter()
+        .filter(move |(name, _)| name.eq_ignore_ascii_case(wanted))
+        .filter_map(|(_, value)| value.to_str().ok())
+}
+
+/// JA4H fingerprint of a GET request with the `headers` (wire names, in order) over HTTP/2 or HTTP/1.1.
+pub fn ja4h(http2: bool, headers: &[(String, HeaderValue)]) -> String {
+    let names: Vec<&str> = headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .filter(|name| {
+            !name.eq_ignore_ascii_case("cookie") && !name.eq_ignore_ascii_case("referer")
+        })
+        .collect();
+    // First 4 characters of the first language, without dashes: "en-US,en;q=0.9" is "enus"
+    let language: String = header_values(headers, "accept-language")
+        .next()
+        .and_then(|value| value.split([',', ';']).next())
+        .map(|tag| {
+            tag.replace('-', "")
+                .to_ascii_lowercase()
+                .chars()
+                .take(4)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut cookies: Vec<&str> = header_values(headers, "cookie")
+        .flat_map(|value| value.split(';'))
+        .map(str::trim)
+        .filter(|cookie| !cookie.is_empty())
+        .collect();
+    cookies.sort_unstable();
+    let mut cookie_names: Vec<&str> = cookies
+        .iter()
+        .map(|cookie| cookie.split_once('=').map_or(*cookie, |(name, _)| name))
+        .collect();
+    cookie_names.sort_unstable();
+    let referer = header_values(headers, "referer").next().is_some();
+
+    format!(
+        "ge{}{}{}{:02}{language:0<4}_{}_{}_{}",
+        if http2 { "20" } else { "11" },
+        if cookies.is_empty() { 'n' } else { 'c' },
+        if referer { 'r' } else { 'n' },
+        names.len().min(99),
+        ja4_hash(&names.join(",")),
+        ja4_hash(&cookie_names.join(",")),
+        ja4_hash(&cookies.join(",")),
+    )
+}
+
+/// SETTINGS frame order starting with `order`, followed by the unlisted settings in the default order.
+fn complete_settings_order(mut order: Vec<SettingsOrder>) -> [SettingsOrder; 8] {
+    for setting in [
+        SettingsOrder::HeaderTableSize,
+        SettingsOrder::EnablePush,
+        SettingsOrder::InitialWindowSize,
+        SettingsOrder::MaxConcurrentStreams,
+        SettingsOrder::MaxFrameSize,
+        SettingsOrder::MaxHeaderListSize,
+        SettingsOrder::UnknownSetting8,
+        SettingsOrder::UnknownSetting9,
+    ] {
+        if !order.contains(&setting) {
+            order.push(setting);
+        }
+    }
+    order.try_into().unwrap()
+}
+
+/// `stream:exclusive:dependency:weight` of a PRIORITY frame, as in the Akamai fingerprint.
+fn priority_frame(priority: &Priority) -> String {
+    // The dependency is private, read it back from the encoded frame (9 bytes header + 5 bytes payload)
+    let mut frame = BytesMut::new();
+    priority.encode(&mut frame);
+    let dependency = u32::from_be_bytes([frame[9], frame[10], frame[11], frame[12]]);
+    let stream_id: u32 = priority.stream_id().into();
+    format!(
+        "{stream_id}:{}:{}:{}",
+        dependency >> 31,
+        dependency & 0x7fff_ffff,
+        u16::from(frame[13]) + 1
+    )
+}
+
+/// Akamai HTTP/2 fingerprint (`SETTINGS|WINDOW_UPDATE|PRIORITY|PSEUDO_HEADER_ORDER`) of the settings.
+///
+/// Settings left unset are reported as not sent, even though the HTTP/2 stack may send its own defaults.
+pub fn akamai_h2(settings: &Http2Settings) -> String {
+    let settings_order = settings.settings_order.unwrap_or([
+        SettingsOrder::HeaderTableSize,
+        SettingsOrder::EnablePush,
+        SettingsOrder::InitialWindowSize,
+        SettingsOrder::MaxConcurrentStreams,
+        SettingsOrder::MaxFrameSize,
+        SettingsOrder::MaxHeaderListSize,
+        SettingsOrder::UnknownSetting8,
+        SettingsOrder::UnknownSetting9,
+    ]);
+    let settings_frame = settings_order
+        .iter()
+        .filter_map(|setting| {
+            let (id, value) = match setting {
+                SettingsOrder::HeaderTableSize => (1, settings.header_table_size?),
+                SettingsOrder::EnablePush => (2, u32::from(settings.enable_push?)),
+                SettingsOrder::MaxConcurrentStreams => (3, settings.max_concurrent_streams?),
+                SettingsOrder::InitialWindowSize => (4, settings.initial_stream_window_size?),
+                SettingsOrder::MaxFrameSize => (5, settings.max_frame_size?),
+                SettingsOrder::MaxHeaderListSize => (6, settings.max_header_list_size?),
+                SettingsOrder::UnknownSetting8 => (8, u32::from(settings.unknown_setting8?)),
+                SettingsOrder::UnknownSetting9 => (9, u32::from(settings.unknown_setting9?)),
+            };
+            Some(format!("{id}:{value}"))
+        })
+        .collect::<Vec<_>>()
+        .join(";");
+
+    // The connection window starts at 65535, the WINDOW_UPDATE frame carries the increment
+    let window_update = settings
+        .initial_connection_window_size
+        .map(|size| size.saturating_sub(65535).to_string())
+        .unwrap_or_else(|| "00".to_string());
+
+    let priority = match settings.priority.as_deref() {
+        Some(frames) if !frames.is_empty() => frames
+            .iter()
+            .map(priority_frame)
+            .collect::<Vec<_>>()
+            .join(","),
+        _ => "0".to_string(),
+    };
+
+    let pseudo_order = settings
+        .headers_pseudo_order
+        .unwrap_or([
+            PseudoOrder::Method,
+            PseudoOrder::Scheme,
+            PseudoOrder::Authority,
+            PseudoOrder::Path,
+        ])
+        .iter()
+        .map(|pseudo| match pseudo {
+            PseudoOrder::Method => "m",
+            PseudoOrder::Authority => "a",
+            PseudoOrder::Scheme => "s",
+            PseudoOrder::Path => "p",
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!("{settings_frame}|{window_update}|{priority}|{pseudo_order}")
+}
+
+/// Parse an Akamai HTTP/2 fingerprint (`SETTINGS|WINDOW_UPDATE|PRIORITY|PSEUDO_HEADER_ORDER`)
+/// into HTTP/2 settings. Settings missing from the fingerprint are not sent.
+pub fn parse_akamai_h2(fingerprint: &str) -> Result<Http2Settings> {
+    let invalid = |part: &str| anyhow!("Invalid Akamai HTTP/2 fingerprint {part}: {fingerprint}");
+    let parts: Vec<&str> = fingerprint.trim().split('|').collect();
+    let [settings_frame, window_update, priority, pseudo_order] = parts[..] else {
+        return Err(invalid("(expected 4 parts separated by |)"));
+    };
+
+    let mut settings = Http2Settings::builder().build();
+
+    // Settings, appending the unlisted ones to complete the order
+    let mut settings_order = Vec::with_capacity(8);
+    for setting in settings_frame.split(';').filter(|s| !s.is_empty()) {
+        let (id, value) = setting
+            .split_once(':')
+            .and_then(|(id, value)| Some((id.parse::<u16>().ok()?, value.parse::<u32>().ok()?)))
+            .ok_or_else(|| invalid("setting"))?;
+        let order = match id {
+            1 => {
+                settings.header_table_size = Some(value);
+                SettingsOrder::HeaderTableSize
+            }
+            2 => {
+                settings.enable_push = Some(value != 0);
+                SettingsOrder::EnablePush
+            }
+            3 => {
+                settings.max_concurrent_streams = Some(value);
+                SettingsOrder::MaxConcurrentStreams
+            }
+            4 => {
+                settings.initial_stream_window_size = Some(value);
+                SettingsOrder::InitialWindowSize
+            }
+            5 => {
+                settings.max_frame_size = Some(value);
+                SettingsOrder::MaxFrameSize
+            }
+            6 => {
+                settings.max_header_list_size = Some(value);
+                SettingsOrder::MaxHeaderListSize
+            }
+            8 => {
+                settings.unknown_setting8 = Some(value != 0);
+                SettingsOrder::UnknownSetting8
+            }
+            9 => {
+                settings.unknown_setting9 = Some(value != 0);
+                SettingsOrder::UnknownSetting9
+            }
+            _ => return Err(invalid("setting id")),
+        };
+        if settings_order.contains(&order) {
+            return Err(invalid("duplicate setting"));
+        }
+        settings_order.push(order);
+    }
+    settings.settings_order = Some(complete_settings_order(settings_order));
+
+    // Window update: increment over the initial 65535 bytes connection window
+    settings.initial_connection_window_size = match window_update {
+        "" | "0" | "00" => None,
+        increment => Some(
+            increment
+                .parse::<u32>()
+                .ok()
+                .and_then(|increment| increment.checked_add(65535))
+                .ok_or_else(|| invalid("window update"))?,
+        ),
+    };
+
+    // Priority frames: stream:exclusive:dependency:weight
+    if priority != "0" && !priority.is_empty() {
+        let frames = priority
+            .split(',')
+            .map(|frame| {
+                let fields: Vec<u32> = frame
+                    .split(':')
+                    .map(|field| field.parse::<u32>())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| invalid("priority"))?;
+                let [stream_id, exclusive, dependency, weight] = fields[..] else {
+                    return Err(invalid("priority"));
+                };
+                if stream_id == 0 || !(1..=256).contains(&weight) {
+                    return Err(invalid("priority"));
+                }
+                Ok(Priority::new(
+                    StreamId::from(stream_id),
+                    StreamDependency::new(
+                        StreamId::from(dependency),
+                        (weight - 1) as u8,
+                        exclusive == 1,
+                    ),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        settings.priority = Some(frames.into());
+    }
+
+    // Pseudo-header order
+    let pseudo_order = pseudo_order
+        .split(',')
+        .map(|pseudo| match pseudo {
+            "m" => Ok(PseudoOrder::Method),
+            "a" => Ok(PseudoOrder::Authority),
+            "s" => Ok(PseudoOrder::Scheme),
+            "p" => Ok(PseudoOrder::Path),
+            _ => Err(invalid("pseudo-header order")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let pseudo_order: [PseudoOrder; 4] = pseudo_order
+        .try_into()
+        .map_err(|_| invalid("pseudo-header order"))?;
+    if (1..4).any(|i| pseudo_order[..i].contains(&pseudo_order[i])) {
+        return Err(invalid("pseudo-header order"));
+    }
+    settings.headers_pseudo_order = Some(pseudo_order);
+
+    Ok(settings)
+}
+
+/// Replace the HTTP/2 settings with the ones of an Akamai fingerprint,
+/// keeping the HEADERS frame priority that the fingerprint doesn't describe.
+pub fn set_akamai_h2(settings: &mut Option<ImpersonateSettings>, fingerprint: &str) -> Result<()> {
+    let mut http2 = parse_akamai_h2(fingerprint)?;
+    let settings = impersonate_settings_mut(settings);
+    if let Some(previous) = settings.http2.take() {
+        http2.initial_stream_id = previous.initial_stream_id;
+        http2.headers_priority = previous.headers_priority;
+    }
+    settings.http2 = Some(http2);
+    Ok(())
+}
+
+/// Override HTTP/2 settings with the values of a `{name: value}` mapping, `null` leaving a setting unsent.
+///
+/// `settings_order` lists SETTINGS identifiers (1-6, 8, 9) to send first, the others follow in the default order.
+pub fn set_http2_settings(
+    settings: &mut Option<ImpersonateSettings>,
+    values: &Value,
+) -> Result<()> {
+    let values = values
+        .as_object()
+        .ok_or_else(|| anyhow!("Invalid http2_settings: expected a dict"))?;
+    let http2 = http2_settings_mut(settings);
+    for (key, value) in values {
+        let number = || -> Result<Option<u32>> {
+            match value {
+                Value::Null => Ok(None),
+                _ => value
+                    .as_u64()
+                    .and_then(|n| u32::try_from(n).ok())
+                    .map(Some)
+                    .ok_or_else(|| anyhow!("Invalid http2_settings: {key} must be an integer")),
+            }
+        };
+        let flag = || -> Result<Option<bool>> {
+            match value {
+                Value::Null => Ok(None),
+                Value::Bool(b) => Ok(Some(*b)),
+                _ => Err(anyhow!("Invalid http2_settings: {key} must be a boolean")),
+            }
+        };
+        match key.as_str() {
+            "header_table_size" => http2.header_table_size = number()?,
+            "enable_push" => http2.enable_push = flag()?,
+            "max_concurrent_streams" => http2.max_concurrent_streams = number()?,
+            "initial_stream_window_size" => http2.initial_stream_window_size = number()?,
+            "max_frame_size" => http2.max_frame_size = number()?,
+            "max_header_list_size" => http2.max_header_list_size = number()?,
+            "unknown_setting8" => http2.unknown_setting8 = flag()?,
+            "unknown_setting9" => http2.unknown_setting9 = flag()?,
+            "initial_connection_window_size" => http2.initial_connection_window_size = number()?,
+            "initial_stream_id" => http2.initial_stream_id = number()?,
+            "settings_order" => {
+                let invalid = || {
+                    anyhow!("Invalid http2_settings: settings_order must be a list of setting ids")
+                };
+                let ids = match value {
+                    Value::Null => {
+                        http2.settings_order = None;
+                        continue;
+                    }
+                    Value::Array(ids) => ids,
+                    _ => return Err(invalid()),
+                };
+                let mut order = Vec::with_capacity(8);
+                for id in ids {
+                    let setting = match id.as_u64().ok_or_else(invalid)? {
+                        1 => SettingsOrder::HeaderTableSize,
+                        2 => SettingsOrder::EnablePush,
+                        3 => SettingsOrder::MaxConcurrentStreams,
+                        4 => SettingsOrder::InitialWindowSize,
+                        5 => SettingsOrder::MaxFrameSize,
+                        6 => SettingsOrder::MaxHeaderListSize,
+                        8 => SettingsOrder::UnknownSetting8,
+                        9 => SettingsOrder::UnknownSetting9,
+                        _ => return Err(invalid()),
+                    };
+                    if order.contains(&setting) {
+                        return Err(invalid());
+                    }
+                    order.push(setting);
+                }
+                http2.settings_order = Some(complete_settings_order(order));
+            }
+            _ => return Err(anyhow!("Invalid http2_settings: unknown key {key}")),
+        }
+    }
+    Ok(())
+}
+
+/// Encoded weight (weight - 1) of a stream dependency with a weight in [1, 256].
+fn priority_weight(weight: u16) -> Result<u8> {
+    weight
+        .checked_sub(1)
+        .and_then(|weight| u8::try_from(weight).ok())
+        .ok_or_else(|| anyhow!("Invalid HTTP/2 priority weight {weight}, expected [1, 256]"))
+}
+
+/// Replace the PRIORITY frames sent after the connection preface,
+/// given as `(stream_id, dependency, weight, exclusive)`.
+pub fn set_http2_priorities(
+    settings: &mut Option<ImpersonateSettings>,
+    priorities: &[(u32, u32, u16, bool)],
+) -> Result<()> {
+    let frames = priorities
+        .iter()
+        .map(|&(stream_id, dependency, weight, exclusive)| {
+            if stream_id == 0 || stream_id == dependency {
+                return Err(anyhow!(
+                    "Invalid HTTP/2 priority stream {stream_id} (dependency {dependency})"
+                ));
+            }
+            Ok(Priority::new(
+                StreamId::from(stream_id),
+                StreamDependency::new(
+                    StreamId::from(dependency),
+                    priority_weight(weight)?,
+                    exclusive,
+                ),
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    http2_settings_mut(settings).priority = (!frames.is_empty()).then(|| frames.into());
+    Ok(())
+}
+
+/// Replace the stream dependency of the HEADERS frames, given as `(dependency, weight, exclusive)`.
+pub fn set_http2_headers_priority(
+    settings: &mut Option<ImpersonateSettings>,
+    (dependency, weight, exclusive): (u32, u16, bool),
+) -> Result<()> {
+    http2_settings_mut(settings).headers_priority =
+        Some((dependency, priority_weight(weight)?, exclusive));
+    Ok(())
+}
+
+#[cfg(test)]
+mod fingerprint_tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_is_grease() {
+        assert!(is_grease(0x0a0a));
+        assert!(is_grease(0xfafa));
+        assert!(!is_grease(0x0a1a));
+        assert!(!is_grease(29));
+    }
+
+    #[test]
+    fn test_parse_ja3_chrome() {
+        let ja3 = "771,2570-4865-4866-4867-49195-49199,2570-0-23-65281-10-11-35-16-5-13-18-51-45-43-27-17513-2570-21,2570-4588-29-23-24,0";
+        let parsed = parse_ja3(ja3).unwrap();
+        assert_eq!(
+            parsed.tls_ciphers,
+            vec![
+                "TLS_AES_128_GCM_SHA256",
+                "TLS_AES_256_GCM_SHA384",
+                "TLS_CHACHA20_POLY1305_SHA256",
+                "ECDHE-ECDSA-AES128-GCM-SHA256",
+                "ECDHE-RSA-AES128-GCM-SHA256",
+            ]
+        );
+        assert_eq!(
+            parsed.tls_curves,
+            vec!["X25519MLKEM768", "X25519", "P-256", "P-384"]
+        );
+    }
+
+    #[test]
+    fn test_parse_client_hello() {
+        let mut extensions = Vec::new();
+        for (extension, data) in [
+            (0x0a0a_u16, vec![]),
+            (10, vec![0, 6, 0x0a, 0x0a, 0, 29, 0, 23]),
+            (13, vec![0, 4, 4, 3, 8, 4]),
+            (
+                16,
+                vec![
+                    0, 12, 2, b'h', b'2', 8, b'h', b't', b't', b'p', b'/', b'1', b'.', b'1',
+                ],
+            ),
+            (27, vec![2, 0, 2]),
+            (0xfe0d, vec![0]),
+        ] {
+            extensions.extend(extension.to_be_bytes());
+            extensions.extend((data.len() as u16).to_be_bytes());
+            extensions.extend(data);
+        }
+        let mut body = vec![3, 3];
+        body.extend([0; 32]);
+        body.push(0);
+        body.extend([0, 4, 0x0a, 0x0a, 0x13, 0x01]);
+        body.extend([1, 0]);
+        body.extend((extensions.len() as u16).to_be_bytes());
+        body.extend(extensions);
+        let mut handshake = vec![1, 0];
+        handshake.extend((body.len() as u16).to_be_bytes());
+        handshake.extend(body);
+        let mut record = vec![0x16, 3, 1];
+        record.extend((handshake.len() as u16).to_be_bytes());
+        record.extend(&handshake);
+
+        let expected = ClientHello {
+            ciphers: vec!["TLS_AES_128_GCM_SHA256".to_string()],
+            sigalgs: vec![
+                "ecdsa_secp256r1_sha256".to_string(),
+                "rsa_pss_rsae_sha256".to_string(),
+            ],
+            curves: vec!["X25519".to_string(), "P-256".to_string()],
+            alpn: vec!["h2".to_string(), "http/1.1".to_string()],
+            cert_compression: vec!["brotli".to_string()],
+            ech_grease: true,
+        };
+        assert_eq!(parse_client_hello(&record).unwrap(), expected);
+        assert_eq!(parse_client_hello(&handshake).unwrap(), expected);
+        assert!(parse_client_hello(&handshake[..20]).is_err());
+        assert!(parse_client_hello(&[2, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_ja4_chrome() {
+        let mut tls = rquest::mimic::impersonate(rquest::Impersonate::Chrome131, false).tls;
+        assert_eq!(ja4(&tls).unwrap(), "t13d1516h2_8daaf6152771_02713d6af862");
+        // JA4 sorts the extensions
+        tls.permute_extensions = Some(false);
+        assert_eq!(ja4(&tls).unwrap(), "t13d1516h2_8daaf6152771_02713d6af862");
+        tls.alpn_protos = AlpnProtos::Http1;
+        assert!(ja4(&tls).unwrap().starts_with("t13d1515h1_8daaf6152771_"));
+        tls.cipher_list = Some("ECDHE-ECDSA-AES128-GCM-SHA256:UNKNOWN".into());
+        assert_eq!(ja4(&tls), None);
+
+        // The ClientHello may be padded without a post-quantum key share
+        let tls = rquest::mimic::impersonate(rquest::Impersonate::Chrome100, false).tls;
+        assert_eq!(ja4(&tls), None);
+        assert_eq!(ja4(&TlsSettings::default()), None);
+    }
+
+    #[test]
+    fn test_ja4h() {
+        let headers = [
+            ("user-agent", "primp"),
+            ("accept-language", "en-US,en;q=0.9"),
+            ("cookie", "b=2; a=1"),
+            ("referer", "https://example.com/"),
+            ("accept-encoding", "gzip"),
+        ]
+        .map(|(name, value)| (name.to_string(), HeaderValue::from_static(value)));
+        assert_eq!(
+            ja4h(true, &headers),
+            "ge20cr03enus_e01356d67f74_1eb7c54d5283_06beefe2b477"
+        );
+    }
+
+    #[test]
+    fn test_request_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("user-agent", HeaderValue::from_static("primp"));
+        headers.insert("accept", HeaderValue::from_static("*/*"));
+        let order = [HeaderName::from_static("accept")];
+
+        let sent = request_headers(headers.clone(), Some(&order), true, true);
+        let names: Vec<&str> = sent.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["accept", "user-agent", "accept-encoding"]);
+
+        let sent = request_headers(headers, Some(&order), false, true);
+        let names: Vec<&str> = sent.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["Accept", "User-Agent", "Accept-Encoding", "Host"]);
+        assert_eq!(
+            ja4h(false, &sent),
+            "ge11nn040000_52d5234e7d62_000000000000_000000000000"
+        );
+    }
+
+    #[test]
+    fn test_akamai_h2_chrome() {
+        let settings = rquest::mimic::impersonate(rquest::Impersonate::Chrome131, false)
+            .http2
+            .unwrap();
+        assert_eq!(
+            akamai_h2(&settings),
+            "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p"
+        );
+    }
+
+    #[test]
+    fn test_akamai_h2_priority() {
+        use rquest::{StreamDependency, StreamId};
+        let settings = Http2Settings::builder()
+            .initial_connection_window_size(12517377)
+            .priority(std::borrow::Cow::Owned(vec![Priority::new(
+                StreamId::from(3),
+                StreamDependency::new(StreamId::zero(), 200, false),
+            )]))
+            .build();
+        assert_eq!(akamai_h2(&settings), "|12451842|3:0:0:201|m,s,a,p");
+    }
+
+    #[test]
+    fn test_parse_akamai_h2_roundtrip() {
+        for fingerprint in [
+            "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p",
+            "1:65536;4:131072;5:16384|12517377|3:0:0:201,5:0:0:101|m,p,a,s",
+            "2:0;4:2097152;3:100|10485760|0|m,s,p,a",
+        ] {
+            let settings = parse_akamai_h2(fingerprint).unwrap();
+            assert_eq!(akamai_h2(&settings), fingerprint);
+        }
+    }
+
+    #[test]
+    fn test_parse_akamai_h2_invalid() {
+        assert!(parse_akamai_h2("1:65536|0|m,a,s,p").is_err());
+        assert!(parse_akamai_h2("7:1|0|0|m,a,s,p").is_err());
+        assert!(parse_akamai_h2("1:1;1:2|0|0|m,a,s,p").is_err());
+        assert!(parse_akamai_h2("1:1|x|0|m,a,s,p").is_err());
+        assert!(parse_akamai_h2("1:1|0|3:0:0:0|m,a,s,p").is_err());
+        assert!(parse_akamai_h2("1:1|0|0|m,a,s").is_err());
+        assert!(parse_akamai_h2("1:1|0|0|m,a,s,s").is_err());
+    }
+
+    #[test]
+    fn test_set_http2_priorities() {
+        let mut settings = None;
+        set_http2_priorities(&mut settings, &[(3, 0, 201, false), (5, 3, 256, true)]).unwrap();
+        set_http2_headers_priority(&mut settings, (0, 256, true)).unwrap();
+        let http2 = settings.as_ref().unwrap().http2.as_ref().unwrap();
+        assert_eq!(
+            akamai_h2(http2).split('|').nth(2),
+            Some("3:0:0:201,5:1:3:256")
+        );
+        assert_eq!(http2.headers_priority, Some((0, 255, true)));
+
+        set_http2_priorities(&mut settings, &[]).unwrap();
+        assert!(settings.unwrap().http2.unwrap().priority.is_none());
+
+        let mut settings = None;
+        assert!(set_http2_priorities(&mut settings, &[(0, 0, 16, false)]).is_err());
+        assert!(set_http2_priorities(&mut settings, &[(3, 3, 16, false)]).is_err());
+        assert!(set_http2_priorities(&mut settings, &[(3, 0, 0, false)]).is_err());
+        assert!(set_http2_headers_priority(&mut settings, (0, 257, true)).is_err());
+    }
+
+    #[test]
+    fn test_set_http2_settings() {
+        let mut settings = None;
+        set_akamai_h2(
+            &mut settings,
+            "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p",
+        )
+        .unwrap();
+        let values = json!({
+            "header_table_size": 4096,
+            "enable_push": null,
+            "max_concurrent_streams": 100,
+            "initial_connection_window_size": 65535 + 10485760,
+            "settings_order": [3, 1],
+        });
+        set_http2_settings(&mut settings, &values).unwrap();
+        assert_eq!(
+            akamai_h2(settings.as_ref().unwrap().http2.as_ref().unwrap()),
+            "3:100;1:4096;4:6291456;6:262144|10485760|0|m,a,s,p"
+        );
+
+        assert!(set_http2_settings(&mut settings, &json!({"max_frame_size": -1})).is_err());
+        assert!(set_http2_settings(&mut settings, &json!({"enable_push": 1})).is_err());
+        assert!(set_http2_settings(&mut settings, &json!({"settings_order": [7]})).is_err());
+        assert!(set_http2_settings(&mut settings, &json!({"settings_order": [1, 1]})).is_err());
+        assert!(set_http2_settings(&mut settings, &json!({"window": 1})).is_err());
+    }
+
+    #[test]
+    fn test_parse_ja3_invalid() {
+        assert!(parse_ja3("771,4865").is_err());
+        assert!(parse_ja3("769,4865,0,29,0").is_err());
+        assert!(parse_ja3("771,4865-x,0,29,0").is_err());
+        assert!(parse_ja3("771,65000,0,29,0").is_err());
+        assert!(parse_ja3("771,4865,0,30,0").is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rquest::{
+    header::{HeaderMap, HeaderValue, CONTENT_TYPE, COOKIE, LOCATION, SET_COOKIE},
+    StatusCode, Version,
+};
+use serde_json::{json, Value};
+
+/// Requests and responses of a client, exported in HAR 1.2 format.
+#[derive(Debug, Default)]
+pub struct HarRecorder {
+    entries: Mutex<Vec<Value>>,
+}
+
+impl HarRecorder {
+    /// Add an entry, `wait` is the time until the response headers and `receive` the time to read the body.
+    pub fn record(
+        &self,
+        started: SystemTime,
+        mut request: Value,
+        response: Value,
+        wait: Duration,
+        receive: Duration,
+    ) {
+        let (wait, receive) = (millis(wait), millis(receive));
+        request["httpVersion"] = response["httpVersion"].clone();
+        let entry = json!({
+            "startedDateTime": format_datetime(started),
+            "time": wait + receive,
+            "request": request,
+            "response": response,
+            "cache": {},
+            "timings": {
+                "blocked": -1,
+                "dns": -1,
+                "connect": -1,
+                "ssl": -1,
+                "send": 0,
+                "wait": wait,
+                "receive": receive,
+            },
+        });
+        self.entries.lock().unwrap().push(entry);
+    }
+
+    /// HAR document with the recorded entries.
+    pub fn to_har(&self) -> Value {
+        json!({
+            "log": {
+                "version": "1.2",
+                "creator": {"name": "primp", "version": env!("CARGO_PKG_VERSION")},
+                "pages": [],
+                "entries": *self.entries.lock().unwrap(),
+            }
+        })
+    }
+}
+
+/// HAR request of a request, with the `headers` actually sent.
+/// `httpVersion` is set by `HarRecorder::record` from the response.
+pub fn har_request(request: &rquest::Request, headers: &HeaderMap) -> Value {
+    let url = request.url();
+    let query: Vec<Value> = url
+        .query_pairs()
+        .map(|(name, value)| json!({"name": name, "value": value}))
+        .collect();
+    let cookies: Vec<Value> = headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(cookie_pair)
+        .collect();
+    let body = request.body().and_then(|body| body.as_bytes());
+    let mut har_request = json!({
+        "method": request.method().as_str(),
+        "url": url.as_str(),
+        "cookies": cookies,
+        "headers": har_headers(headers),
+        "queryString": query,
+        "headersSize": -1,
+        "bodySize": body.map_or(0, |body| body.len()),
+    });
+    if let Some(body) = body {
+        har_request["postData"] = json!({
+            "mimeType": mime_type(headers),
+            "text": String::from_utf8_lossy(body),
+        });
+    }
+    har_request
+}
+
+/// HAR response, the body is base64 encoded unless it is valid UTF-8.
+pub fn har_response(version: Version, status: u16, headers: &HeaderMap, body: &[u8]) -> Value {
+    let cookies: Vec<Value> = headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| cookie_pair(value.split(';').next().unwrap_or_default()))
+        .collect();
+    let mut content = json!({"size": body.len(), "mimeType": mime_type(headers)});
+    match std::str::from_utf8(body) {
+        Ok(text) => content["text"] = json!(text),
+        Err(_) => {
+            content["text"] = json!(STANDARD.encode(body));
+            content["encoding"] = json!("base64");
+        }
+    }
+    let status_text = StatusCode::from_u16(status)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or_default();
+    json!({
+        "status": status,
+        "statusText": status_text,
+        "httpVersion": http_version(version),
+        "cookies": cookies,
+        "headers": har_headers(headers),
+        "content": content,
+        "redirectURL": headers.get(LOCATION).map(decode_value).unwrap_or_default(),
+        "headersSize": -1,
+        "bodySize": body.len(),
+    })
+}
+
+fn har_headers(headers: &HeaderMap) -> Vec<Value> {
+    headers
+        .iter()
+        .map(|(name, value)| json!({"name": name.as_str(), "value": decode_value(value)}))
+        .collect()
+}
+
+fn decode_value(value: &HeaderValue) -> String {
+    String::from_utf8_lossy(value.as_bytes()).into_owned()
+}
+
+fn mime_type(headers: &HeaderMap) -> String {
+    headers
+        .get(CONTENT_TYPE)
+        .map(decode_value)
+        .unwrap_or_default()
+}
+
+fn cookie_pair(pair: &str) -> Option<Value> {
+    let (name, value) = pair.split_once('=')?;
+    Some(json!({"name": name.trim(), "value": value.trim()}))
+}
+
+fn http_version(version: Version) -> &'static str {
+    match version {
+        Version::HTTP_09 => "HTTP/0.9",
+        Version::HTTP_10 => "HTTP/1.0",
+        Version::HTTP_2 => "HTTP/2.0",
+        Version::HTTP_3 => "HTTP/3.0",
+        _ => "HTTP/1.1",
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1000.0 * 1000.0).round() / 1000.0
+}
+
+/// ISO 8601 UTC date with milliseconds, e.g. "2024-01-31T12:00:00.000Z".
+fn format_datetime(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = ((secs / 86400) as i64, secs % 86400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod har_tests {
+    use super::*;
+
+    #[test]
+    fn test_format_datetime() {
+        assert_eq!(format_datetime(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        let time = httpdate::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+        assert_eq!(
+            format_datetime(time + Duration::from_millis(5)),
+            "1994-11-06T08:49:37.005Z"
+        );
+        let time = httpdate::parse_http_date("Thu, 29 Feb 2024 23:59:59 GMT").unwrap();
+        assert_eq!(format_datetime(time), "2024-02-29T23:59:59.000Z");
+    }
+
+    #[test]
+    fn test_har_response() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("image/png"));
+        headers.append(SET_COOKIE, HeaderValue::from_static("a=1; Path=/"));
+        headers.insert(LOCATION, HeaderValue::from_static("/next"));
+        let response = har_response(Version::HTTP_2, 302, &headers, b"\xff\x00");
+        assert_eq!(response["statusText"], "Found");
+        assert_eq!(response["httpVersion"], "HTTP/2.0");
+        assert_eq!(response["cookies"], json!([{"name": "a", "value": "1"}]));
+        assert_eq!(response["redirectURL"], "/next");
+        assert_eq!(
+            response["content"],
+            json!({"size": 2, "mimeType": "image/png", "text": "/wA=", "encoding": "base64"})
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use pyo3::{
+    exceptions::PyKeyError,
+    prelude::*,
+    types::{PyIterator, PyList},
+};
+use rquest::header::{HeaderMap, HeaderValue, SET_COOKIE};
+
+/// Case-insensitive, read-only mapping of response headers.
+///
+/// Holds the received `HeaderMap` and only converts the entries that are accessed to Python strings.
+/// Multiple values of a header are joined with ", ", `get_all` returns them separately.
+/// Names are iterated in the order they were first received.
+#[pyclass(mapping, frozen)]
+pub struct Headers {
+    pub headers: HeaderMap,
+}
+
+impl Headers {
+    pub fn new(headers: HeaderMap) -> Self {
+        Headers { headers }
+    }
+
+    /// Cookies set by the `Set-Cookie` headers, as name-value pairs.
+    pub fn cookies(&self) -> impl Iterator<Item = (String, String)> + '_ {
+        self.headers.get_all(SET_COOKIE).iter().filter_map(|value| {
+            let pair = decode_value(value);
+            let pair = pair.split(';').next().unwrap_or_default();
+            let (name, value) = pair.split_once('=')?;
+            Some((name.trim().to_string(), value.trim().to_string()))
+        })
+    }
+
+    fn value(&self, key: &str) -> Option<String> {
+        let mut values = self
+            .headers
+            .get_all(key)
+            .iter()
+            .map(decode_value)
+            .peekable();
+        values.peek()?;
+        Some(values.collect::<Vec<_>>().join(", "))
+    }
+}
+
+/// Decode a header value as UTF-8, falling back to Latin-1 for other bytes.
+fn decode_value(value: &HeaderValue) -> Cow<'_, str> {
+    match value.to_str() {
+        Ok(s) => Cow::Borrowed(s),
+        Err(_) => Cow::Owned(value.as_bytes().iter().map(|&b| b as char).collect()),
+    }
+}
+
+#[pymethods]
+impl Headers {
+    fn __getitem__(&self, key: &str) -> PyResult<String> {
+        self.value(key)
+            .ok_or_else(|| PyKeyError::new_err(key.to_string()))
+    }
+
+    /// All values of a header, in the order they were received.
+    fn get_all(&self, key: &str) -> Vec<String> {
+        self.headers
+            .get_all(key)
+            .iter()
+            .map(|value| decode_value(value).into_owned())
+            .collect()
+    }
+
+    /// (name, value) pairs with one pair per value, unlike `items`, grouped by name: the values of a header
+    /// are in the order they were received, but not interleaved with other headers as on the wire
+    /// (the received order across names is not kept by the HTTP stack).
+    fn grouped_items(&self) -> Vec<(&str, String)> {
+        self.headers
+            .iter()
+            .map(|(key, value)| (key.as_str(), decode_value(value).into_owned()))
+            .collect()
+    }
+
+    #[pyo3(signature = (key, default=None))]
+    fn get(&self, key: &str, default: Option<String>) -> Option<String> {
+        self.value(key).or(default)
+    }
+
+    fn __contains__(&self, key: &str) -> bool {
+        self.headers.contains_key(key)
+    }
+
+    fn __len__(&self) -> usize {
+        self.headers.keys_len()
+    }
+
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        PyList::new(py, self.keys())?.try_iter()
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.headers.keys().map(|key| key.as_str()).collect()
+    }
+
+    fn values(&self) -> Vec<String> {
+        self.headers
+            .keys()
+            .filter_map(|key| self.value(key.as_str()))
+            .collect()
+    }
+
+    fn items(&self) -> Vec<(&str, String)> {
+        self.headers
+            .keys()
+            .filter_map(|key| Some((key.as_str(), self.value(key.as_str())?)))
+            .collect()
+    }
+
+    fn __eq__(&self, other: HashMap<String, String>) -> bool {
+        other.len() == self.headers.keys_len()
+            && other
+                .iter()
+                .all(|(key, value)| self.value(key).as_ref() == Some(value))
+    }
+
+    fn __repr__(&self) -> String {
+        let items: Vec<String> = self
+            .items()
+            .into_iter()
+            .map(|(key, value)| format!("{key:?}: {value:?}"))
+            .collect();
+        format!("Headers({{{}}})", items.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod headers_tests {
+    use super::*;
+
+    #[test]
+    fn test_headers_value() {
+        let mut map = HeaderMap::new();
+        map.append("vary", HeaderValue::from_static("accept"));
+        map.append("vary", HeaderValue::from_static("origin"));
+        map.insert("x-latin", HeaderValue::from_bytes(b"caf\xe9").unwrap());
+        let headers = Headers::new(map);
+        assert_eq!(headers.value("Vary").unwrap(), "accept, origin");
+        assert_eq!(headers.value("x-latin").unwrap(), "café");
+        assert_eq!(headers.value("missing"), None);
+    }
+
+    #[test]
+    fn test_headers_multi_values() {
+        let mut map = HeaderMap::new();
+        map.append(SET_COOKIE, HeaderValue::from_static("a=1"));
+        map.append("vary", HeaderValue::from_static("accept"));
+        map.append(SET_COOKIE, HeaderValue::from_static("b=2"));
+        let headers = Headers::new(map);
+        assert_eq!(headers.get_all("Set-Cookie"), vec!["a=1", "b=2"]);
+        assert!(headers.get_all("missing").is_empty());
+        assert_eq!(
+            headers.grouped_items(),
+            vec![
+                ("set-cookie", "a=1".to_string()),
+                ("set-cookie", "b=2".to_string()),
+                ("vary", "accept".to_string())
+            ]
+        );
+        assert_eq!(headers.keys(), vec!["set-cookie", "vary"]);
+    }
+
+    #[test]
+    fn test_headers_cookies() {
+        let mut map = HeaderMap::new();
+        map.append(SET_COOKIE, HeaderValue::from_static("a=1; Path=/"));
+        map.append(SET_COOKIE, HeaderValue::from_static("b = 2"));
+        map.append(SET_COOKIE, HeaderValue::from_static("invalid"));
+        let cookies: Vec<_> = Headers::new(map).cookies().collect();
+        assert_eq!(
+            cookies,
+            vec![
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "2".to_string())
+            ]
+        );
+    }
+}
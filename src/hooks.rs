@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use pyo3::{
+    prelude::*,
+    types::{PyBytes, PyDict, PyList, PyString},
+};
+use rquest::header::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::response::Response;
+use crate::StrOrBytes;
+
+/// Header returned by a request hook equal to the one of the request.
+fn same_header(
+    name: &str,
+    value: &StrOrBytes,
+    old_name: &HeaderName,
+    old_value: &HeaderValue,
+) -> bool {
+    name.eq_ignore_ascii_case(old_name.as_str()) && value.as_bytes() == old_value.as_bytes()
+}
+
+/// Python callables run before each request is sent and after each response is received.
+#[derive(Debug)]
+pub struct Hooks {
+    request: Vec<PyObject>,
+    response: Vec<PyObject>,
+}
+
+impl Hooks {
+    /// Hooks from a `{"request": [...], "response": [...]}` mapping.
+    pub fn new(mut hooks: HashMap<String, Vec<PyObject>>) -> Result<Self> {
+        let request = hooks.remove("request").unwrap_or_default();
+        let response = hooks.remove("response").unwrap_or_default();
+        if let Some(event) = hooks.keys().next() {
+            return Err(anyhow!(
+                "Invalid hook event: {event}. Expected \"request\" or \"response\""
+            ));
+        }
+        Ok(Hooks { request, response })
+    }
+
+    /// Call the request hooks with a `{"method", "url", "headers"}` dict, the headers being a list of
+    /// `(name, value)` pairs in the order they are sent, duplicates included, with `bytes` values when
+    /// they are not valid UTF-8. A hook can change the url and headers in place or return a new dict.
+    pub fn run_request(&self, request: &mut rquest::Request) -> Result<()> {
+        if self.request.is_empty() {
+            return Ok(());
+        }
+        let (url, new_headers) = Python::with_gil(|py| {
+            let headers = PyList::empty(py);
+            for (name, value) in request.headers() {
+                let value = match std::str::from_utf8(value.as_bytes()) {
+                    Ok(value) => PyString::new(py, value).into_any(),
+                    Err(_) => PyBytes::new(py, value.as_bytes()).into_any(),
+                };
+                headers.append((name.as_str(), value))?;
+            }
+            let mut dict = PyDict::new(py);
+            dict.set_item("method", request.method().as_str())?;
+            dict.set_item("url", request.url().as_str())?;
+            dict.set_item("headers", headers)?;
+            for hook in &self.request {
+                let result = hook.bind(py).call1((&dict,))?;
+                if !result.is_none() {
+                    dict = result.downcast_into::<PyDict>().map_err(PyErr::from)?;
+                }
+            }
+            let url: String = dict
+                .get_item("url")?
+                .ok_or_else(|| anyhow!("Request hook removed the url"))?
+                .extract()?;
+            let new_headers: Vec<(String, StrOrBytes)> = dict
+                .get_item("headers")?
+                .ok_or_else(|| anyhow!("Request hook removed the headers"))?
+                .extract()?;
+            Ok::<_, anyhow::Error>((url, new_headers))
+        })?;
+
+        if url != request.url().as_str() {
+            *request.url_mut() = rquest::Url::parse(&url)?;
+        }
+        let unchanged = new_headers.len() == request.headers().len()
+            && new_headers.iter().zip(request.headers()).all(
+                |((name, value), (old_name, old_value))| {
+                    same_header(name, value, old_name, old_value)
+                },
+            );
+        if !unchanged {
+            // The entries left as they were keep their value (and its sensitive flag)
+            let mut old: Vec<Option<(HeaderName, HeaderValue)>> = request
+                .headers()
+                .iter()
+                .map(|(name, value)| Some((name.clone(), value.clone())))
+                .collect();
+            let mut header_map = HeaderMap::with_capacity(new_headers.len());
+            for (name, value) in new_headers {
+                let kept = old
+                    .iter_mut()
+                    .find(|entry| {
+                        entry.as_ref().is_some_and(|(old_name, old_value)| {
+                            same_header(&name, &value, old_name, old_value)
+                        })
+                    })
+                    .and_then(Option::take);
+                let (name, value) = match kept {
+                    Some(entry) => entry,
+                    None => (
+                        HeaderName::from_bytes(name.as_bytes())?,
+                        HeaderValue::from_bytes(value.as_bytes())?,
+                    ),
+                };
+                header_map.append(name, value);
+            }
+            *request.headers_mut() = header_map;
+        }
+        Ok(())
+    }
+
+    /// Call the response hooks with the response, a hook can return another `Response` to replace it.
+    pub fn run_response(&self, py: Python, mut response: Py<Response>) -> PyResult<Py<Response>> {
+        for hook in &self.response {
+            let result = hook.bind(py).call1((&response,))?;
+            if !result.is_none() {
+                response = result.extract()?;
+            }
+        }
+        Ok(response)
+    }
+}
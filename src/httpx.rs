@@ -0,0 +1,511 @@
+use pyo3::{
+    prelude::*,
+    sync::GILOnceCell,
+    types::{PyBytes, PyDict, PyString, PyTuple},
+};
+use rquest::StatusCode;
+
+use crate::response::Response;
+use crate::Client;
+
+/// httpx client arguments without a primp counterpart, the protocol is chosen by the impersonation.
+const IGNORED_KWARGS: [&str; 4] = ["http1", "http2", "trust_env", "limits"];
+
+/// httpx style client: `Client(base_url=..., follow_redirects=...)`, then `client.get(url)`.
+///
+/// The arguments are those of `primp.Client` and the httpx ones: `base_url`, `follow_redirects`
+/// (default `False` like httpx), a CA bundle path as `verify` and `httpx.Timeout` objects.
+/// A request with a `follow_redirects` different from the client one is sent with a second client,
+/// which has its own cookies.
+#[pyclass(module = "primp.compat.httpx", name = "Client")]
+pub struct HttpxClient {
+    #[pyo3(get)]
+    client: Py<Client>,
+    #[pyo3(get)]
+    base_url: String,
+    follow_redirects: bool,
+    config: Py<PyDict>,
+    redirect_client: GILOnceCell<Py<Client>>,
+}
+
+#[pymethods]
+impl HttpxClient {
+    #[new]
+    #[pyo3(signature = (**kwargs))]
+    fn new(py: Python, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let config = match kwargs {
+            Some(kwargs) => kwargs.copy()?,
+            None => PyDict::new(py),
+        };
+        for name in IGNORED_KWARGS {
+            if config.contains(name)? {
+                config.del_item(name)?;
+            }
+        }
+        let base_url = match config.get_item("base_url")? {
+            Some(base_url) => {
+                config.del_item("base_url")?;
+                base_url.str()?.to_string()
+            }
+            None => String::new(),
+        };
+        let follow_redirects = match config.get_item("follow_redirects")? {
+            Some(follow_redirects) => follow_redirects.is_truthy()?,
+            None => false,
+        };
+        config.set_item("follow_redirects", follow_redirects)?;
+        if let Some(verify) = config.get_item("verify")? {
+            if verify.is_instance_of::<PyString>() {
+                config.set_item("ca_cert_file", verify)?;
+                config.set_item("verify", true)?;
+            }
+        }
+        if let Some(timeout) = config.get_item("timeout")? {
+            config.set_item("timeout", timeout_seconds(&timeout)?)?;
+        }
+        let client = new_client(&config)?;
+        Ok(HttpxClient {
+            client,
+            base_url,
+            follow_redirects,
+            config: config.unbind(),
+            redirect_client: GILOnceCell::new(),
+        })
+    }
+
+    /// Send a request, the arguments are those of `httpx.Client.request()`.
+    #[pyo3(signature = (method, url, **kwargs))]
+    fn request(
+        &self,
+        py: Python,
+        method: &str,
+        url: &str,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<HttpxResponse> {
+        let kwargs = match kwargs {
+            Some(kwargs) => kwargs.copy()?,
+            None => PyDict::new(py),
+        };
+        let follow_redirects = match kwargs.get_item("follow_redirects")? {
+            Some(value) if !value.is_none() => value.is_truthy()?,
+            _ => self.follow_redirects,
+        };
+        for name in ["follow_redirects", "extensions"] {
+            if kwargs.contains(name)? {
+                kwargs.del_item(name)?;
+            }
+        }
+        if let Some(content) = kwargs.get_item("content")? {
+            if let Ok(text) = content.downcast::<PyString>() {
+                let content = PyBytes::new(py, text.to_str()?.as_bytes());
+                kwargs.set_item("content", content)?;
+            }
+        }
+        if let Some(timeout) = kwargs.get_item("timeout")? {
+            kwargs.set_item("timeout", timeout_seconds(&timeout)?)?;
+        }
+
+        let client = if follow_redirects == self.follow_redirects {
+            &self.client
+        } else {
+            self.redirect_client.get_or_try_init(py, || {
+                let config = self.config.bind(py).copy()?;
+                config.set_item("follow_redirects", follow_redirects)?;
+                new_client(&config)
+            })?
+        };
+        let url = join_url(&self.base_url, url);
+        let response = client
+            .bind(py)
+            .call_method("request", (method, url), Some(&kwargs))?
+            .downcast_into::<Response>()?
+            .unbind();
+        Ok(HttpxResponse { response })
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn get(
+        &self,
+        py: Python,
+        url: &str,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<HttpxResponse> {
+        self.request(py, "GET", url, kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn head(
+        &self,
+        py: Python,
+        url: &str,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<HttpxResponse> {
+        self.request(py, "HEAD", url, kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn options(
+        &self,
+        py: Python,
+        url: &str,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<HttpxResponse> {
+        self.request(py, "OPTIONS", url, kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn delete(
+        &self,
+        py: Python,
+        url: &str,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<HttpxResponse> {
+        self.request(py, "DELETE", url, kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn post(
+        &self,
+        py: Python,
+        url: &str,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<HttpxResponse> {
+        self.request(py, "POST", url, kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn put(
+        &self,
+        py: Python,
+        url: &str,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<HttpxResponse> {
+        self.request(py, "PUT", url, kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn patch(
+        &self,
+        py: Python,
+        url: &str,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<HttpxResponse> {
+        self.request(py, "PATCH", url, kwargs)
+    }
+
+    fn close(&self) {}
+
+    fn __enter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, _args: &Bound<'_, PyTuple>) {}
+}
+
+/// httpx style async client, the requests are sent by a `Client` in the default executor
+/// of the running event loop.
+#[pyclass(module = "primp.compat.httpx", name = "AsyncClient")]
+pub struct AsyncHttpxClient {
+    client: Py<HttpxClient>,
+}
+
+#[pymethods]
+impl AsyncHttpxClient {
+    #[new]
+    #[pyo3(signature = (**kwargs))]
+    fn new(py: Python, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let client = Py::new(py, HttpxClient::new(py, kwargs)?)?;
+        Ok(AsyncHttpxClient { client })
+    }
+
+    #[getter]
+    fn base_url(&self, py: Python) -> String {
+        self.client.borrow(py).base_url.clone()
+    }
+
+    #[pyo3(signature = (method, url, **kwargs))]
+    fn request<'py>(
+        &self,
+        py: Python<'py>,
+        method: &str,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.run_in_executor(py, (method, url), kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn get<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.run_in_executor(py, ("GET", url), kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn head<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.run_in_executor(py, ("HEAD", url), kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn options<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.run_in_executor(py, ("OPTIONS", url), kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn delete<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.run_in_executor(py, ("DELETE", url), kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn post<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.run_in_executor(py, ("POST", url), kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn put<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.run_in_executor(py, ("PUT", url), kwargs)
+    }
+
+    #[pyo3(signature = (url, **kwargs))]
+    fn patch<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.run_in_executor(py, ("PATCH", url), kwargs)
+    }
+
+    fn aclose<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        ready(py, py.None().into_bound(py))
+    }
+
+    fn __aenter__<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        ready(slf.py(), slf.clone().into_any())
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __aexit__<'py>(
+        &self,
+        py: Python<'py>,
+        _args: &Bound<'py, PyTuple>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        ready(py, py.None().into_bound(py))
+    }
+}
+
+impl AsyncHttpxClient {
+    /// Future of `Client.request(method, url, **kwargs)` run in the default executor.
+    fn run_in_executor<'py>(
+        &self,
+        py: Python<'py>,
+        (method, url): (&str, &str),
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let request = self.client.bind(py).getattr("request")?;
+        let call = py
+            .import("functools")?
+            .getattr("partial")?
+            .call((request, method, url), kwargs)?;
+        py.import("asyncio")?
+            .call_method0("get_running_loop")?
+            .call_method1("run_in_executor", (py.None(), call))
+    }
+}
+
+/// Future of the running event loop already resolved to `value`.
+fn ready<'py>(py: Python<'py>, value: Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let future = py
+        .import("asyncio")?
+        .call_method0("get_running_loop")?
+        .call_method0("create_future")?;
+    future.call_method1("set_result", (value,))?;
+    Ok(future)
+}
+
+/// httpx style response, with the attributes of `primp.Response` and the httpx ones.
+#[pyclass(module = "primp.compat.httpx", name = "Response")]
+pub struct HttpxResponse {
+    #[pyo3(get)]
+    response: Py<Response>,
+}
+
+#[pymethods]
+impl HttpxResponse {
+    #[getter]
+    fn status_code(&self, py: Python) -> u16 {
+        self.response.borrow(py).status_code
+    }
+
+    #[getter]
+    fn reason_phrase(&self, py: Python) -> &'static str {
+        StatusCode::from_u16(self.status_code(py))
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or_default()
+    }
+
+    #[getter]
+    fn is_success(&self, py: Python) -> bool {
+        (200..300).contains(&self.status_code(py))
+    }
+
+    #[getter]
+    fn is_redirect(&self, py: Python) -> bool {
+        [301, 302, 303, 307, 308].contains(&self.status_code(py))
+    }
+
+    #[getter]
+    fn is_client_error(&self, py: Python) -> bool {
+        (400..500).contains(&self.status_code(py))
+    }
+
+    #[getter]
+    fn is_server_error(&self, py: Python) -> bool {
+        (500..600).contains(&self.status_code(py))
+    }
+
+    #[getter]
+    fn is_error(&self, py: Python) -> bool {
+        self.status_code(py) >= 400
+    }
+
+    #[getter]
+    fn get_encoding<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.response.bind(py).getattr("encoding")
+    }
+
+    #[setter]
+    fn set_encoding(&self, py: Python, encoding: &str) -> PyResult<()> {
+        self.response.bind(py).setattr("encoding", encoding)
+    }
+
+    /// Raise `primp.HTTPError` if the status code is 4xx or 5xx, otherwise return the response.
+    fn raise_for_status(slf: &Bound<'_, Self>) -> PyResult<Py<Self>> {
+        let py = slf.py();
+        slf.borrow()
+            .response
+            .bind(py)
+            .call_method0("raise_for_status")?;
+        Ok(slf.clone().unbind())
+    }
+
+    /// Body of the response, already read.
+    fn read<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        self.response.borrow(py).content.bind(py).clone()
+    }
+
+    fn close(&self) {}
+
+    fn __getattr__<'py>(&self, py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        self.response.bind(py).getattr(name)
+    }
+
+    fn __repr__(&self, py: Python) -> String {
+        format!(
+            "<Response [{} {}]>",
+            self.status_code(py),
+            self.reason_phrase(py)
+        )
+    }
+}
+
+fn new_client(config: &Bound<'_, PyDict>) -> PyResult<Py<Client>> {
+    Ok(config
+        .py()
+        .get_type::<Client>()
+        .call((), Some(config))?
+        .downcast_into::<Client>()?
+        .unbind())
+}
+
+/// Seconds of an httpx timeout: None, a number or an `httpx.Timeout` (connect + read).
+fn timeout_seconds(timeout: &Bound<'_, PyAny>) -> PyResult<Option<f64>> {
+    if timeout.is_none() {
+        return Ok(None);
+    }
+    if timeout.hasattr("connect")? && timeout.hasattr("read")? {
+        let connect: Option<f64> = timeout.getattr("connect")?.extract()?;
+        let read: Option<f64> = timeout.getattr("read")?.extract()?;
+        return Ok(connect.into_iter().chain(read).reduce(|a, b| a + b));
+    }
+    timeout.extract().map(Some)
+}
+
+/// URL of a request, relative URLs are appended to the path of `base_url` like httpx does.
+fn join_url(base_url: &str, url: &str) -> String {
+    if base_url.is_empty() || url.contains("://") {
+        return url.to_string();
+    }
+    format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        url.trim_start_matches('/')
+    )
+}
+
+/// Add the `primp.compat` module with the `primp.compat.httpx` submodule.
+pub fn register_httpx(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    let compat = PyModule::new(py, "compat")?;
+    let httpx = PyModule::new(py, "httpx")?;
+    httpx.add_class::<HttpxClient>()?;
+    httpx.add_class::<AsyncHttpxClient>()?;
+    httpx.add_class::<HttpxResponse>()?;
+    compat.add_submodule(&httpx)?;
+    m.add_submodule(&compat)?;
+    let modules = py.import("sys")?.getattr("modules")?;
+    modules.set_item("primp.compat", compat)?;
+    modules.set_item("primp.compat.httpx", httpx)
+}
+
+#[cfg(test)]
+mod httpx_tests {
+    use super::*;
+
+    #[test]
+    fn test_join_url() {
+        assert_eq!(
+            join_url("https://api.example.com/v1/", "/users"),
+            "https://api.example.com/v1/users"
+        );
+        assert_eq!(
+            join_url("https://api.example.com/v1", "users?page=2"),
+            "https://api.example.com/v1/users?page=2"
+        );
+        assert_eq!(
+            join_url("https://api.example.com", "https://example.org/"),
+            "https://example.org/"
+        );
+        assert_eq!(join_url("", "https://example.org/"), "https://example.org/");
+    }
+}
@@ -0,0 +1,176 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use anyhow::Result;
+use pyo3::{
+    prelude::*,
+    types::{PyDict, PyList, PyString},
+    IntoPyObjectExt,
+};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+
+/// Parse a JSON document with the GIL released, then build the Python objects holding it.
+#[cfg(not(all(
+    feature = "simd-json",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+pub fn json_to_py<'py>(py: Python<'py>, bytes: &[u8]) -> Result<Bound<'py, PyAny>> {
+    let value = py.allow_threads(|| {
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        let value = JsonValue::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok::<_, serde_json::Error>(value)
+    })?;
+    Ok(value.to_py(py)?)
+}
+
+/// Parse a JSON document with simd-json, which parses a mutable copy in place, with the GIL released,
+/// then build the Python objects holding it.
+#[cfg(all(
+    feature = "simd-json",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub fn json_to_py<'py>(py: Python<'py>, bytes: &[u8]) -> Result<Bound<'py, PyAny>> {
+    let mut buffer = bytes.to_vec();
+    let value = py.allow_threads(|| {
+        let mut deserializer = simd_json::Deserializer::from_slice(&mut buffer)?;
+        JsonValue::deserialize(&mut deserializer)
+    })?;
+    Ok(value.to_py(py)?)
+}
+
+/// Parsed JSON value, lighter than `serde_json::Value`: strings without escapes are borrowed from
+/// the input and objects are kept as (key, value) pairs, only to be converted to Python objects.
+#[derive(Debug, PartialEq)]
+enum JsonValue<'a> {
+    Null,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Str(Cow<'a, str>),
+    Array(Vec<JsonValue<'a>>),
+    Object(Vec<(Cow<'a, str>, JsonValue<'a>)>),
+}
+
+impl JsonValue<'_> {
+    fn to_py<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(match self {
+            JsonValue::Null => py.None().into_bound(py),
+            JsonValue::Bool(v) => (*v).into_bound_py_any(py)?,
+            JsonValue::I64(v) => (*v).into_bound_py_any(py)?,
+            JsonValue::U64(v) => (*v).into_bound_py_any(py)?,
+            JsonValue::F64(v) => (*v).into_bound_py_any(py)?,
+            JsonValue::Str(v) => PyString::new(py, v).into_any(),
+            JsonValue::Array(items) => {
+                let list = PyList::empty(py);
+                for item in items {
+                    list.append(item.to_py(py)?)?;
+                }
+                list.into_any()
+            }
+            JsonValue::Object(entries) => {
+                let dict = PyDict::new(py);
+                for (key, value) in entries {
+                    dict.set_item(key.as_ref(), value.to_py(py)?)?;
+                }
+                dict.into_any()
+            }
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonValue<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(JsonValueVisitor)
+    }
+}
+
+/// Deserializes any JSON value into the matching `JsonValue`.
+struct JsonValueVisitor;
+
+impl<'de> Visitor<'de> for JsonValueVisitor {
+    type Value = JsonValue<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(JsonValue::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(JsonValue::I64(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(JsonValue::U64(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(JsonValue::F64(v))
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(JsonValue::Str(Cow::Borrowed(v)))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(JsonValue::Str(Cow::Owned(v.to_string())))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(JsonValue::Str(Cow::Owned(v)))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(JsonValue::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some(key) = map.next_key()? {
+            let JsonValue::Str(key) = key else {
+                return Err(de::Error::custom("expected a string key"));
+            };
+            entries.push((key, map.next_value()?));
+        }
+        Ok(JsonValue::Object(entries))
+    }
+}
+
+#[cfg(test)]
+mod json_tests {
+    use super::*;
+
+    #[test]
+    fn test_json_value() {
+        let value: JsonValue =
+            serde_json::from_str(r#"{"a": "x\ny", "b": [1, -1, 1.5, true, null]}"#).unwrap();
+        let JsonValue::Object(entries) = &value else {
+            panic!("{value:?}");
+        };
+        assert!(matches!(entries[0].0, Cow::Borrowed("a")));
+        assert_eq!(entries[0].1, JsonValue::Str(Cow::Owned("x\ny".into())));
+        assert_eq!(
+            entries[1].1,
+            JsonValue::Array(vec![
+                JsonValue::U64(1),
+                JsonValue::I64(-1),
+                JsonValue::F64(1.5),
+                JsonValue::Bool(true),
+                JsonValue::Null,
+            ])
+        );
+        assert!(serde_json::from_str::<JsonValue>("[1,").is_err());
+    }
+}
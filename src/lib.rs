@@ -1,53 +1,342 @@
 #![allow(clippy::too_many_arguments)]
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
 use std::str::FromStr;
-use std::sync::{Arc, LazyLock, Mutex};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
 
-use anyhow::{Error, Result};
-use bytes::Bytes;
+use anyhow::{anyhow, Error, Result};
+use encoding_rs::Encoding;
 use foldhash::fast::RandomState;
 use indexmap::IndexMap;
-use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3::types::PyBytes;
-use pythonize::depythonize;
+use pyo3::types::PyDict;
+use pythonize::{depythonize, pythonize};
 use rquest::{
-    header::{HeaderValue, COOKIE},
-    multipart,
+    header::{HeaderMap, HeaderName, HeaderValue, COOKIE},
+    mimic::ImpersonateSettings,
     redirect::Policy,
-    tls::Impersonate,
-    Body, Method,
+    AlpnProtos, Method, RequestBuilder, RootCertsStore, TlsSettings, Version,
 };
 use serde_json::Value;
-use tokio::{
-    fs::File,
-    runtime::{self, Runtime},
+use tokio::runtime::{self, Runtime};
+
+mod adapters;
+use adapters::register_adapters;
+
+mod client_hints;
+use client_hints::ClientHints;
+
+mod compat;
+use compat::register_compat;
+
+mod dns;
+use dns::{FamilyResolver, IpVersion};
+
+mod error;
+use error::{register_exceptions, request_error, RequestContext};
+
+mod fingerprint;
+use fingerprint::{akamai_h2, ja4, ja4h, parse_ja3, request_headers};
+
+mod har;
+use har::{har_request, har_response, HarRecorder};
+
+mod headers;
+
+mod hooks;
+use hooks::Hooks;
+
+mod httpx;
+use httpx::register_httpx;
+
+mod json;
+
+mod local;
+
+mod logging;
+use logging::{next_request_id, REQUEST_TARGET};
+
+mod mock;
+use mock::MockTransport;
+
+mod navigation;
+use navigation::Navigation;
+
+mod prepared;
+use prepared::PreparedRequest;
+
+mod profile;
+use profile::{
+    Profile, SettingsOptions, IMPERSONATE_ALIASES, IMPERSONATE_LIST, IMPERSONATE_OS_LIST,
 };
-use tokio_util::codec::{BytesCodec, FramedRead};
+
+mod redirect;
+use redirect::{redirect_copy, RedirectPolicy, Redirects, UploadFiles};
 
 mod response;
-use response::Response;
+use response::{Response, ResponseParts};
+
+mod retry;
+use retry::Retry;
+
+mod scrapy;
+use scrapy::register_scrapy;
+
+mod socks;
+mod stats;
+use stats::{ClientStats, RequestMetrics};
 
 mod traits;
 use traits::{CookiesTraits, HeadersTraits};
 
 mod utils;
-use utils::load_ca_certs;
+use utils::{
+    ascii_host, build_ca_store, build_proxy, check_exact_url, get_system_proxy,
+    is_stale_connection_error, load_ca_certs, load_system_ca_certs, parse_http_version,
+    parse_never_index, set_never_index, split_zone_id, zone_interface,
+};
+
+mod wire;
+use wire::{log_request, log_response, sent_headers};
 
 type IndexMapSSR = IndexMap<String, String, RandomState>;
 
-// Tokio global one-thread runtime
-static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
-    runtime::Builder::new_current_thread()
-        .enable_all()
-        .build()
-        .unwrap()
-});
+/// Keys of a `Client.batch()` request, the arguments of `Client.request()`.
+const BATCH_KEYS: [&str; 13] = [
+    "method",
+    "url",
+    "params",
+    "headers",
+    "cookies",
+    "content",
+    "data",
+    "json",
+    "files",
+    "auth",
+    "auth_bearer",
+    "timeout",
+    "http_version",
+];
+
+/// Optional value of a `Client.batch()` request, `None` when missing.
+fn batch_item<'py, T: FromPyObject<'py>>(
+    request: &Bound<'py, PyDict>,
+    key: &str,
+) -> PyResult<Option<T>> {
+    match request.get_item(key)? {
+        Some(value) if !value.is_none() => value.extract().map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// `verify` argument: a boolean, `"system"` to use the system CA bundle (Linux and BSD) or a CA bundle path.
+#[derive(FromPyObject)]
+enum Verify {
+    Bool(bool),
+    Mode(String),
+}
+
+/// `impersonate` argument: a profile name or a `{host: profile}` mapping, `"*"` for the other hosts.
+#[derive(FromPyObject)]
+enum ImpersonateArg {
+    Name(String),
+    Hosts(IndexMap<String, String>),
+}
+
+/// Argument accepting either `str` or `bytes`.
+#[derive(FromPyObject)]
+enum StrOrBytes {
+    Str(String),
+    Bytes(Vec<u8>),
+}
+
+impl StrOrBytes {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            StrOrBytes::Str(s) => s.as_bytes(),
+            StrOrBytes::Bytes(b) => b,
+        }
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        match self {
+            StrOrBytes::Str(s) => s.into_bytes(),
+            StrOrBytes::Bytes(b) => b,
+        }
+    }
+}
+
+/// Certificates the server certificate is verified against, resolved from `verify`, `ca_cert` and `ca_cert_file`.
+enum Trust {
+    Bundled,
+    System,
+    Pem(Vec<u8>),
+    Disabled,
+}
+
+/// TLS options of the client replacing the ones of the impersonation, kept to apply them again
+/// when the impersonation changes (rquest replaces all the TLS settings).
+struct TlsOptions {
+    trust: Trust,
+    verify_hostname: bool,
+    session_resumption: Option<bool>,
+    ech_grease: Option<bool>,
+    permute_extensions: Option<bool>,
+}
+
+impl TlsOptions {
+    /// Root certificate store of the trust, None when verification is disabled.
+    fn root_certs_store(&self) -> Result<Option<RootCertsStore>> {
+        Ok(match &self.trust {
+            Trust::Bundled => Some(load_ca_certs.into()),
+            Trust::System => Some(load_system_ca_certs.into()),
+            Trust::Pem(ca_pem) => Some(build_ca_store(ca_pem)?.into()),
+            Trust::Disabled => None,
+        })
+    }
+
+    /// Apply the options to the TLS settings of an impersonation.
+    fn apply(&self, tls: &mut TlsSettings) -> Result<()> {
+        match self.root_certs_store()? {
+            Some(store) => tls.root_certs_store = store,
+            None => tls.certs_verification = false,
+        }
+        tls.verify_hostname = self.verify_hostname;
+        self.apply_client_hello(tls);
+        Ok(())
+    }
+
+    /// Apply the options changing the ClientHello.
+    fn apply_client_hello(&self, tls: &mut TlsSettings) {
+        if let Some(enabled) = self.session_resumption {
+            tls.pre_shared_key = enabled;
+        }
+        if let Some(enabled) = self.ech_grease {
+            tls.enable_ech_grease = enabled;
+        }
+        if let Some(enabled) = self.permute_extensions {
+            tls.permute_extensions = Some(enabled);
+        }
+    }
+}
+
+/// Default headers of the impersonation, None without them.
+fn settings_headers(settings: &Option<ImpersonateSettings>) -> Option<&HeaderMap> {
+    settings
+        .as_ref()
+        .and_then(|settings| settings.headers.as_deref())
+}
+
+/// Switch the impersonation of an rquest client, which replaces its TLS settings and default headers:
+/// the TLS options of the client are applied again and, with `keep_headers`, its headers kept.
+fn set_impersonation(
+    client: &mut rquest::Client,
+    settings: Option<ImpersonateSettings>,
+    tls_options: &TlsOptions,
+    keep_headers: bool,
+    hpack_never_index: Option<&[HeaderName]>,
+) -> Result<()> {
+    let Some(mut settings) = settings else {
+        return Ok(());
+    };
+    let headers = keep_headers.then(|| client.headers_mut().clone());
+    tls_options.apply(&mut settings.tls)?;
+    client.set_impersonate_settings(settings)?;
+    if let Some(headers) = headers {
+        *client.headers_mut() = headers;
+    }
+    if let Some(names) = hpack_never_index {
+        set_never_index(client.headers_mut(), names);
+    }
+    Ok(())
+}
+
+// Tokio global runtime: one-thread unless configured with `configure_runtime` before the first request.
+// Tagged with the pid of the process that built it, so a child created by `os.fork` builds its own.
+static RUNTIME: Mutex<Option<(u32, &'static Runtime)>> = Mutex::new(None);
+static RUNTIME_WORKER_THREADS: OnceLock<usize> = OnceLock::new();
+
+fn runtime() -> &'static Runtime {
+    let pid = std::process::id();
+    let mut guard = RUNTIME.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((runtime_pid, runtime)) = *guard {
+        if runtime_pid == pid {
+            return runtime;
+        }
+        log::info!("fork detected (pid {runtime_pid} -> {pid}), building a new runtime");
+    }
+    let mut builder = match RUNTIME_WORKER_THREADS.get() {
+        Some(&worker_threads) => {
+            let mut builder = runtime::Builder::new_multi_thread();
+            builder.worker_threads(worker_threads);
+            builder
+        }
+        None => runtime::Builder::new_current_thread(),
+    };
+    // Leaked: a runtime inherited through fork has no worker threads left and must never be dropped.
+    let runtime: &'static Runtime = Box::leak(Box::new(builder.enable_all().build().unwrap()));
+    *guard = Some((pid, runtime));
+    runtime
+}
+
+// Interval at which a blocking call wakes up to let Python run signal handlers (e.g. Ctrl-C)
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Block on the future with the GIL released, checking Python signals every `SIGNAL_CHECK_INTERVAL`.
+/// An exception raised by a signal handler (e.g. `KeyboardInterrupt`) drops the future, aborting the request.
+fn block_on<F>(py: Python, future: F) -> PyResult<F::Output>
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    let mut future = Box::pin(future);
+    loop {
+        let output = py.allow_threads(|| {
+            runtime()
+                .block_on(async { tokio::time::timeout(SIGNAL_CHECK_INTERVAL, &mut future).await })
+        });
+        match output {
+            Ok(output) => return Ok(output),
+            Err(_) => py.check_signals()?,
+        }
+    }
+}
 
 #[pyclass]
 /// HTTP client that can impersonate web browsers.
 pub struct Client {
     client: Arc<Mutex<rquest::Client>>,
+    stats: Arc<ClientStats>,
+    client_hints: Option<Arc<ClientHints>>,
+    navigation: Option<Arc<Navigation>>,
+    track_navigation: bool,
+    referer: bool,
+    impersonate_hosts: Option<Arc<HashMap<String, rquest::Client>>>,
+    settings_options: SettingsOptions,
+    tls_options: TlsOptions,
+    keep_headers: bool,
+    hpack_never_index: Option<Arc<Vec<HeaderName>>>,
+    http1_title_case: bool,
+    retry_stale_connections: bool,
+    retries: Option<Arc<Retry>>,
+    redirects: Option<Arc<Redirects>>,
+    error_body_snippet: usize,
+    hooks: Option<Arc<Hooks>>,
+    transport: Option<MockTransport>,
+    har: Option<Arc<HarRecorder>>,
+    debug: bool,
+    on_metrics: Option<Arc<PyObject>>,
+    normalize_urls: bool,
+    local_urls: bool,
+    https_only: bool,
+    warn_body_methods: bool,
+    default_encoding: String,
+    impersonate: Option<String>,
+    pid: AtomicU32,
+    used: AtomicBool,
     #[pyo3(get, set)]
     auth: Option<(String, Option<String>)>,
     #[pyo3(get, set)]
@@ -78,20 +367,154 @@ impl Client {
     /// * `cookie_store` - Enable a persistent cookie store. Received cookies will be preserved and included
     ///         in additional requests. Default is `true`.
     /// * `referer` - Enable or disable automatic setting of the `Referer` header. Default is `true`.
-    /// * `proxy` - An optional proxy URL for HTTP requests.
+    /// * `proxy` - An optional proxy URL for HTTP requests. Supported schemes: http, https, socks4, socks4a, socks5,
+    ///         socks5h. With `socks4a://` and `socks5h://` hostnames are resolved by the proxy; `socks4://` takes
+    ///         no credentials, `socks4a://` a user ID ("socks4a://user@host:1080"). "system" uses the
+    ///         platform-configured proxy (environment variables, Windows WinINET, macOS SystemConfiguration
+    ///         or GNOME settings).
     /// * `timeout` - An optional timeout for HTTP requests in seconds.
     /// * `impersonate` - An optional entity to impersonate. Supported browsers and versions include Chrome, Safari, OkHttp, and Edge.
+    ///         A dict maps hosts to entities, e.g. {"api.example.com": "okhttp_5", "*": "chrome_131"}; "*" applies
+    ///         to the other hosts. Cookies and the connection pool are shared, redirects keep the first host's entity.
     /// * `follow_redirects` - A boolean to enable or disable following redirects. Default is `true`.
     /// * `max_redirects` - The maximum number of redirects to follow. Default is 20. Applies if `follow_redirects` is `true`.
-    /// * `verify` - An optional boolean indicating whether to verify SSL certificates. Default is `true`.
+    /// * `verify` - An optional boolean indicating whether to verify SSL certificates,
+    ///         "system" to verify against the system CA bundle (Linux and BSD only: `SSL_CERT_FILE` or the
+    ///         distribution bundle, such as /etc/ssl/certs/ca-certificates.crt),
+    ///         or a path to a CA bundle (like `ca_cert_file`). Default is `true`.
     /// * `ca_cert_file` - Path to CA certificate store. Default is None.
     /// * `https_only` - Restrict the Client to be used with HTTPS only requests. Default is `false`.
     /// * `http2_only` - If true - use only HTTP/2, if false - use only HTTP/1. Default is `false`.
+    /// * `ip_version` - IP address family to connect over: "auto", "ipv4" or "ipv6". Default is "auto".
+    /// * `local_address` - Local IP address to bind outgoing sockets to. Default is None.
+    /// * `interface` - Network interface to bind outgoing sockets to (`SO_BINDTODEVICE`, Linux only). Default is None.
+    /// * `tcp_nodelay` - Set `TCP_NODELAY` on sockets. Default is `true`.
+    /// * `tcp_keepalive` - Enable `SO_KEEPALIVE` with the given idle time in seconds. Default is None.
+    /// * `pool_idle_timeout` - Timeout in seconds for idle pooled connections. Default is 90.
+    /// * `pool_max_idle_per_host` - Maximum number of idle connections kept in the pool per host. Default is unlimited.
+    /// * `http1_only` - If true - use only HTTP/1.1. Default is `false`.
+    /// * `alpn` - ALPN protocols offered in the TLS ClientHello: `["h2", "http/1.1"]`, `["h2"]` or `["http/1.1"]`.
+    ///         Default is None (the impersonated browser's list).
+    /// * `ca_cert` - CA certificates in PEM format (str or bytes), used instead of `ca_cert_file`
+    ///         and the bundled CA store when `verify` is true. Default is None.
+    /// * `verify_hostname` - If false - validate the certificate chain but skip matching the hostname.
+    ///         Default is `true`.
+    /// * `session_resumption` - If true - cache TLS sessions per host and resume them (session tickets / PSK)
+    ///         on new connections. Default is None (the impersonated browser's setting, off without impersonation).
+    /// * `ech_grease` - If true - send a GREASE Encrypted Client Hello extension, as Chrome does for hosts
+    ///         without an ECH config. Default is None (the impersonated browser's setting).
+    /// * `cert_compression` - Certificate compression algorithms advertised in the ClientHello:
+    ///         "brotli", "zstd", "zlib"; an empty list disables the extension.
+    ///         Default is None (the impersonated browser's list).
+    /// * `permute_extensions` - If true - shuffle the ClientHello extension order on every connection,
+    ///         like Chrome 110+ (JA3 varies, JA4 stays stable). Default is None (the impersonated browser's setting).
+    /// * `tls_ciphers` - Cipher suites offered in the ClientHello, in order (BoringSSL names,
+    ///         e.g. "TLS_AES_128_GCM_SHA256", "ECDHE-ECDSA-AES128-GCM-SHA256"). Default is None (the impersonated browser's list).
+    /// * `tls_sigalgs` - Signature algorithms offered in the ClientHello, in order (e.g. "ecdsa_secp256r1_sha256",
+    ///         "rsa_pss_rsae_sha256"). Default is None (the impersonated browser's list).
+    /// * `tls_curves` - Named groups offered in the ClientHello, in order (e.g. "X25519MLKEM768", "X25519", "P-256").
+    ///         Default is None (the impersonated browser's list).
+    /// * `tls_extension_order` - Extension permutation indices (BoringSSL extension table, range [0, 25))
+    ///         fixing the ClientHello extension order. Default is None (the impersonated browser's order).
+    /// * `http2_fingerprint` - Akamai HTTP/2 fingerprint to send, e.g. "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p"
+    ///         (SETTINGS|WINDOW_UPDATE|PRIORITY|PSEUDO_HEADER_ORDER). Default is None (the impersonated browser's settings).
+    /// * `impersonate_profile` - Custom impersonation profile: a dict with the optional keys "impersonate" (base profile),
+    ///         "headers", "headers_order", "tls" ("alpn", "ciphers", "sigalgs", "curves", "cert_compression",
+    ///         "extension_order", "permute_extensions", "ech_grease") and "http2" (Akamai fingerprint).
+    ///         The other client arguments take precedence over the profile. Default is None.
+    /// * `impersonate_profile_file` - Path to a JSON file with a custom impersonation profile. Default is None.
+    /// * `locale` - Locale of the impersonated browser, e.g. "de-DE". Rewrites the `Accept-Language` header
+    ///         with the q-values of the browser family. Default is None (the impersonated browser's en-US).
+    /// * `client_hints` - Send the high-entropy client hints (sec-ch-ua-arch, -bitness, -model, -platform-version,
+    ///         -full-version, -full-version-list, -wow64) to the origins that ask for them with `Accept-CH`, consistent
+    ///         with the impersonated user agent. Requires a Chromium-based impersonation. Default is False.
+    /// * `mobile` - Impersonate the mobile variant of the browser: Android user agent and `sec-ch-ua-mobile: ?1`
+    ///         for Chromium and Firefox, iPhone for Safari. Default is False.
+    /// * `request_context` - Request type the impersonation headers (Accept, Priority, sec-fetch-*) are sent for:
+    ///         "document" (page navigation), "xhr", "fetch" (JSON APIs) or "image". Default is None ("document").
+    /// * `track_navigation` - Send `sec-fetch-site` (same-origin, same-site or cross-site) and `Referer` relative to
+    ///         the previous response, like a browser following links, instead of always `sec-fetch-site: none`.
+    ///         Only applies to impersonations sending sec-fetch-site; `Referer` follows the `referer` option.
+    ///         Sites are approximated by the last two host labels (no public suffix list): `a.co.uk` and `b.co.uk`
+    ///         are same-site. Default is False.
+    /// * `http2_priorities` - PRIORITY frames sent after the connection preface, as (stream_id, dependency, weight, exclusive)
+    ///         tuples with a weight in [1, 256], e.g. [(3, 0, 201, False), (5, 0, 101, False)]; an empty list sends none.
+    ///         Default is None (the impersonated browser's frames).
+    /// * `http2_headers_priority` - Stream dependency of the HEADERS frames as a (dependency, weight, exclusive) tuple,
+    ///         e.g. (0, 256, True) for Chrome. Default is None (the impersonated browser's priority).
+    /// * `http2_settings` - HTTP/2 settings overrides: "header_table_size", "enable_push", "max_concurrent_streams",
+    ///         "initial_stream_window_size", "max_frame_size", "max_header_list_size", "unknown_setting8",
+    ///         "unknown_setting9", "initial_connection_window_size", "initial_stream_id" and "settings_order"
+    ///         (SETTINGS ids sent first, e.g. [3, 1]). None values leave a setting unsent. Default is None.
+    /// * `http2_keep_alive_interval` - Interval in seconds between HTTP/2 PING frames keeping the connection alive.
+    ///         Default is None (no pings).
+    /// * `http2_keep_alive_timeout` - Seconds to wait for a PING acknowledgement before closing the connection.
+    ///         Default is None (20 seconds). Applies if `http2_keep_alive_interval` is set.
+    /// * `http2_keep_alive_while_idle` - Send the PING frames on connections without open streams too.
+    ///         Default is False. Applies if `http2_keep_alive_interval` is set.
+    /// * `hpack_never_index` - Headers sent as HPACK never indexed literals over HTTP/2, e.g. ["cookie"], like browsers
+    ///         do for credentials. `Authorization` set by `auth`/`auth_bearer` is never indexed regardless.
+    ///         Cookies added by the cookie store are not covered. Default is None.
+    /// * `http1_title_case` - Send HTTP/1.1 header names in Title-Case (`User-Agent`, `Accept-Encoding`) like browsers.
+    ///         Browser impersonations with `http1_only` also send `Connection: keep-alive`.
+    ///         Default is None (True when impersonating, lowercase otherwise).
+    /// * `retry_stale_connections` - Retry idempotent requests (GET, HEAD, OPTIONS, PUT, DELETE) once on a new connection
+    ///         when the server did not process them: the connection was closed before a response, or the HTTP/2 stream was
+    ///         refused (GOAWAY, REFUSED_STREAM). Default is True.
+    /// * `retries` - A `Retry` policy for connect errors, timeouts and retryable statuses. Requests with a streamed body
+    ///         are not retried. After the last retry, the error is raised or the last response returned. Default is None.
+    /// * `error_body_snippet` - Include up to this many bytes of the response body in the `HTTPError` message
+    ///         of `Response.raise_for_status()`, where APIs usually explain the error. Default is None.
+    /// * `hooks` - Callables run for each request: `{"request": [...], "response": [...]}`. Request hooks get
+    ///         a `{"method", "url", "headers"}` dict before it is sent, the headers being a list of `(name, value)`
+    ///         pairs (duplicates included, `bytes` values when not UTF-8), and can change the url and headers
+    ///         in place or return a new dict. Response hooks get the `Response` and can return another one to replace it.
+    ///         Default is None.
+    /// * `transport` - A `MockTransport` answering the requests with registered responses instead of the network,
+    ///         for tests. Default is None.
+    /// * `debug` - Log the request line and headers sent (including the impersonation headers), the resolved addresses,
+    ///         the connected address, and the response status line and headers, like `curl -v`, at DEBUG level
+    ///         to the "primp.wire" logger. Default is False.
+    /// * `on_metrics` - Called after each request with a dict: `url` (final URL), `method`, `status_code`,
+    ///         `bytes_sent`, `bytes_received`, `timings` (`total`, `wait` until the response headers, `receive`
+    ///         of the body, in seconds), `retries`, `error` (None on success) and `request_id`,
+    ///         e.g. to feed Prometheus or StatsD. Exceptions raised by the callback are logged and ignored.
+    ///         Default is None.
+    /// * `normalize_urls` - Punycode-encode Unicode hostnames and percent-encode the non-ASCII characters and spaces
+    ///         of the path and query as UTF-8, like browsers. With False, the path and query are sent exactly as given
+    ///         and URLs that would be changed raise `InvalidRequest`, for exact-byte replay. Default is True.
+    /// * `redirect_policy` - Which redirects are followed, within `max_redirects`: "same_origin", "same_host",
+    ///         "https_upgrade_only" (http:// to https:// on the same host), a `callback(from_url, to_url, status)`
+    ///         returning whether to follow, or a list of them that must all allow the redirect. The response of
+    ///         a redirect that is not followed is returned. Default is None (all redirects).
+    /// * `redirect_forward_headers` - Sensitive headers (`Authorization`, `Cookie`, `Proxy-Authorization`) kept
+    ///         on cross-origin redirects, which remove them by default like browsers. The upgrade from http:// to
+    ///         https:// on the same host is not cross-origin. Default is None.
+    /// * `redirect_strip_headers` - Other headers removed on cross-origin redirects, e.g. `["x-api-key"]`.
+    ///         Default is None.
+    /// * `redirect_method_policy` - Method after a 301 or 302 redirect of a POST request: "browser" sends a GET
+    ///         without body like browsers, "strict" the same method and body (RFC 9110). 303 redirects are always
+    ///         followed with a GET, 307 and 308 redirects with the same method and body. Default is "browser".
+    /// * `warn_body_methods` - Log a warning when a GET, HEAD, DELETE or OPTIONS request has a body: it is sent,
+    ///         but these methods define no body semantics and some servers and proxies ignore or reject it.
+    ///         Default is True.
+    /// * `impersonate_skip_headers` - Keep the TLS and HTTP/2 fingerprint of the impersonation but not its default
+    ///         headers (User-Agent, Accept, sec-ch-ua, ...), only the `headers` argument and the request headers
+    ///         are sent, in the browser's order. Default is False.
+    /// * `impersonate_skip_http2` - Keep the TLS fingerprint of the impersonation but use the default HTTP/2
+    ///         settings instead of the browser's. Default is False.
+    /// * `impersonate_os` - OS of the impersonated browser: "windows", "macos" or "linux" rewrite the user agent
+    ///         and `sec-ch-ua-platform` (Safari only on "macos"), "android" and "ios" are the mobile variant (like
+    ///         `mobile`, "ios" for Safari). Default is None (the impersonated browser's OS).
+    /// * `default_encoding` - Encoding of the response text when neither the `Content-Type` charset nor the
+    ///         `<meta charset>` tag gives one, e.g. "windows-1251" for legacy sites. Default is None ("utf-8").
+    /// * `local_urls` - Answer GET and HEAD requests of `file://` URLs from the disk and of `data:` URLs from
+    ///         the URL itself, e.g. for fixtures. Not allowed with `https_only`. Default is False.
     ///
     /// # Example
     ///
     /// ```
-    /// from primp import Client
+    /// from primp import Client, Retry
     ///
     /// client = Client(
     ///     auth=("name", "password"),
@@ -109,12 +532,64 @@ impl Client {
     ///     ca_cert_file="/cert/cacert.pem",
     ///     https_only=True,
     ///     http2_only=True,
+    ///     ip_version="ipv4",
+    ///     local_address="192.0.2.10",
+    ///     interface="eth1",
+    ///     tcp_nodelay=True,
+    ///     tcp_keepalive=60,
+    ///     pool_idle_timeout=30,
+    ///     pool_max_idle_per_host=10,
+    ///     http1_only=False,
+    ///     alpn=["h2", "http/1.1"],
+    ///     ca_cert=open("cert.pem").read(),
+    ///     verify_hostname=True,
+    ///     session_resumption=True,
+    ///     ech_grease=True,
+    ///     cert_compression=["brotli"],
+    ///     permute_extensions=True,
+    ///     tls_ciphers=["TLS_AES_128_GCM_SHA256", "TLS_AES_256_GCM_SHA384"],
+    ///     tls_sigalgs=["ecdsa_secp256r1_sha256", "rsa_pss_rsae_sha256"],
+    ///     tls_curves=["X25519", "P-256"],
+    ///     http2_fingerprint="1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p",
+    ///     impersonate_profile_file="profile.json",
+    ///     locale="de-DE",
+    ///     client_hints=True,
+    ///     mobile=False,
+    ///     request_context="fetch",
+    ///     track_navigation=True,
+    ///     http2_priorities=[(3, 0, 201, False)],
+    ///     http2_headers_priority=(0, 256, True),
+    ///     http2_settings={"header_table_size": 65536, "max_concurrent_streams": 1000},
+    ///     http2_keep_alive_interval=30,
+    ///     http2_keep_alive_timeout=10,
+    ///     http2_keep_alive_while_idle=True,
+    ///     hpack_never_index=["cookie", "x-api-key"],
+    ///     http1_title_case=True,
+    ///     retry_stale_connections=True,
+    ///     retries=Retry(total=3, backoff_factor=0.5, status_forcelist=[429, 502, 503]),
     /// )
     /// ```
     #[new]
     #[pyo3(signature = (auth=None, auth_bearer=None, params=None, headers=None, cookies=None,
         cookie_store=true, referer=true, proxy=None, timeout=None, impersonate=None, follow_redirects=true,
-        max_redirects=20, verify=true, ca_cert_file=None, https_only=false, http2_only=false))]
+        max_redirects=20, verify=None, ca_cert_file=None, https_only=false, http2_only=false,
+        ip_version=None, local_address=None, interface=None, tcp_nodelay=true, tcp_keepalive=None,
+        pool_idle_timeout=None, pool_max_idle_per_host=None, http1_only=false, alpn=None,
+        ca_cert=None,
+        verify_hostname=true, session_resumption=None,
+        ech_grease=None, cert_compression=None,
+        permute_extensions=None, tls_ciphers=None, tls_sigalgs=None, tls_curves=None,
+        tls_extension_order=None, http2_fingerprint=None, impersonate_profile=None,
+        impersonate_profile_file=None, locale=None, client_hints=None,
+        mobile=None, request_context=None, track_navigation=false, http2_priorities=None,
+        http2_headers_priority=None, http2_settings=None, http2_keep_alive_interval=None,
+        http2_keep_alive_timeout=None, http2_keep_alive_while_idle=None, hpack_never_index=None,
+        http1_title_case=None, retry_stale_connections=true, retries=None, error_body_snippet=None,
+        hooks=None, transport=None, debug=false, on_metrics=None, normalize_urls=true,
+        redirect_policy=None, redirect_forward_headers=None, redirect_strip_headers=None,
+        redirect_method_policy=None, warn_body_methods=true, impersonate_skip_headers=false,
+        impersonate_skip_http2=false, impersonate_os=None, default_encoding=None,
+        local_urls=false))]
     fn new(
         auth: Option<(String, Option<String>)>,
         auth_bearer: Option<String>,
@@ -125,26 +600,145 @@ impl Client {
         referer: Option<bool>,
         proxy: Option<String>,
         timeout: Option<f64>,
-        impersonate: Option<&str>,
+        impersonate: Option<ImpersonateArg>,
         follow_redirects: Option<bool>,
         max_redirects: Option<usize>,
-        verify: Option<bool>,
+        verify: Option<Verify>,
         ca_cert_file: Option<String>,
         https_only: Option<bool>,
         http2_only: Option<bool>,
+        ip_version: Option<&str>,
+        local_address: Option<&str>,
+        interface: Option<String>,
+        tcp_nodelay: Option<bool>,
+        tcp_keepalive: Option<f64>,
+        pool_idle_timeout: Option<f64>,
+        pool_max_idle_per_host: Option<usize>,
+        http1_only: Option<bool>,
+        alpn: Option<Vec<String>>,
+        ca_cert: Option<StrOrBytes>,
+        verify_hostname: Option<bool>,
+        session_resumption: Option<bool>,
+        ech_grease: Option<bool>,
+        cert_compression: Option<Vec<String>>,
+        permute_extensions: Option<bool>,
+        tls_ciphers: Option<Vec<String>>,
+        tls_sigalgs: Option<Vec<String>>,
+        tls_curves: Option<Vec<String>>,
+        tls_extension_order: Option<Vec<u8>>,
+        http2_fingerprint: Option<&str>,
+        impersonate_profile: Option<&Bound<'_, PyAny>>,
+        impersonate_profile_file: Option<&str>,
+        locale: Option<&str>,
+        client_hints: Option<bool>,
+        mobile: Option<bool>,
+        request_context: Option<&str>,
+        track_navigation: Option<bool>,
+        http2_priorities: Option<Vec<(u32, u32, u16, bool)>>,
+        http2_headers_priority: Option<(u32, u16, bool)>,
+        http2_settings: Option<&Bound<'_, PyAny>>,
+        http2_keep_alive_interval: Option<f64>,
+        http2_keep_alive_timeout: Option<f64>,
+        http2_keep_alive_while_idle: Option<bool>,
+        hpack_never_index: Option<Vec<String>>,
+        http1_title_case: Option<bool>,
+        retry_stale_connections: Option<bool>,
+        retries: Option<Retry>,
+        error_body_snippet: Option<usize>,
+        hooks: Option<HashMap<String, Vec<PyObject>>>,
+        transport: Option<MockTransport>,
+        debug: Option<bool>,
+        on_metrics: Option<PyObject>,
+        normalize_urls: Option<bool>,
+        redirect_policy: Option<RedirectPolicy>,
+        redirect_forward_headers: Option<Vec<String>>,
+        redirect_strip_headers: Option<Vec<String>>,
+        redirect_method_policy: Option<String>,
+        warn_body_methods: Option<bool>,
+        impersonate_skip_headers: Option<bool>,
+        impersonate_skip_http2: Option<bool>,
+        impersonate_os: Option<String>,
+        default_encoding: Option<String>,
+        local_urls: Option<bool>,
     ) -> Result<Self> {
         // Client builder
         let mut client_builder = rquest::Client::builder();
 
-        // Impersonate
-        if let Some(impersonation_type) = impersonate {
-            let impersonation =
-                Impersonate::from_str(impersonation_type).map_err(PyValueError::new_err)?;
-            client_builder = client_builder.impersonate(impersonation);
+        // Impersonate_profile || Impersonate_profile_file
+        let profile = match (impersonate_profile, impersonate_profile_file) {
+            (Some(impersonate_profile), _) => {
+                let value: Value = depythonize(impersonate_profile)?;
+                Some(Profile::from_json(&value)?)
+            }
+            (None, Some(impersonate_profile_file)) => {
+                Some(Profile::from_file(impersonate_profile_file)?)
+            }
+            (None, None) => None,
+        };
+        let (impersonate, impersonate_hosts) = match impersonate {
+            Some(ImpersonateArg::Name(name)) => (Some(name), None),
+            Some(ImpersonateArg::Hosts(mut hosts)) => (hosts.shift_remove("*"), Some(hosts)),
+            None => (None, None),
+        };
+        let impersonate =
+            impersonate.or_else(|| profile.as_ref().and_then(|p| p.impersonate.clone()));
+
+        // Impersonate, edited by the options below, kept to apply them again in `set_impersonate`
+        let settings_options = SettingsOptions {
+            profile,
+            skip_headers: impersonate_skip_headers.unwrap_or(false),
+            skip_http2: impersonate_skip_http2.unwrap_or(false),
+            os: impersonate_os,
+            mobile: mobile.unwrap_or(false),
+            request_context: request_context.map(String::from),
+            locale: locale.map(String::from),
+            keep_alive: http1_only == Some(true),
+            alpn,
+            cert_compression,
+            tls_ciphers,
+            tls_sigalgs,
+            tls_curves,
+            tls_extension_order,
+            http2_fingerprint: http2_fingerprint.map(String::from),
+            http2_priorities,
+            http2_headers_priority,
+            http2_settings: http2_settings.map(depythonize).transpose()?,
+        };
+        let impersonate_settings = settings_options.settings(impersonate.as_deref())?;
+
+        // Client_hints
+        let client_hints = match client_hints {
+            Some(true) => Some(Arc::new(ClientHints::new(settings_headers(
+                &impersonate_settings,
+            ))?)),
+            _ => None,
+        };
+
+        // Track_navigation
+        let track_navigation = track_navigation.unwrap_or(false);
+        let sends_sec_fetch_site = settings_headers(&impersonate_settings)
+            .is_some_and(|headers| headers.contains_key("sec-fetch-site"));
+        let navigation = (track_navigation && sends_sec_fetch_site)
+            .then(|| Arc::new(Navigation::new(referer.unwrap_or(true))));
+
+        // Http1_title_case
+        let impersonates_headers = impersonate_settings
+            .as_ref()
+            .is_some_and(|settings| settings.headers.is_some());
+        let http1_title_case = http1_title_case.unwrap_or(impersonates_headers);
+        if http1_title_case {
+            client_builder = client_builder.with_http1_builder(|builder| {
+                builder.title_case_headers(true);
+            });
         }
 
-        // Headers || Cookies
-        if headers.is_some() || cookies.is_some() {
+        if let Some(settings) = impersonate_settings {
+            client_builder = client_builder.impersonate_settings(settings);
+        }
+
+        // Headers || Cookies: replace the impersonation headers, also when it changes
+        let keep_headers = headers.is_some() || cookies.is_some();
+        if keep_headers {
             let headers = headers.unwrap_or_else(|| IndexMap::with_hasher(RandomState::default()));
             let mut headers_headermap = headers.to_headermap();
             if let Some(cookies) = cookies {
@@ -167,7 +761,14 @@ impl Client {
         // Proxy
         let proxy = proxy.or_else(|| std::env::var("PRIMP_PROXY").ok());
         if let Some(proxy) = &proxy {
-            client_builder = client_builder.proxy(rquest::Proxy::all(proxy)?);
+            if proxy == "system" {
+                // rquest detects env vars, WinINET and macOS SystemConfiguration proxies by itself
+                if let Some(system_proxy) = get_system_proxy() {
+                    client_builder = client_builder.proxy(build_proxy(&system_proxy)?);
+                }
+            } else {
+                client_builder = client_builder.proxy(build_proxy(proxy)?);
+            }
         }
 
         // Timeout
@@ -175,23 +776,84 @@ impl Client {
             client_builder = client_builder.timeout(Duration::from_secs_f64(seconds));
         }
 
-        // Redirects
-        if follow_redirects.unwrap_or(true) {
-            client_builder = client_builder.redirect(Policy::limited(max_redirects.unwrap_or(20)));
-        } else {
-            client_builder = client_builder.redirect(Policy::none());
+        // Default_encoding
+        let default_encoding = default_encoding.unwrap_or_else(|| "utf-8".to_string());
+        if Encoding::for_label(default_encoding.as_bytes()).is_none() {
+            return Err(anyhow!("Unsupported default_encoding: {default_encoding}"));
         }
 
-        // Ca_cert_file. BEFORE!!! verify (fn load_ca_certs() reads env var PRIMP_CA_BUNDLE)
-        if let Some(ca_bundle_path) = &ca_cert_file {
-            std::env::set_var("PRIMP_CA_BUNDLE", ca_bundle_path);
-        }
+        // Redirects, followed in `send_future` to control the request of each hop
+        client_builder = client_builder.redirect(Policy::none());
+        let redirects = follow_redirects
+            .unwrap_or(true)
+            .then(|| {
+                Redirects::new(
+                    max_redirects.unwrap_or(20),
+                    redirect_policy,
+                    referer.unwrap_or(true),
+                    redirect_method_policy.as_deref(),
+                    redirect_forward_headers,
+                    redirect_strip_headers,
+                )
+            })
+            .transpose()?
+            .map(Arc::new);
 
-        // Verify
-        if verify.unwrap_or(true) {
-            client_builder = client_builder.root_certs_store(load_ca_certs);
-        } else {
-            client_builder = client_builder.danger_accept_invalid_certs(true);
+        // Ca_cert || Ca_cert_file
+        let ca_pem = match (ca_cert, ca_cert_file) {
+            (Some(ca_cert), _) => Some(ca_cert.into_bytes()),
+            (None, Some(ca_cert_file)) => Some(
+                std::fs::read(&ca_cert_file)
+                    .map_err(|e| anyhow!("Failed to read ca_cert_file {ca_cert_file}: {e}"))?,
+            ),
+            (None, None) => None,
+        };
+
+        // Verify || Verify_hostname || Session_resumption || Ech_grease || Permute_extensions
+        let trust = match verify.unwrap_or(Verify::Bool(true)) {
+            Verify::Bool(true) => match ca_pem {
+                Some(ca_pem) => Trust::Pem(ca_pem),
+                None => Trust::Bundled,
+            },
+            Verify::Bool(false) => Trust::Disabled,
+            Verify::Mode(mode) if mode == "system" => {
+                if cfg!(any(windows, target_os = "macos")) {
+                    return Err(anyhow!(
+                        "verify=\"system\" is only supported on Linux and BSD, use verify=True or a CA bundle path"
+                    ));
+                }
+                if load_system_ca_certs().is_none() {
+                    return Err(anyhow!("No system certificate store found"));
+                }
+                Trust::System
+            }
+            Verify::Mode(ca_bundle_path) => Trust::Pem(
+                std::fs::read(&ca_bundle_path)
+                    .map_err(|e| anyhow!("Failed to read verify {ca_bundle_path}: {e}"))?,
+            ),
+        };
+        let tls_options = TlsOptions {
+            trust,
+            verify_hostname: verify_hostname.unwrap_or(true),
+            session_resumption,
+            ech_grease,
+            permute_extensions,
+        };
+        match tls_options.root_certs_store()? {
+            Some(store) => client_builder = client_builder.root_certs_store(store),
+            None => client_builder = client_builder.danger_accept_invalid_certs(true),
+        }
+        if !tls_options.verify_hostname {
+            client_builder = client_builder.verify_hostname(false);
+        }
+        if let Some(enabled) = tls_options.session_resumption {
+            client_builder = client_builder.pre_shared_key(enabled);
+        }
+        if let Some(enabled) = tls_options.ech_grease {
+            client_builder = client_builder.enable_ech_grease(enabled);
+        }
+        if let Some(enabled) = tls_options.permute_extensions {
+            client_builder = client_builder.permute_extensions(enabled);
         }
 
         // Https_only
@@ -199,15 +861,147 @@ impl Client {
             client_builder = client_builder.https_only(true);
         }
 
-        // Http2_only
-        if let Some(true) = http2_only {
-            client_builder = client_builder.http2_only();
+        // Http1_only || Http2_only
+        match (http1_only, http2_only) {
+            (Some(true), Some(true)) => {
+                return Err(anyhow!("http1_only and http2_only are mutually exclusive"));
+            }
+            (Some(true), _) => client_builder = client_builder.http1_only(),
+            (_, Some(true)) => client_builder = client_builder.http2_only(),
+            _ => {}
         }
 
-        let client = Arc::new(Mutex::new(client_builder.build()?));
+        // Ip_version, Debug
+        let debug = debug.unwrap_or(false);
+        let ip_version = ip_version
+            .map(IpVersion::from_str)
+            .transpose()?
+            .unwrap_or_default();
+        if ip_version != IpVersion::Auto || debug {
+            client_builder =
+                client_builder.dns_resolver(Arc::new(FamilyResolver::new(ip_version, debug)));
+        }
+
+        // Local_address
+        if let Some(local_address) = local_address {
+            let local_address = IpAddr::from_str(local_address)
+                .map_err(|_| anyhow!("Invalid local_address: {local_address}"))?;
+            client_builder = client_builder.local_address(local_address);
+        }
+
+        // Interface, or the one of the zone ID of an IPv6 link-local proxy
+        let proxy_zone = proxy.as_deref().and_then(|proxy| split_zone_id(proxy).1);
+        let interface = match (interface, proxy_zone) {
+            (None, Some(zone)) => Some(zone_interface(&zone)?),
+            (interface, _) => interface,
+        };
+        if let Some(interface) = interface {
+            #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+            {
+                client_builder = client_builder.interface(interface);
+            }
+            #[cfg(not(any(target_os = "android", target_os = "fuchsia", target_os = "linux")))]
+            {
+                return Err(anyhow!(
+                    "Binding to interface {interface} is only supported on Linux"
+                ));
+            }
+        }
+
+        // Tcp_nodelay
+        client_builder = client_builder.tcp_nodelay(tcp_nodelay.unwrap_or(true));
+
+        // Tcp_keepalive
+        if let Some(seconds) = tcp_keepalive {
+            client_builder = client_builder.tcp_keepalive(Duration::from_secs_f64(seconds));
+        }
+
+        // Pool_idle_timeout
+        if let Some(seconds) = pool_idle_timeout {
+            client_builder = client_builder.pool_idle_timeout(Duration::from_secs_f64(seconds));
+        }
+
+        // Pool_max_idle_per_host
+        if let Some(max) = pool_max_idle_per_host {
+            client_builder = client_builder.pool_max_idle_per_host(max);
+        }
+
+        // Http2_keep_alive_interval || Http2_keep_alive_timeout || Http2_keep_alive_while_idle
+        if let Some(seconds) = http2_keep_alive_interval {
+            client_builder = client_builder.with_http2_builder(|builder| {
+                builder.keep_alive_interval(Duration::from_secs_f64(seconds));
+                if let Some(seconds) = http2_keep_alive_timeout {
+                    builder.keep_alive_timeout(Duration::from_secs_f64(seconds));
+                }
+                if let Some(enabled) = http2_keep_alive_while_idle {
+                    builder.keep_alive_while_idle(enabled);
+                }
+            });
+        }
+
+        let mut client = client_builder.build()?;
+
+        // Hpack_never_index
+        let hpack_never_index = hpack_never_index
+            .map(|names| parse_never_index(&names))
+            .transpose()?;
+        if let Some(names) = &hpack_never_index {
+            set_never_index(client.headers_mut(), names);
+        }
+
+        // Impersonate (per host): clones of the client share its cookie store and connection pool
+        let impersonate_hosts = impersonate_hosts
+            .map(|hosts| {
+                hosts
+                    .into_iter()
+                    .map(|(host, name)| {
+                        let mut host_client = client.clone();
+                        set_impersonation(
+                            &mut host_client,
+                            settings_options.settings(Some(&name))?,
+                            &tls_options,
+                            keep_headers,
+                            hpack_never_index.as_deref(),
+                        )?;
+                        Ok((ascii_host(&host), host_client))
+                    })
+                    .collect::<Result<HashMap<_, _>>>()
+            })
+            .transpose()?
+            .map(Arc::new);
+
+        let client = Arc::new(Mutex::new(client));
 
         Ok(Client {
             client,
+            stats: Arc::new(ClientStats::default()),
+            client_hints,
+            navigation,
+            track_navigation,
+            referer: referer.unwrap_or(true),
+            impersonate_hosts,
+            settings_options,
+            tls_options,
+            keep_headers,
+            hpack_never_index: hpack_never_index.map(Arc::new),
+            http1_title_case,
+            retry_stale_connections: retry_stale_connections.unwrap_or(true),
+            retries: retries.map(Arc::new),
+            redirects,
+            error_body_snippet: error_body_snippet.unwrap_or_default(),
+            hooks: hooks.map(Hooks::new).transpose()?.map(Arc::new),
+            transport,
+            har: None,
+            debug,
+            on_metrics: on_metrics.map(Arc::new),
+            normalize_urls: normalize_urls.unwrap_or(true),
+            local_urls: local_urls.unwrap_or(false),
+            https_only: https_only.unwrap_or(false),
+            warn_body_methods: warn_body_methods.unwrap_or(true),
+            default_encoding,
+            impersonate,
+            pid: AtomicU32::new(std::process::id()),
+            used: AtomicBool::new(false),
             auth,
             auth_bearer,
             params,
@@ -269,13 +1063,229 @@ impl Client {
 
     #[setter]
     pub fn set_proxy(&mut self, proxy: String) -> Result<()> {
+        if proxy == "system" {
+            return Err(anyhow!(
+                "System proxy detection is only available via Client(proxy=\"system\")"
+            ));
+        }
+        if split_zone_id(&proxy).1.is_some() {
+            return Err(anyhow!(
+                "A proxy with an IPv6 zone ID is only supported via Client(proxy=...)"
+            ));
+        }
         let mut client = self.client.lock().unwrap();
-        let rproxy = rquest::Proxy::all(proxy.clone())?;
+        let rproxy = build_proxy(&proxy)?;
         client.set_proxies(vec![rproxy]);
         self.proxy = Some(proxy);
         Ok(())
     }
 
+    #[getter]
+    pub fn get_impersonate(&self) -> Option<String> {
+        self.impersonate.clone()
+    }
+
+    /// Switch the impersonation in place: the cookie store and the connection pool are kept,
+    /// pooled connections keep the TLS handshake they were opened with.
+    /// The client options editing the impersonation (`impersonate_os`, `mobile`, `locale`,
+    /// `impersonate_profile`, TLS and HTTP/2 overrides, ...) are applied to the new one.
+    #[setter]
+    pub fn set_impersonate(&mut self, impersonate: &str) -> Result<()> {
+        self.check_fork()?;
+        let settings = self.settings_options.settings(Some(impersonate))?;
+        let client_hints = match &self.client_hints {
+            Some(_) => Some(Arc::new(ClientHints::new(settings_headers(&settings))?)),
+            None => None,
+        };
+        let sends_sec_fetch_site = settings_headers(&settings)
+            .is_some_and(|headers| headers.contains_key("sec-fetch-site"));
+        let navigation = match &self.navigation {
+            _ if !(self.track_navigation && sends_sec_fetch_site) => None,
+            Some(navigation) => Some(Arc::clone(navigation)),
+            None => Some(Arc::new(Navigation::new(self.referer))),
+        };
+
+        let mut client = self.client.lock().unwrap();
+        set_impersonation(
+            &mut client,
+            settings,
+            &self.tls_options,
+            self.keep_headers,
+            self.hpack_never_index.as_deref().map(Vec::as_slice),
+        )?;
+        self.client_hints = client_hints;
+        self.navigation = navigation;
+        self.impersonate = Some(impersonate.to_string());
+        Ok(())
+    }
+
+    /// Returns request statistics of the client.
+    ///
+    /// * `requests_total` - Number of requests sent.
+    /// * `requests_in_flight` - Number of requests currently in progress.
+    /// * `requests_failed` - Number of requests that failed with an error.
+    /// * `bytes_sent` - Total size of the request bodies sent.
+    /// * `bytes_received` - Total size of the response bodies received.
+    fn stats(&self) -> IndexMap<String, u64, RandomState> {
+        self.stats.to_indexmap()
+    }
+
+    /// Returns the fingerprints the client configuration is expected to produce, computed locally.
+    ///
+    /// * `akamai_h2` - Akamai HTTP/2 fingerprint of the impersonated browser's HTTP/2 settings,
+    ///         None without impersonation (the HTTP/2 stack defaults are used).
+    /// * `ja4` - JA4 of the first ClientHello to a domain name, from the cipher, signature algorithm, curve
+    ///         and ALPN lists and the TLS options. None without impersonation or when the ClientHello
+    ///         may be padded (no post-quantum key share first), as the extensions then depend on the host.
+    /// * `ja4h` - JA4H of a GET request with the default headers of the client, over HTTP/2 when ALPN
+    ///         prefers it (HTTPS), without the cookies of the cookie store and the request headers.
+    fn expected_fingerprints(&self) -> Result<IndexMap<String, Option<String>, RandomState>> {
+        let mut settings = self
+            .settings_options
+            .settings(self.impersonate.as_deref())?;
+        // `keep_alive` is set by http1_only, which only offers HTTP/1.1 with ALPN
+        let http1_only = self.settings_options.keep_alive;
+        if let Some(settings) = &mut settings {
+            self.tls_options.apply_client_hello(&mut settings.tls);
+            if http1_only {
+                settings.tls.alpn_protos = AlpnProtos::Http1;
+            }
+        }
+        let akamai_h2 = settings
+            .as_ref()
+            .and_then(|settings| settings.http2.as_ref())
+            .map(akamai_h2);
+        let ja4 = settings.as_ref().and_then(|settings| ja4(&settings.tls));
+
+        let alpn = settings
+            .as_ref()
+            .map_or(AlpnProtos::default(), |settings| settings.tls.alpn_protos);
+        let http2 = !http1_only && alpn != AlpnProtos::Http1;
+        let headers = self.client.lock().unwrap().headers_mut().clone();
+        let headers_order = settings
+            .as_ref()
+            .and_then(|settings| settings.headers_order.as_deref());
+        let headers = request_headers(headers, headers_order, http2, self.http1_title_case);
+
+        Ok(IndexMap::from_iter([
+            ("akamai_h2".to_string(), akamai_h2),
+            ("ja4".to_string(), ja4),
+            ("ja4h".to_string(), Some(ja4h(http2, &headers))),
+        ]))
+    }
+
+    /// Starts recording the requests and responses of the client, discarding a previous recording.
+    ///
+    /// Requests, responses with their body, headers, cookies and timings are kept in memory
+    /// until `export_har()` writes them to a HAR file.
+    fn start_har_recording(&mut self) {
+        self.har = Some(Arc::new(HarRecorder::default()));
+    }
+
+    /// Writes the recorded requests and responses to a HAR 1.2 file, the recording continues.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the HAR file.
+    fn export_har(&self, path: &str) -> Result<()> {
+        let har = self
+            .har
+            .as_ref()
+            .ok_or_else(|| anyhow!("HAR recording is not started, call start_har_recording()"))?;
+        let har = serde_json::to_vec_pretty(&har.to_har())?;
+        std::fs::write(path, har).map_err(|e| anyhow!("Failed to write {path}: {e}"))?;
+        Ok(())
+    }
+
+    /// Sends many requests concurrently and returns their responses in order.
+    ///
+    /// The requests run together on the Tokio runtime with the GIL released, the GIL is only taken
+    /// to read the requests and build the responses.
+    ///
+    /// # Arguments
+    ///
+    /// * `requests` - A list of dicts with the arguments of `request()`: "method" (default "GET"), "url", "params",
+    ///         "headers", "cookies", "content", "data", "json", "files", "auth", "auth_bearer", "timeout", "http_version".
+    ///
+    /// # Returns
+    ///
+    /// * A list with a `Response` or the exception raised by the request, for each request.
+    fn batch(&self, py: Python, requests: Vec<Bound<'_, PyDict>>) -> Result<Vec<PyObject>> {
+        let mut futures = Vec::with_capacity(requests.len());
+        for request in &requests {
+            for key in request.keys() {
+                let key: String = key.extract()?;
+                if !BATCH_KEYS.contains(&key.as_str()) {
+                    return Err(anyhow!("Invalid batch request: unknown key {key}"));
+                }
+            }
+            let method: Option<String> = batch_item(request, "method")?;
+            let url: String = batch_item(request, "url")?
+                .ok_or_else(|| anyhow!("Invalid batch request: url is required"))?;
+            let data_value: Option<Value> = request
+                .get_item("data")?
+                .filter(|data| !data.is_none())
+                .map(|data| depythonize(&data))
+                .transpose()?;
+            let json_value: Option<Value> = request
+                .get_item("json")?
+                .filter(|json| !json.is_none())
+                .map(|json| depythonize(&json))
+                .transpose()?;
+            let http_version: Option<String> = batch_item(request, "http_version")?;
+            futures.push(self.request_future(
+                method.as_deref().unwrap_or("GET"),
+                &url,
+                batch_item(request, "params")?,
+                batch_item(request, "headers")?,
+                batch_item(request, "cookies")?,
+                batch_item(request, "content")?,
+                data_value,
+                json_value,
+                batch_item(request, "files")?,
+                batch_item(request, "auth")?,
+                batch_item(request, "auth_bearer")?,
+                batch_item(request, "timeout")?,
+                http_version.as_deref(),
+            )?);
+        }
+
+        let handles: Vec<_> = futures.into_iter().map(|f| runtime().spawn(f)).collect();
+        let abort_handles: Vec<_> = handles.iter().map(|h| h.abort_handle()).collect();
+        let results = block_on(py, async {
+            let mut results = Vec::with_capacity(handles.len());
+            for handle in handles {
+                results.push(match handle.await {
+                    Ok(result) => result,
+                    Err(e) => Err(anyhow!("Batch request task failed: {e}")),
+                });
+            }
+            results
+        })
+        .inspect_err(|_| abort_handles.iter().for_each(|h| h.abort()))?;
+
+        results
+            .into_iter()
+            .map(|result| match result {
+                Ok(parts) => {
+                    let response = Response::from_parts(
+                        py,
+                        parts,
+                        self.error_body_snippet,
+                        &self.default_encoding,
+                    )?;
+                    Ok(self
+                        .run_response_hooks(py, Py::new(py, response)?)?
+                        .into_any())
+                }
+                Err(e) => {
+                    self.stats.add_failed();
+                    Ok(request_error(py, e).into_value(py).into_any())
+                }
+            })
+            .collect()
+    }
+
     /// Constructs an HTTP request with the given method, URL, and optionally sets a timeout, headers, and query parameters.
     /// Sends the request and returns a `Response` object containing the server's response.
     ///
@@ -289,10 +1299,12 @@ impl Client {
     /// * `content` - The content to send in the request body as bytes. Default is None.
     /// * `data` - The form data to send in the request body. Default is None.
     /// * `json` -  A JSON serializable object to send in the request body. Default is None.
-    /// * `files` - A map of file fields to file paths to be sent as multipart/form-data. Default is None.
+    /// * `files` - A map of file fields to file paths to be sent as multipart/form-data. Streamed from the disk,
+    ///         and read again to be sent after a 307/308 redirect. Default is None.
     /// * `auth` - A tuple containing the username and an optional password for basic authentication. Default is None.
     /// * `auth_bearer` - A string representing the bearer token for bearer token authentication. Default is None.
     /// * `timeout` - The timeout for the request in seconds. Default is 30.
+    /// * `http_version` - HTTP version to use for this request: "1.1" or "2". Default is None.
     ///
     /// # Returns
     ///
@@ -302,7 +1314,7 @@ impl Client {
     ///
     /// * `PyException` - If there is an error making the request.
     #[pyo3(signature = (method, url, params=None, headers=None, cookies=None, content=None,
-        data=None, json=None, files=None, auth=None, auth_bearer=None, timeout=None))]
+        data=None, json=None, files=None, auth=None, auth_bearer=None, timeout=None, http_version=None))]
     fn request(
         &self,
         py: Python,
@@ -318,111 +1330,100 @@ impl Client {
         auth: Option<(String, Option<String>)>,
         auth_bearer: Option<String>,
         timeout: Option<f64>,
-    ) -> Result<Response> {
-        let client = Arc::clone(&self.client);
-        let method = Method::from_bytes(method.as_bytes())?;
-        let is_post_put_patch = matches!(method, Method::POST | Method::PUT | Method::PATCH);
-        let params = params.or_else(|| self.params.clone());
+        http_version: Option<&str>,
+    ) -> Result<Py<Response>> {
         let data_value: Option<Value> = data.map(depythonize).transpose()?;
         let json_value: Option<Value> = json.map(depythonize).transpose()?;
-        let auth = auth.or(self.auth.clone());
-        let auth_bearer = auth_bearer.or(self.auth_bearer.clone());
-        let timeout: Option<f64> = timeout.or(self.timeout);
-
-        let future = async {
-            // Create request builder
-            let mut request_builder = client.lock().unwrap().request(method, url);
-
-            // Params
-            if let Some(params) = params {
-                request_builder = request_builder.query(&params);
-            }
-
-            // Headers
-            if let Some(headers) = headers {
-                request_builder = request_builder.headers(headers.to_headermap());
-            }
-
-            // Cookies
-            if let Some(cookies) = cookies {
-                request_builder =
-                    request_builder.header(COOKIE, HeaderValue::from_str(&cookies.to_string())?);
-            }
-
-            // Only if method POST || PUT || PATCH
-            if is_post_put_patch {
-                // Content
-                if let Some(content) = content {
-                    request_builder = request_builder.body(content);
-                }
-                // Data
-                if let Some(form_data) = data_value {
-                    request_builder = request_builder.form(&form_data);
-                }
-                // Json
-                if let Some(json_data) = json_value {
-                    request_builder = request_builder.json(&json_data);
-                }
-                // Files
-                if let Some(files) = files {
-                    let mut form = multipart::Form::new();
-                    for (file_name, file_path) in files {
-                        let file = File::open(file_path).await?;
-                        let stream = FramedRead::new(file, BytesCodec::new());
-                        let file_body = Body::wrap_stream(stream);
-                        let part = multipart::Part::stream(file_body).file_name(file_name.clone());
-                        form = form.part(file_name, part);
-                    }
-                    request_builder = request_builder.multipart(form);
-                }
-            }
-
-            // Auth
-            if let Some((username, password)) = auth {
-                request_builder = request_builder.basic_auth(username, password);
-            } else if let Some(token) = auth_bearer {
-                request_builder = request_builder.bearer_auth(token);
-            }
-
-            // Timeout
-            if let Some(seconds) = timeout {
-                request_builder = request_builder.timeout(Duration::from_secs_f64(seconds));
-            }
+        let future = self
+            .request_future(
+                method,
+                url,
+                params,
+                headers,
+                cookies,
+                content,
+                data_value,
+                json_value,
+                files,
+                auth,
+                auth_bearer,
+                timeout,
+                http_version,
+            )
+            .map_err(|e| request_error(py, e))?;
+        self.run_future(py, future)
+    }
 
-            // Send the request and await the response
-            let resp = request_builder.send().await?;
-
-            // Response items
-            let cookies: IndexMapSSR = resp
-                .cookies()
-                .map(|cookie| (cookie.name().to_string(), cookie.value().to_string()))
-                .collect();
-            let headers: IndexMapSSR = resp.headers().to_indexmap();
-            let status_code = resp.status().as_u16();
-            let url = resp.url().to_string();
-            let buf = resp.bytes().await?;
-
-            log::info!("response: {} {} {}", url, status_code, buf.len());
-            Ok((buf, cookies, headers, status_code, url))
-        };
+    /// Builds a request like `request()` without sending it.
+    ///
+    /// The `PreparedRequest` has the final URL with the params, the headers including the client
+    /// and impersonation defaults, cookies and auth, and the body. It can be modified, e.g. to sign it,
+    /// then sent with `send()`. Requests with `files` can not be prepared.
+    ///
+    /// # Returns
+    ///
+    /// * `PreparedRequest` - The request, with `method`, `url`, `headers`, `content`, `body_length`,
+    ///         `timeout` and `http_version` attributes.
+    #[pyo3(signature = (method, url, params=None, headers=None, cookies=None, content=None,
+        data=None, json=None, files=None, auth=None, auth_bearer=None, timeout=None, http_version=None))]
+    fn build_request(
+        &self,
+        py: Python,
+        method: &str,
+        url: &str,
+        params: Option<IndexMapSSR>,
+        headers: Option<IndexMapSSR>,
+        cookies: Option<IndexMapSSR>,
+        content: Option<Vec<u8>>,
+        data: Option<&Bound<'_, PyAny>>,
+        json: Option<&Bound<'_, PyAny>>,
+        files: Option<IndexMap<String, String>>,
+        auth: Option<(String, Option<String>)>,
+        auth_bearer: Option<String>,
+        timeout: Option<f64>,
+        http_version: Option<&str>,
+    ) -> Result<PreparedRequest> {
+        let data_value: Option<Value> = data.map(depythonize).transpose()?;
+        let json_value: Option<Value> = json.map(depythonize).transpose()?;
+        let timeout = timeout.or(self.timeout);
+        let future = self.prepare_future(
+            method,
+            url,
+            params,
+            headers,
+            cookies,
+            content,
+            data_value,
+            json_value,
+            files,
+            auth,
+            auth_bearer,
+            timeout,
+            http_version,
+        )?;
+        let (client, request, _) = block_on(py, future)??;
+        let http_version = http_version.map(|version| version.to_string());
+        PreparedRequest::new(&client, &request, timeout, http_version)
+    }
 
-        // Execute an async future, releasing the Python GIL for concurrency.
-        // Use Tokio global runtime to block on the future.
-        let result: Result<(Bytes, IndexMapSSR, IndexMapSSR, u16, String), Error> =
-            py.allow_threads(|| RUNTIME.block_on(future));
-        let (f_buf, f_cookies, f_headers, f_status_code, f_url) = result?;
-
-        Ok(Response {
-            content: PyBytes::new(py, &f_buf).unbind(),
-            cookies: f_cookies,
-            encoding: String::new(),
-            headers: f_headers,
-            status_code: f_status_code,
-            url: f_url,
-        })
+    /// Sends a request built by `build_request()`.
+    ///
+    /// The headers of the `PreparedRequest` are sent as they are, except that the client still adds
+    /// its default headers and cookies that were removed from them.
+    fn send(&self, py: Python, request: PyRef<PreparedRequest>) -> Result<Py<Response>> {
+        let future = request
+            .to_request(&self.request_client(&request.url))
+            .and_then(|(client, built)| {
+                let (method, url) = (built.method().to_string(), built.url().to_string());
+                let prepare = async move { Ok::<_, Error>((client, built, None)) };
+                self.send_future(&method, &url, request.timeout, prepare)
+            })
+            .map_err(|e| request_error(py, e))?;
+        self.run_future(py, future)
     }
 
-    #[pyo3(signature = (url, params=None, headers=None, cookies=None, auth=None, auth_bearer=None, timeout=None))]
+    #[pyo3(signature = (url, params=None, headers=None, cookies=None, auth=None, auth_bearer=None, timeout=None,
+        http_version=None, content=None, data=None, json=None))]
     fn get(
         &self,
         py: Python,
@@ -433,7 +1434,11 @@ impl Client {
         auth: Option<(String, Option<String>)>,
         auth_bearer: Option<String>,
         timeout: Option<f64>,
-    ) -> Result<Response> {
+        http_version: Option<&str>,
+        content: Option<Vec<u8>>,
+        data: Option<&Bound<'_, PyAny>>,
+        json: Option<&Bound<'_, PyAny>>,
+    ) -> Result<Py<Response>> {
         self.request(
             py,
             "GET",
@@ -441,17 +1446,18 @@ impl Client {
             params,
             headers,
             cookies,
-            None,
-            None,
-            None,
+            content,
+            data,
+            json,
             None,
             auth,
             auth_bearer,
             timeout,
+            http_version,
         )
     }
 
-    #[pyo3(signature = (url, params=None, headers=None, cookies=None, auth=None, auth_bearer=None, timeout=None))]
+    #[pyo3(signature = (url, params=None, headers=None, cookies=None, auth=None, auth_bearer=None, timeout=None, http_version=None))]
     fn head(
         &self,
         py: Python,
@@ -462,7 +1468,8 @@ impl Client {
         auth: Option<(String, Option<String>)>,
         auth_bearer: Option<String>,
         timeout: Option<f64>,
-    ) -> Result<Response> {
+        http_version: Option<&str>,
+    ) -> Result<Py<Response>> {
         self.request(
             py,
             "HEAD",
@@ -477,10 +1484,11 @@ impl Client {
             auth,
             auth_bearer,
             timeout,
+            http_version,
         )
     }
 
-    #[pyo3(signature = (url, params=None, headers=None, cookies=None, auth=None, auth_bearer=None, timeout=None))]
+    #[pyo3(signature = (url, params=None, headers=None, cookies=None, auth=None, auth_bearer=None, timeout=None, http_version=None))]
     fn options(
         &self,
         py: Python,
@@ -491,7 +1499,8 @@ impl Client {
         auth: Option<(String, Option<String>)>,
         auth_bearer: Option<String>,
         timeout: Option<f64>,
-    ) -> Result<Response> {
+        http_version: Option<&str>,
+    ) -> Result<Py<Response>> {
         self.request(
             py,
             "OPTIONS",
@@ -506,10 +1515,12 @@ impl Client {
             auth,
             auth_bearer,
             timeout,
+            http_version,
         )
     }
 
-    #[pyo3(signature = (url, params=None, headers=None, cookies=None, auth=None, auth_bearer=None, timeout=None))]
+    #[pyo3(signature = (url, params=None, headers=None, cookies=None, auth=None, auth_bearer=None, timeout=None,
+        http_version=None, content=None, data=None, json=None))]
     fn delete(
         &self,
         py: Python,
@@ -520,7 +1531,11 @@ impl Client {
         auth: Option<(String, Option<String>)>,
         auth_bearer: Option<String>,
         timeout: Option<f64>,
-    ) -> Result<Response> {
+        http_version: Option<&str>,
+        content: Option<Vec<u8>>,
+        data: Option<&Bound<'_, PyAny>>,
+        json: Option<&Bound<'_, PyAny>>,
+    ) -> Result<Py<Response>> {
         self.request(
             py,
             "DELETE",
@@ -528,18 +1543,19 @@ impl Client {
             params,
             headers,
             cookies,
-            None,
-            None,
-            None,
+            content,
+            data,
+            json,
             None,
             auth,
             auth_bearer,
             timeout,
+            http_version,
         )
     }
 
     #[pyo3(signature = (url, params=None, headers=None, cookies=None, content=None, data=None,
-        json=None, files=None, auth=None, auth_bearer=None, timeout=None))]
+        json=None, files=None, auth=None, auth_bearer=None, timeout=None, http_version=None))]
     fn post(
         &self,
         py: Python,
@@ -554,7 +1570,8 @@ impl Client {
         auth: Option<(String, Option<String>)>,
         auth_bearer: Option<String>,
         timeout: Option<f64>,
-    ) -> Result<Response> {
+        http_version: Option<&str>,
+    ) -> Result<Py<Response>> {
         self.request(
             py,
             "POST",
@@ -569,11 +1586,12 @@ impl Client {
             auth,
             auth_bearer,
             timeout,
+            http_version,
         )
     }
 
     #[pyo3(signature = (url, params=None, headers=None, cookies=None, content=None, data=None,
-        json=None, files=None, auth=None, auth_bearer=None, timeout=None))]
+        json=None, files=None, auth=None, auth_bearer=None, timeout=None, http_version=None))]
     fn put(
         &self,
         py: Python,
@@ -588,7 +1606,8 @@ impl Client {
         auth: Option<(String, Option<String>)>,
         auth_bearer: Option<String>,
         timeout: Option<f64>,
-    ) -> Result<Response> {
+        http_version: Option<&str>,
+    ) -> Result<Py<Response>> {
         self.request(
             py,
             "PUT",
@@ -603,11 +1622,12 @@ impl Client {
             auth,
             auth_bearer,
             timeout,
+            http_version,
         )
     }
 
     #[pyo3(signature = (url, params=None, headers=None, cookies=None, content=None, data=None,
-        json=None, files=None, auth=None, auth_bearer=None, timeout=None))]
+        json=None, files=None, auth=None, auth_bearer=None, timeout=None, http_version=None))]
     fn patch(
         &self,
         py: Python,
@@ -622,7 +1642,8 @@ impl Client {
         auth: Option<(String, Option<String>)>,
         auth_bearer: Option<String>,
         timeout: Option<f64>,
-    ) -> Result<Response> {
+        http_version: Option<&str>,
+    ) -> Result<Py<Response>> {
         self.request(
             py,
             "PATCH",
@@ -637,15 +1658,632 @@ impl Client {
             auth,
             auth_bearer,
             timeout,
+            http_version,
+        )
+    }
+}
+
+impl Client {
+    /// A client with the default options, used by the module-level request functions.
+    fn with_defaults(
+        impersonate: Option<ImpersonateArg>,
+        verify: Option<Verify>,
+        ca_cert_file: Option<String>,
+    ) -> Result<Self> {
+        Client::new(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            impersonate,
+            None,
+            None,
+            verify,
+            ca_cert_file,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
         )
     }
+
+    /// Run the response hooks, a hook can replace the response.
+    fn run_response_hooks(&self, py: Python, response: Py<Response>) -> PyResult<Py<Response>> {
+        match &self.hooks {
+            Some(hooks) => hooks.run_response(py, response),
+            None => Ok(response),
+        }
+    }
+
+    /// Adopt the client in a process created by `os.fork`, unless the parent already used it:
+    /// its pooled connections are driven by tasks of the parent runtime and would hang in the child.
+    fn check_fork(&self) -> Result<()> {
+        let pid = std::process::id();
+        if self.pid.load(Ordering::Relaxed) != pid {
+            if self.used.load(Ordering::Relaxed) {
+                return Err(anyhow!(
+                    "Client was used before os.fork(), create a new Client in the child process"
+                ));
+            }
+            self.pid.store(pid, Ordering::Relaxed);
+        }
+        self.used.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// The client sending the requests to the host of `url`: its `impersonate` client or the default one.
+    fn request_client(&self, url: &str) -> rquest::Client {
+        let host_client = self.impersonate_hosts.as_ref().and_then(|hosts| {
+            let host = rquest::Url::parse(url)
+                .ok()?
+                .host_str()?
+                .to_ascii_lowercase();
+            hosts.get(&host).cloned()
+        });
+        host_client.unwrap_or_else(|| self.client.lock().unwrap().clone())
+    }
+
+    /// Block on a request future with the GIL released and build the `Response`.
+    fn run_future(
+        &self,
+        py: Python,
+        future: impl Future<Output = Result<ResponseParts>> + Send,
+    ) -> Result<Py<Response>> {
+        // Execute an async future, releasing the Python GIL for concurrency.
+        // Use Tokio global runtime to block on the future, aborting it on Ctrl-C.
+        let result = block_on(py, future)?;
+        if result.is_err() {
+            self.stats.add_failed();
+        }
+        let response = Response::from_parts(
+            py,
+            result.map_err(|e| request_error(py, e))?,
+            self.error_body_snippet,
+            &self.default_encoding,
+        )?;
+        Ok(self.run_response_hooks(py, Py::new(py, response)?)?)
+    }
+
+    /// Build the future sending a request and reading its response, detached from the client borrow.
+    fn request_future(
+        &self,
+        method: &str,
+        url: &str,
+        params: Option<IndexMapSSR>,
+        headers: Option<IndexMapSSR>,
+        cookies: Option<IndexMapSSR>,
+        content: Option<Vec<u8>>,
+        data_value: Option<Value>,
+        json_value: Option<Value>,
+        files: Option<IndexMap<String, String>>,
+        auth: Option<(String, Option<String>)>,
+        auth_bearer: Option<String>,
+        timeout: Option<f64>,
+        http_version: Option<&str>,
+    ) -> Result<impl Future<Output = Result<ResponseParts>> + Send + 'static> {
+        let timeout = timeout.or(self.timeout);
+        let prepare = self.prepare_future(
+            method,
+            url,
+            params,
+            headers,
+            cookies,
+            content,
+            data_value,
+            json_value,
+            files,
+            auth,
+            auth_bearer,
+            timeout,
+            http_version,
+        )?;
+        self.send_future(method, url, timeout, prepare)
+    }
+
+    /// Build the future creating a request, with the client sending it.
+    fn prepare_future(
+        &self,
+        method: &str,
+        url: &str,
+        params: Option<IndexMapSSR>,
+        headers: Option<IndexMapSSR>,
+        cookies: Option<IndexMapSSR>,
+        content: Option<Vec<u8>>,
+        data_value: Option<Value>,
+        json_value: Option<Value>,
+        files: Option<IndexMap<String, String>>,
+        auth: Option<(String, Option<String>)>,
+        auth_bearer: Option<String>,
+        timeout: Option<f64>,
+        http_version: Option<&str>,
+    ) -> Result<
+        impl Future<Output = Result<(rquest::Client, rquest::Request, Option<UploadFiles>)>>
+            + Send
+            + 'static,
+    > {
+        let (url, zone_id) = split_zone_id(url);
+        let url = url.as_ref();
+        if !self.normalize_urls {
+            check_exact_url(url)?;
+        }
+        // IPv6 zone ID: link-local addresses are only reachable through the interface of the zone
+        #[cfg_attr(
+            not(any(target_os = "android", target_os = "fuchsia", target_os = "linux")),
+            allow(unused_variables)
+        )]
+        let interface = match zone_id {
+            Some(zone)
+                if cfg!(any(
+                    target_os = "android",
+                    target_os = "fuchsia",
+                    target_os = "linux"
+                )) =>
+            {
+                Some(zone_interface(&zone)?)
+            }
+            Some(zone) => return Err(anyhow!("IPv6 zone IDs are only supported on Linux: {zone}")),
+            None => None,
+        };
+        let client = self.request_client(url);
+        let client_hints = self.client_hints.clone();
+        let navigation = self.navigation.clone();
+        let method = Method::from_bytes(method.as_bytes())?;
+        let has_body =
+            content.is_some() || data_value.is_some() || json_value.is_some() || files.is_some();
+        if self.warn_body_methods
+            && has_body
+            && matches!(
+                method,
+                Method::GET | Method::HEAD | Method::DELETE | Method::OPTIONS
+            )
+        {
+            log::warn!(
+                target: REQUEST_TARGET,
+                "{method} request with a body, which some servers and proxies ignore or reject: {url}"
+            );
+        }
+        let params = params.or_else(|| self.params.clone());
+        let auth = auth.or(self.auth.clone());
+        let auth_bearer = auth_bearer.or(self.auth_bearer.clone());
+        let http_version = http_version.map(parse_http_version).transpose()?;
+        let url = url.to_string();
+
+        Ok(async move {
+            // Create request builder, `client.request` rejects the local URLs (no host)
+            let mut request_builder = match rquest::Url::parse(&url) {
+                Ok(local_url) if local::is_local(&local_url) => {
+                    RequestBuilder::from_parts(client, rquest::Request::new(method, local_url))
+                }
+                _ => client.request(method, &url),
+            };
+
+            // Interface of the IPv6 zone ID
+            #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+            if let Some(interface) = interface {
+                request_builder = request_builder.interface(interface);
+            }
+
+            // Params
+            if let Some(params) = params {
+                request_builder = request_builder.query(&params);
+            }
+
+            // Headers
+            if let Some(headers) = headers {
+                request_builder = request_builder.headers(headers.to_headermap());
+            }
+
+            // Cookies
+            if let Some(cookies) = cookies {
+                request_builder =
+                    request_builder.header(COOKIE, HeaderValue::from_str(&cookies.to_string())?);
+            }
+
+            // Content
+            if let Some(content) = content {
+                request_builder = request_builder.body(content);
+            }
+            // Data
+            if let Some(form_data) = data_value {
+                request_builder = request_builder.form(&form_data);
+            }
+            // Json
+            if let Some(json_data) = json_value {
+                request_builder = request_builder.json(&json_data);
+            }
+            // Files
+            let upload = files.map(UploadFiles::new);
+            if let Some(upload) = &upload {
+                request_builder = request_builder.multipart(upload.form().await?);
+            }
+
+            // Auth
+            if let Some((username, password)) = auth {
+                request_builder = request_builder.basic_auth(username, password);
+            } else if let Some(token) = auth_bearer {
+                request_builder = request_builder.bearer_auth(token);
+            }
+
+            // Timeout
+            if let Some(seconds) = timeout {
+                request_builder = request_builder.timeout(Duration::from_secs_f64(seconds));
+            }
+
+            // Http_version
+            if let Some(version) = http_version {
+                request_builder = request_builder.version(version);
+            }
+
+            let (client, request) = request_builder.build_split();
+            let mut request = request?;
+            if let Some(client_hints) = &client_hints {
+                let origin = request.url().origin().ascii_serialization();
+                for (name, value) in &client_hints.headers(&origin) {
+                    if !request.headers().contains_key(name) {
+                        request.headers_mut().insert(name.clone(), value.clone());
+                    }
+                }
+            }
+            if let Some(navigation) = &navigation {
+                for (name, value) in &navigation.headers(request.url()) {
+                    if !request.headers().contains_key(name) {
+                        request.headers_mut().insert(name.clone(), value.clone());
+                    }
+                }
+            }
+            Ok((client, request, upload))
+        })
+    }
+
+    /// Build the future sending a request created by `prepare` and reading its response,
+    /// detached from the client borrow.
+    fn send_future<P>(
+        &self,
+        method: &str,
+        url: &str,
+        timeout: Option<f64>,
+        prepare: P,
+    ) -> Result<impl Future<Output = Result<ResponseParts>> + Send + 'static>
+    where
+        P: Future<Output = Result<(rquest::Client, rquest::Request, Option<UploadFiles>)>>
+            + Send
+            + 'static,
+    {
+        self.check_fork()?;
+        let stats = Arc::clone(&self.stats);
+        let client_hints = self.client_hints.clone();
+        let navigation = self.navigation.clone();
+        let hpack_never_index = self.hpack_never_index.clone();
+        let retry_stale_connections = self.retry_stale_connections;
+        let retries = self.retries.clone();
+        let redirects = self.redirects.clone();
+        let hooks = self.hooks.clone();
+        let transport = self.transport.clone();
+        let har = self.har.clone();
+        let debug = self.debug;
+        let on_metrics = self.on_metrics.clone();
+        let (local_urls, https_only) = (self.local_urls, self.https_only);
+        let url = url.to_string();
+
+        let context_method = method.to_string();
+        let request_id = next_request_id();
+        log::debug!(target: REQUEST_TARGET, "request_id={request_id} request: {method} {url}");
+        Ok(async move {
+            let mut attempt = 0;
+            let mut metrics = RequestMetrics::default();
+            let request_start = Instant::now();
+            let result = async {
+                let _in_flight = stats.start_request();
+
+                let (client, mut request, upload) = prepare.await?;
+                if let Some(hooks) = &hooks {
+                    hooks.run_request(&mut request)?;
+                }
+                if let Some(names) = &hpack_never_index {
+                    set_never_index(request.headers_mut(), names);
+                }
+                if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+                    stats.add_bytes_sent(body.len());
+                    metrics.bytes_sent = body.len();
+                }
+                let headers_sent =
+                    (debug || har.is_some()).then(|| sent_headers(&client, &request));
+                if let (true, Some(headers)) = (debug, &headers_sent) {
+                    log_request(&request, headers);
+                }
+                let har_request = har
+                    .as_ref()
+                    .zip(headers_sent.as_ref())
+                    .map(|(_, headers)| har_request(&request, headers));
+                let (started, start) = (SystemTime::now(), Instant::now());
+                let local_response = match &transport {
+                    Some(transport) => Some(("mock", transport.respond(&request).await?)),
+                    None if local::is_local(request.url()) => {
+                        local::check(request.url(), local_urls, https_only)?;
+                        Some(("local", local::respond(&request).await?))
+                    }
+                    None => None,
+                };
+                if let Some((kind, (buf, headers, status_code, url, history))) = local_response {
+                    stats.add_bytes_received(buf.len());
+                    metrics.url = Some(url.clone());
+                    metrics.status_code = Some(status_code);
+                    metrics.bytes_received = buf.len();
+                    metrics.wait = Some(start.elapsed());
+                    metrics.receive = Some(Duration::ZERO);
+                    if debug {
+                        log_response(None, Version::HTTP_11, status_code, &headers);
+                    }
+                    if let (Some(har), Some(har_request)) = (&har, har_request) {
+                        let har_response =
+                            har_response(Version::HTTP_11, status_code, &headers, &buf);
+                        har.record(
+                            started,
+                            har_request,
+                            har_response,
+                            start.elapsed(),
+                            Duration::ZERO,
+                        );
+                    }
+                    log::info!(
+                        target: REQUEST_TARGET,
+                        "request_id={request_id} {kind} response: {url} {status_code} {}",
+                        buf.len()
+                    );
+                    return Ok((buf, headers, status_code, url, history));
+                }
+                let retries = retries.filter(|retries| retries.is_method_allowed(request.method()));
+                let mut previous_urls = Vec::new();
+                let mut history = Vec::new();
+                let resp = loop {
+                    let copy = redirects.as_ref().map(|_| redirect_copy(&request));
+                    let streamed = request.body().is_some_and(|body| body.as_bytes().is_none());
+                    let resp = loop {
+                        let retry_request = match &retries {
+                            Some(retries) if attempt < retries.total => request.try_clone(),
+                            _ => None,
+                        };
+                        let stale_retry =
+                            if retry_stale_connections && request.method().is_idempotent() {
+                                request.try_clone()
+                            } else {
+                                None
+                            };
+                        let result = match (client.execute(request).await, stale_retry) {
+                            (Err(e), Some(stale_retry)) if is_stale_connection_error(&e) => {
+                                log::info!(
+                                    target: REQUEST_TARGET,
+                                    "request_id={request_id} retry after connection error: {e}"
+                                );
+                                client.execute(stale_retry).await
+                            }
+                            (result, _) => result,
+                        };
+
+                        // Retries
+                        let (Some(retries), Some(retry_request)) = (&retries, retry_request) else {
+                            break result?;
+                        };
+                        let wait = match &result {
+                            Ok(resp) if retries.is_retry_status(resp.status().as_u16()) => {
+                                let status = resp.status().as_u16();
+                                match retries.retry_after(status, resp.headers()) {
+                                    Some(wait) if retries.exceeds_retry_after_max(wait) => {
+                                        log::info!(
+                                            target: REQUEST_TARGET,
+                                            "request_id={request_id} no retry, Retry-After exceeds \
+                                             retry_after_max: {wait:?}"
+                                        );
+                                        break result?;
+                                    }
+                                    Some(wait) => {
+                                        retries.on_throttle(resp.url().as_str(), status, wait)?;
+                                        wait
+                                    }
+                                    None => retries.backoff(attempt + 1),
+                                }
+                            }
+                            Err(e) if retries.is_retry_error(e) => retries.backoff(attempt + 1),
+                            _ => break result?,
+                        };
+                        log::info!(
+                            target: REQUEST_TARGET,
+                            "request_id={request_id} retry in {wait:?}: {:?}",
+                            result.as_ref().map(|resp| resp.status())
+                        );
+                        attempt += 1;
+                        tokio::time::sleep(wait).await;
+                        request = retry_request;
+                    };
+
+                    // Redirects
+                    let (next_request, rewind) = match (&redirects, copy) {
+                        (Some(redirects), Some(copy)) => {
+                            let keeps_body =
+                                streamed && redirects.keeps_body(resp.status(), copy.method());
+                            let next_request = redirects.next_request(
+                                copy,
+                                resp.status(),
+                                resp.headers(),
+                                &mut previous_urls,
+                            )?;
+                            (next_request, upload.as_ref().filter(|_| keeps_body))
+                        }
+                        _ => (None, None),
+                    };
+                    let Some(mut next_request) = next_request else {
+                        break resp;
+                    };
+                    // Files: streamed again, the body sent was consumed
+                    if let Some(upload) = rewind {
+                        upload.rewind(&client, &mut next_request).await?;
+                    }
+                    // Track_navigation
+                    if let Some(navigation) = &navigation {
+                        let url = next_request.url().clone();
+                        navigation.redirect(next_request.headers_mut(), &url);
+                    }
+                    if debug {
+                        log_response(
+                            resp.remote_addr(),
+                            resp.version(),
+                            resp.status().as_u16(),
+                            resp.headers(),
+                        );
+                    }
+                    log::info!(
+                        target: REQUEST_TARGET,
+                        "request_id={request_id} redirect {}: {}",
+                        resp.status().as_u16(),
+                        next_request.url()
+                    );
+                    let hop = (
+                        resp.headers().clone(),
+                        resp.status().as_u16(),
+                        resp.url().to_string(),
+                    );
+                    history.push(hop);
+                    request = next_request;
+                };
+
+                let wait = start.elapsed();
+                metrics.wait = Some(wait);
+                metrics.url = Some(resp.url().to_string());
+                metrics.status_code = Some(resp.status().as_u16());
+                if debug {
+                    log_response(
+                        resp.remote_addr(),
+                        resp.version(),
+                        resp.status().as_u16(),
+                        resp.headers(),
+                    );
+                }
+
+                // Client_hints
+                if let Some(client_hints) = &client_hints {
+                    let origin = resp.url().origin().ascii_serialization();
+                    client_hints.update(&origin, resp.headers().get("accept-ch"));
+                }
+
+                // Track_navigation
+                if let Some(navigation) = &navigation {
+                    navigation.update(resp.url());
+                }
+
+                // Response items
+                let headers = resp.headers().clone();
+                let status_code = resp.status().as_u16();
+                let url = resp.url().to_string();
+                let version = resp.version();
+                let receive_start = Instant::now();
+                let buf = resp.bytes().await?;
+                let receive = receive_start.elapsed();
+                stats.add_bytes_received(buf.len());
+                metrics.receive = Some(receive);
+                metrics.bytes_received = buf.len();
+
+                // Har
+                if let (Some(har), Some(har_request)) = (&har, har_request) {
+                    let har_response = har_response(version, status_code, &headers, &buf);
+                    har.record(started, har_request, har_response, wait, receive);
+                }
+
+                log::info!(
+                    target: REQUEST_TARGET,
+                    "request_id={request_id} response: {url} {status_code} {}",
+                    buf.len()
+                );
+                Ok::<_, Error>((buf, headers, status_code, url, history))
+            }
+            .await;
+            if let Err(e) = &result {
+                log::debug!(target: REQUEST_TARGET, "request_id={request_id} error: {e:#}");
+            }
+            if let Some(on_metrics) = &on_metrics {
+                metrics.report(
+                    on_metrics,
+                    request_id,
+                    &context_method,
+                    &url,
+                    attempt,
+                    request_start.elapsed(),
+                    result.as_ref().err(),
+                );
+            }
+            result.map_err(|e| {
+                e.context(RequestContext {
+                    request_id,
+                    method: context_method,
+                    url,
+                    timeout,
+                    attempt: attempt + 1,
+                })
+            })
+        })
+    }
 }
 
 /// Convenience functions that use a default Client instance under the hood
 #[pyfunction]
 #[pyo3(signature = (method, url, params=None, headers=None, cookies=None, content=None, data=None,
     json=None, files=None, auth=None, auth_bearer=None, timeout=None, impersonate=None, verify=None,
-    ca_cert_file=None))]
+    ca_cert_file=None, http_version=None))]
 fn request(
     py: Python,
     method: &str,
@@ -660,28 +2298,12 @@ fn request(
     auth: Option<(String, Option<String>)>,
     auth_bearer: Option<String>,
     timeout: Option<f64>,
-    impersonate: Option<&str>,
-    verify: Option<bool>,
+    impersonate: Option<ImpersonateArg>,
+    verify: Option<Verify>,
     ca_cert_file: Option<String>,
-) -> Result<Response> {
-    let client = Client::new(
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        impersonate,
-        None,
-        None,
-        verify,
-        ca_cert_file,
-        None,
-        None,
-    )?;
+    http_version: Option<&str>,
+) -> Result<Py<Response>> {
+    let client = Client::with_defaults(impersonate, verify, ca_cert_file)?;
     client.request(
         py,
         method,
@@ -696,12 +2318,14 @@ fn request(
         auth,
         auth_bearer,
         timeout,
+        http_version,
     )
 }
 
 #[pyfunction]
 #[pyo3(signature = (url, params=None, headers=None, cookies=None, auth=None, auth_bearer=None,
-    timeout=None, impersonate=None, verify=None, ca_cert_file=None))]
+    timeout=None, impersonate=None, verify=None, ca_cert_file=None, http_version=None, content=None, data=None,
+    json=None))]
 fn get(
     py: Python,
     url: &str,
@@ -711,28 +2335,15 @@ fn get(
     auth: Option<(String, Option<String>)>,
     auth_bearer: Option<String>,
     timeout: Option<f64>,
-    impersonate: Option<&str>,
-    verify: Option<bool>,
+    impersonate: Option<ImpersonateArg>,
+    verify: Option<Verify>,
     ca_cert_file: Option<String>,
-) -> Result<Response> {
-    let client = Client::new(
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        impersonate,
-        None,
-        None,
-        verify,
-        ca_cert_file,
-        None,
-        None,
-    )?;
+    http_version: Option<&str>,
+    content: Option<Vec<u8>>,
+    data: Option<&Bound<'_, PyAny>>,
+    json: Option<&Bound<'_, PyAny>>,
+) -> Result<Py<Response>> {
+    let client = Client::with_defaults(impersonate, verify, ca_cert_file)?;
     client.get(
         py,
         url,
@@ -742,12 +2353,16 @@ fn get(
         auth,
         auth_bearer,
         timeout,
+        http_version,
+        content,
+        data,
+        json,
     )
 }
 
 #[pyfunction]
 #[pyo3(signature = (url, params=None, headers=None, cookies=None, auth=None, auth_bearer=None,
-    timeout=None, impersonate=None, verify=None, ca_cert_file=None))]
+    timeout=None, impersonate=None, verify=None, ca_cert_file=None, http_version=None))]
 fn head(
     py: Python,
     url: &str,
@@ -757,28 +2372,12 @@ fn head(
     auth: Option<(String, Option<String>)>,
     auth_bearer: Option<String>,
     timeout: Option<f64>,
-    impersonate: Option<&str>,
-    verify: Option<bool>,
+    impersonate: Option<ImpersonateArg>,
+    verify: Option<Verify>,
     ca_cert_file: Option<String>,
-) -> Result<Response> {
-    let client = Client::new(
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        impersonate,
-        None,
-        None,
-        verify,
-        ca_cert_file,
-        None,
-        None,
-    )?;
+    http_version: Option<&str>,
+) -> Result<Py<Response>> {
+    let client = Client::with_defaults(impersonate, verify, ca_cert_file)?;
     client.head(
         py,
         url,
@@ -788,12 +2387,13 @@ fn head(
         auth,
         auth_bearer,
         timeout,
+        http_version,
     )
 }
 
 #[pyfunction]
 #[pyo3(signature = (url, params=None, headers=None, cookies=None, auth=None, auth_bearer=None,
-    timeout=None, impersonate=None, verify=None, ca_cert_file=None))]
+    timeout=None, impersonate=None, verify=None, ca_cert_file=None, http_version=None))]
 fn options(
     py: Python,
     url: &str,
@@ -803,28 +2403,12 @@ fn options(
     auth: Option<(String, Option<String>)>,
     auth_bearer: Option<String>,
     timeout: Option<f64>,
-    impersonate: Option<&str>,
-    verify: Option<bool>,
+    impersonate: Option<ImpersonateArg>,
+    verify: Option<Verify>,
     ca_cert_file: Option<String>,
-) -> Result<Response> {
-    let client = Client::new(
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        impersonate,
-        None,
-        None,
-        verify,
-        ca_cert_file,
-        None,
-        None,
-    )?;
+    http_version: Option<&str>,
+) -> Result<Py<Response>> {
+    let client = Client::with_defaults(impersonate, verify, ca_cert_file)?;
     client.options(
         py,
         url,
@@ -834,12 +2418,14 @@ fn options(
         auth,
         auth_bearer,
         timeout,
+        http_version,
     )
 }
 
 #[pyfunction]
 #[pyo3(signature = (url, params=None, headers=None, cookies=None, auth=None, auth_bearer=None,
-    timeout=None, impersonate=None, verify=None, ca_cert_file=None))]
+    timeout=None, impersonate=None, verify=None, ca_cert_file=None, http_version=None, content=None, data=None,
+    json=None))]
 fn delete(
     py: Python,
     url: &str,
@@ -849,28 +2435,15 @@ fn delete(
     auth: Option<(String, Option<String>)>,
     auth_bearer: Option<String>,
     timeout: Option<f64>,
-    impersonate: Option<&str>,
-    verify: Option<bool>,
+    impersonate: Option<ImpersonateArg>,
+    verify: Option<Verify>,
     ca_cert_file: Option<String>,
-) -> Result<Response> {
-    let client = Client::new(
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        impersonate,
-        None,
-        None,
-        verify,
-        ca_cert_file,
-        None,
-        None,
-    )?;
+    http_version: Option<&str>,
+    content: Option<Vec<u8>>,
+    data: Option<&Bound<'_, PyAny>>,
+    json: Option<&Bound<'_, PyAny>>,
+) -> Result<Py<Response>> {
+    let client = Client::with_defaults(impersonate, verify, ca_cert_file)?;
     client.delete(
         py,
         url,
@@ -880,13 +2453,17 @@ fn delete(
         auth,
         auth_bearer,
         timeout,
+        http_version,
+        content,
+        data,
+        json,
     )
 }
 
 #[pyfunction]
 #[pyo3(signature = (url, params=None, headers=None, cookies=None, content=None, data=None,
     json=None, files=None, auth=None, auth_bearer=None, timeout=None, impersonate=None, verify=None,
-    ca_cert_file=None))]
+    ca_cert_file=None, http_version=None))]
 fn post(
     py: Python,
     url: &str,
@@ -900,28 +2477,12 @@ fn post(
     auth: Option<(String, Option<String>)>,
     auth_bearer: Option<String>,
     timeout: Option<f64>,
-    impersonate: Option<&str>,
-    verify: Option<bool>,
+    impersonate: Option<ImpersonateArg>,
+    verify: Option<Verify>,
     ca_cert_file: Option<String>,
-) -> Result<Response> {
-    let client = Client::new(
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        impersonate,
-        None,
-        None,
-        verify,
-        ca_cert_file,
-        None,
-        None,
-    )?;
+    http_version: Option<&str>,
+) -> Result<Py<Response>> {
+    let client = Client::with_defaults(impersonate, verify, ca_cert_file)?;
     client.post(
         py,
         url,
@@ -935,13 +2496,14 @@ fn post(
         auth,
         auth_bearer,
         timeout,
+        http_version,
     )
 }
 
 #[pyfunction]
 #[pyo3(signature = (url, params=None, headers=None, cookies=None, content=None, data=None,
     json=None, files=None, auth=None, auth_bearer=None, timeout=None, impersonate=None, verify=None,
-    ca_cert_file=None))]
+    ca_cert_file=None, http_version=None))]
 fn put(
     py: Python,
     url: &str,
@@ -955,28 +2517,12 @@ fn put(
     auth: Option<(String, Option<String>)>,
     auth_bearer: Option<String>,
     timeout: Option<f64>,
-    impersonate: Option<&str>,
-    verify: Option<bool>,
+    impersonate: Option<ImpersonateArg>,
+    verify: Option<Verify>,
     ca_cert_file: Option<String>,
-) -> Result<Response> {
-    let client = Client::new(
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        impersonate,
-        None,
-        None,
-        verify,
-        ca_cert_file,
-        None,
-        None,
-    )?;
+    http_version: Option<&str>,
+) -> Result<Py<Response>> {
+    let client = Client::with_defaults(impersonate, verify, ca_cert_file)?;
     client.put(
         py,
         url,
@@ -990,13 +2536,14 @@ fn put(
         auth,
         auth_bearer,
         timeout,
+        http_version,
     )
 }
 
 #[pyfunction]
 #[pyo3(signature = (url, params=None, headers=None, cookies=None, content=None, data=None,
     json=None, files=None, auth=None, auth_bearer=None, timeout=None, impersonate=None, verify=None,
-    ca_cert_file=None))]
+    ca_cert_file=None, http_version=None))]
 fn patch(
     py: Python,
     url: &str,
@@ -1010,28 +2557,12 @@ fn patch(
     auth: Option<(String, Option<String>)>,
     auth_bearer: Option<String>,
     timeout: Option<f64>,
-    impersonate: Option<&str>,
-    verify: Option<bool>,
+    impersonate: Option<ImpersonateArg>,
+    verify: Option<Verify>,
     ca_cert_file: Option<String>,
-) -> Result<Response> {
-    let client = Client::new(
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        impersonate,
-        None,
-        None,
-        verify,
-        ca_cert_file,
-        None,
-        None,
-    )?;
+    http_version: Option<&str>,
+) -> Result<Py<Response>> {
+    let client = Client::with_defaults(impersonate, verify, ca_cert_file)?;
     client.patch(
         py,
         url,
@@ -1045,14 +2576,175 @@ fn patch(
         auth,
         auth_bearer,
         timeout,
+        http_version,
     )
 }
 
+/// Convert a JA3 fingerprint into `Client` TLS arguments.
+///
+/// Returns a dict with `tls_ciphers` and `tls_curves`, to be passed as `Client(**from_ja3(ja3))`.
+/// The extension order and point formats are not reproduced.
+///
+/// # Example
+///
+/// ```
+/// import primp
+///
+/// client = primp.Client(**primp.from_ja3("771,4865-4866-4867-49195-49199,0-23-65281-10-11,29-23-24,0"))
+/// ```
+#[pyfunction]
+fn from_ja3(ja3: &str) -> Result<IndexMap<String, Vec<String>, RandomState>> {
+    let ja3 = parse_ja3(ja3)?;
+    Ok(IndexMap::from_iter([
+        ("tls_ciphers".to_string(), ja3.tls_ciphers),
+        ("tls_curves".to_string(), ja3.tls_curves),
+    ]))
+}
+
+/// Build a custom impersonation profile from a HAR file and an optional captured ClientHello.
+///
+/// The headers and their order are taken from the first request of the HAR archive, request-specific
+/// headers (cookie, host, content-length, content-type, referer) are skipped. The ClientHello file holds
+/// the raw bytes of the TLS record or handshake message, e.g. exported from Wireshark.
+/// Returns a dict to be passed as `Client(impersonate_profile=...)`, the extension order is not reproduced.
+///
+/// # Example
+///
+/// ```
+/// import primp
+///
+/// profile = primp.profile_from_har("browser.har", client_hello_file="client_hello.bin")
+/// client = primp.Client(impersonate_profile={"impersonate": "chrome_131", **profile})
+/// ```
+#[pyfunction]
+#[pyo3(signature = (har_file, client_hello_file=None))]
+fn profile_from_har(
+    py: Python,
+    har_file: &str,
+    client_hello_file: Option<&str>,
+) -> Result<PyObject> {
+    let har =
+        std::fs::read(har_file).map_err(|e| anyhow!("Failed to read har_file {har_file}: {e}"))?;
+    let har: Value =
+        serde_json::from_slice(&har).map_err(|e| anyhow!("Invalid har_file {har_file}: {e}"))?;
+    let client_hello = client_hello_file
+        .map(|path| {
+            std::fs::read(path).map_err(|e| anyhow!("Failed to read client_hello_file {path}: {e}"))
+        })
+        .transpose()?;
+    let profile = profile::profile_from_har(&har, client_hello.as_deref())?;
+    Ok(pythonize(py, &profile)?.unbind())
+}
+
+/// Impersonation aliases and the impersonation they currently resolve to.
+///
+/// Aliases ("chrome", "chrome_latest", "firefox_esr", ...) follow the newest available version of the family,
+/// so they keep working when a pinned version is removed.
+#[pyfunction]
+fn impersonate_aliases() -> IndexMap<String, String, RandomState> {
+    IMPERSONATE_ALIASES
+        .iter()
+        .map(|(alias, target)| (alias.to_string(), target.to_string()))
+        .collect()
+}
+
+/// Configures the Tokio runtime shared by all clients, before the first request.
+///
+/// By default a single-thread runtime drives the requests, so requests sent from several Python threads
+/// take turns on it. With `worker_threads` a multi-thread runtime runs them in parallel.
+///
+/// # Arguments
+///
+/// * `worker_threads` - Number of runtime worker threads.
+///
+/// # Errors
+///
+/// * `PyException` - If the runtime is already configured or started.
+#[pyfunction]
+fn configure_runtime(worker_threads: usize) -> Result<()> {
+    if worker_threads == 0 {
+        return Err(anyhow!("worker_threads must be at least 1"));
+    }
+    if RUNTIME
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .is_some()
+    {
+        return Err(anyhow!(
+            "configure_runtime must be called before the first request"
+        ));
+    }
+    RUNTIME_WORKER_THREADS
+        .set(worker_threads)
+        .map_err(|_| anyhow!("The runtime is already configured"))
+}
+
+/// Sets the most verbose level of the log records forwarded from primp to Python logging.
+///
+/// Python loggers and their levels are cached on their first use, this also clears the cache,
+/// so call it after changing the logging configuration. The loggers are "primp" (client),
+/// "primp.request" (requests, with a `request_id=N` prefix) and "primp.wire" (`Client(debug=True)`).
+///
+/// # Arguments
+///
+/// * `level` - "off", "error", "warning", "info" or "debug". Default is "debug".
+#[pyfunction]
+#[pyo3(signature = (level="debug"))]
+fn set_log_level(level: &str) -> Result<()> {
+    logging::set_level(level)
+}
+
+/// Default headers of an impersonation, in the order they are sent.
+///
+/// The baseline to diff custom headers against: the request headers (Host, Cookie, Content-Type, ...)
+/// and the `headers` of the client are not included.
+///
+/// # Arguments
+///
+/// * `impersonate` - Impersonation, e.g. "chrome_131".
+/// * `impersonate_os` - OS of the impersonated browser, like `Client(impersonate_os=...)`. Default is None.
+///
+/// # Example
+///
+/// ```
+/// import primp
+///
+/// headers = primp.default_headers(impersonate="chrome_131", impersonate_os="windows")
+/// ```
+#[pyfunction]
+#[pyo3(signature = (impersonate, impersonate_os=None))]
+fn default_headers(
+    impersonate: &str,
+    impersonate_os: Option<&str>,
+) -> Result<IndexMap<String, String, RandomState>> {
+    profile::default_headers(impersonate, impersonate_os)
+}
+
+/// Supported `impersonate` values (without the aliases, see `impersonate_aliases`).
+#[pyfunction]
+fn impersonate_list() -> Vec<&'static str> {
+    IMPERSONATE_LIST.to_vec()
+}
+
+/// Supported `impersonate_os` values.
+#[pyfunction]
+fn impersonate_os_list() -> Vec<&'static str> {
+    IMPERSONATE_OS_LIST.to_vec()
+}
+
 #[pymodule]
 fn primp(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
-    pyo3_log::init();
+    logging::init();
 
     m.add_class::<Client>()?;
+    m.add_class::<Retry>()?;
+    m.add_class::<MockTransport>()?;
+    m.add_class::<PreparedRequest>()?;
+    register_exceptions(m)?;
+    register_adapters(m)?;
+    register_compat(m)?;
+    register_httpx(m)?;
+    register_scrapy(m)?;
     m.add_function(wrap_pyfunction!(request, m)?)?;
     m.add_function(wrap_pyfunction!(get, m)?)?;
     m.add_function(wrap_pyfunction!(head, m)?)?;
@@ -1061,5 +2753,13 @@ fn primp(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(post, m)?)?;
     m.add_function(wrap_pyfunction!(patch, m)?)?;
     m.add_function(wrap_pyfunction!(put, m)?)?;
+    m.add_function(wrap_pyfunction!(from_ja3, m)?)?;
+    m.add_function(wrap_pyfunction!(profile_from_har, m)?)?;
+    m.add_function(wrap_pyfunction!(impersonate_aliases, m)?)?;
+    m.add_function(wrap_pyfunction!(impersonate_list, m)?)?;
+    m.add_function(wrap_pyfunction!(impersonate_os_list, m)?)?;
+    m.add_function(wrap_pyfunction!(default_headers, m)?)?;
+    m.add_function(wrap_pyfunction!(configure_runtime, m)?)?;
+    m.add_function(wrap_pyfunction!(set_log_level, m)?)?;
     Ok(())
 }
@@ -0,0 +1,169 @@
+use std::io::ErrorKind;
+
+use anyhow::{anyhow, Result};
+use base64::{
+    alphabet::STANDARD,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine,
+};
+use bytes::Bytes;
+use rquest::{
+    header::{HeaderMap, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, LAST_MODIFIED},
+    Method, Url,
+};
+
+use crate::response::ResponseParts;
+use crate::utils::percent_decode;
+
+/// Whether the requests of a URL are answered locally, without a connection.
+pub fn is_local(url: &Url) -> bool {
+    matches!(url.scheme(), "file" | "data")
+}
+
+/// Whether the client may answer the requests of a local URL: only with `local_urls`, never with `https_only`.
+pub fn check(url: &Url, local_urls: bool, https_only: bool) -> Result<()> {
+    if https_only {
+        return Err(anyhow!("URL scheme is not allowed with https_only: {url}"));
+    }
+    if !local_urls {
+        return Err(anyhow!(
+            "{}: URLs are disabled, enable them with Client(local_urls=True): {url}",
+            url.scheme()
+        ));
+    }
+    Ok(())
+}
+
+/// Response of a local URL: the content of a `data:` URL, or the file of a `file://` URL,
+/// 404 if it does not exist and 403 if it can not be read.
+pub async fn respond(request: &rquest::Request) -> Result<ResponseParts> {
+    let url = request.url();
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        return Err(anyhow!(
+            "Method {} is not supported for {url}, only GET and HEAD",
+            request.method()
+        ));
+    }
+    if url.scheme() == "data" {
+        let (content, mime) = parse_data_url(url)?;
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_str(&mime)?);
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(content.len()));
+        let content = match *request.method() {
+            Method::HEAD => Bytes::new(),
+            _ => Bytes::from(content),
+        };
+        return Ok((content, headers, 200, url.to_string(), Vec::new()));
+    }
+    let path = url
+        .to_file_path()
+        .map_err(|_| anyhow!("Invalid file URL: {url}"))?;
+    let (content, metadata) = match tokio::fs::read(&path).await {
+        Ok(content) => (content, tokio::fs::metadata(&path).await.ok()),
+        Err(e) => {
+            let status_code = match e.kind() {
+                ErrorKind::NotFound => 404,
+                ErrorKind::PermissionDenied => 403,
+                _ => return Err(anyhow!("Failed to read {}: {e}", path.display())),
+            };
+            let parts = (
+                Bytes::new(),
+                HeaderMap::new(),
+                status_code,
+                url.to_string(),
+                Vec::new(),
+            );
+            return Ok(parts);
+        }
+    };
+
+    let mut headers = HeaderMap::new();
+    let mime = mime_guess::from_path(&path).first_or_octet_stream();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_str(mime.as_ref())?);
+    headers.insert(CONTENT_LENGTH, HeaderValue::from(content.len()));
+    if let Some(modified) = metadata.and_then(|metadata| metadata.modified().ok()) {
+        let modified = httpdate::fmt_http_date(modified);
+        headers.insert(LAST_MODIFIED, HeaderValue::from_str(&modified)?);
+    }
+    let content = match *request.method() {
+        Method::HEAD => Bytes::new(),
+        _ => Bytes::from(content),
+    };
+    Ok((content, headers, 200, url.to_string(), Vec::new()))
+}
+
+/// Body and media type of a `data:[<mediatype>][;base64],<data>` URL, the fragment is ignored.
+fn parse_data_url(url: &Url) -> Result<(Vec<u8>, String)> {
+    let end = url.as_str().len() - url.fragment().map_or(0, |fragment| fragment.len() + 1);
+    let input = &url.as_str()["data:".len()..end];
+    let (meta, data) = input
+        .split_once(',')
+        .ok_or_else(|| anyhow!("Invalid data URL, missing ',': {url}"))?;
+    let meta = meta.trim();
+    let data = percent_decode(data.as_bytes());
+    let lowercase = meta.to_ascii_lowercase();
+    let (mime, content) = match lowercase.strip_suffix("base64") {
+        Some(rest) if rest.trim_end().ends_with(';') => {
+            let mime = &meta[..rest.trim_end().len() - 1];
+            // Forgiving base64: ASCII whitespace is ignored and the padding is optional
+            let data: Vec<u8> = data
+                .into_iter()
+                .filter(|b| !b.is_ascii_whitespace())
+                .collect();
+            let engine = GeneralPurpose::new(
+                &STANDARD,
+                GeneralPurposeConfig::new()
+                    .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+            );
+            let content = engine
+                .decode(data)
+                .map_err(|e| anyhow!("Invalid base64 in data URL: {e}"))?;
+            (mime.trim(), content)
+        }
+        _ => (meta, data),
+    };
+    let mime = match mime {
+        "" => "text/plain;charset=US-ASCII".to_string(),
+        mime if mime.starts_with(';') => format!("text/plain{mime}"),
+        mime => mime.to_string(),
+    };
+    Ok((content, mime))
+}
+
+#[cfg(test)]
+mod local_tests {
+    use super::*;
+
+    fn parse(url: &str) -> (Vec<u8>, String) {
+        parse_data_url(&Url::parse(url).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_data_url() {
+        assert_eq!(
+            parse("data:,Hello%2C%20World%21"),
+            (
+                b"Hello, World!".to_vec(),
+                "text/plain;charset=US-ASCII".into()
+            )
+        );
+        assert_eq!(
+            parse("data:text/html;charset=utf-8,%3Cp%3Eok%3C/p%3E#top"),
+            (b"<p>ok</p>".to_vec(), "text/html;charset=utf-8".into())
+        );
+        assert_eq!(
+            parse("data:image/png;base64,/w%3D%3D"),
+            (vec![0xff], "image/png".into())
+        );
+        assert_eq!(
+            parse("data:;BASE64,SGVs bG8"),
+            (b"Hello".to_vec(), "text/plain;charset=US-ASCII".into())
+        );
+        assert_eq!(
+            parse("data:;charset=utf-8,a"),
+            (b"a".to_vec(), "text/plain;charset=utf-8".into())
+        );
+        assert!(parse_data_url(&Url::parse("data:text/plain").unwrap()).is_err());
+        assert!(parse_data_url(&Url::parse("data:;base64,!!").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Result};
+use log::LevelFilter;
+use pyo3_log::ResetHandle;
+
+/// Target of the per-request logs, the Python logger is "primp.request".
+pub const REQUEST_TARGET: &str = "primp::request";
+
+static REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+static RESET_HANDLE: OnceLock<ResetHandle> = OnceLock::new();
+
+/// Id of a new request, unique in the process, to correlate its log records.
+pub fn next_request_id() -> u64 {
+    REQUEST_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Forward the Rust log records to Python logging.
+pub fn init() {
+    RESET_HANDLE.get_or_init(pyo3_log::init);
+}
+
+/// Set the most verbose level forwarded to Python and drop the cached Python loggers and levels,
+/// so that logging configuration changed since the first records is used.
+pub fn set_level(level: &str) -> Result<()> {
+    let level = match level.to_ascii_lowercase().as_str() {
+        "off" => LevelFilter::Off,
+        "error" => LevelFilter::Error,
+        "warning" | "warn" => LevelFilter::Warn,
+        "info" => LevelFilter::Info,
+        "debug" => LevelFilter::Debug,
+        _ => {
+            return Err(anyhow!(
+                "Invalid log level: {level}. Expected one of: off, error, warning, info, debug"
+            ))
+        }
+    };
+    log::set_max_level(level);
+    if let Some(handle) = RESET_HANDLE.get() {
+        handle.reset();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod logging_tests {
+    use super::*;
+
+    #[test]
+    fn test_set_level() {
+        assert!(set_level("WARNING").is_ok());
+        assert_eq!(log::max_level(), LevelFilter::Warn);
+        assert!(set_level("verbose").is_err());
+        assert!(next_request_id() < next_request_id());
+    }
+}
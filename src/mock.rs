@@ -0,0 +1,187 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use bytes::Bytes;
+use foldhash::fast::RandomState;
+use indexmap::IndexMap;
+use pyo3::prelude::*;
+use pythonize::depythonize;
+use rquest::{
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE},
+    Method,
+};
+use serde_json::Value;
+
+use crate::response::ResponseParts;
+use crate::StrOrBytes;
+
+type IndexMapSSR = IndexMap<String, String, RandomState>;
+
+/// Canned response for the requests matching a URL pattern.
+#[derive(Clone, Debug)]
+struct MockRoute {
+    method: Option<Method>,
+    pattern: String,
+    status_code: u16,
+    headers: HeaderMap,
+    body: Bytes,
+    delay: Option<Duration>,
+}
+
+/// Transport answering requests with registered responses instead of the network, for tests.
+///
+/// Attach it with `Client(transport=...)`. Routes can be added after the client is created.
+#[pyclass(frozen)]
+#[derive(Clone, Debug, Default)]
+pub struct MockTransport {
+    routes: Arc<Mutex<Vec<MockRoute>>>,
+    calls: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+#[pymethods]
+impl MockTransport {
+    #[new]
+    fn new() -> Self {
+        MockTransport::default()
+    }
+
+    /// Register a response for the requests matching `url`.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL to match, where `*` matches any characters, e.g. "https://example.com/api/*".
+    /// * `method` - Method to match. Default is None (any method).
+    /// * `status_code` - Status code of the response. Default is 200.
+    /// * `headers` - Headers of the response. Default is None.
+    /// * `content` - Body of the response. Default is None (empty).
+    /// * `json` - Body of the response serialized as JSON, with `Content-Type: application/json`. Default is None.
+    /// * `delay` - Seconds to wait before responding. Default is None.
+    ///
+    /// The most recently added matching route is used.
+    #[pyo3(signature = (url, method=None, status_code=200, headers=None, content=None, json=None, delay=None))]
+    fn add(
+        &self,
+        url: &str,
+        method: Option<&str>,
+        status_code: u16,
+        headers: Option<IndexMapSSR>,
+        content: Option<StrOrBytes>,
+        json: Option<&Bound<'_, PyAny>>,
+        delay: Option<f64>,
+    ) -> Result<()> {
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers.unwrap_or_default() {
+            header_map.append(
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_str(&value)?,
+            );
+        }
+        let body = match (content, json) {
+            (Some(_), Some(_)) => return Err(anyhow!("content and json are mutually exclusive")),
+            (Some(content), None) => Bytes::from(content.into_bytes()),
+            (None, Some(json)) => {
+                let value: Value = depythonize(json)?;
+                header_map
+                    .entry(CONTENT_TYPE)
+                    .or_insert(HeaderValue::from_static("application/json"));
+                Bytes::from(serde_json::to_vec(&value)?)
+            }
+            (None, None) => Bytes::new(),
+        };
+        let route = MockRoute {
+            method: method
+                .map(|m| Method::from_bytes(m.to_ascii_uppercase().as_bytes()))
+                .transpose()?,
+            pattern: url.to_string(),
+            status_code,
+            headers: header_map,
+            body,
+            delay: delay.map(Duration::try_from_secs_f64).transpose()?,
+        };
+        self.routes.lock().unwrap().push(route);
+        Ok(())
+    }
+
+    /// (method, url) of the requests received, in order.
+    #[getter]
+    fn calls(&self) -> Vec<(String, String)> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Remove the routes and the recorded calls.
+    fn reset(&self) {
+        self.routes.lock().unwrap().clear();
+        self.calls.lock().unwrap().clear();
+    }
+}
+
+impl MockTransport {
+    /// Response of the most recently added route matching the request.
+    pub async fn respond(&self, request: &rquest::Request) -> Result<ResponseParts> {
+        let (method, url) = (request.method(), request.url().as_str());
+        self.calls
+            .lock()
+            .unwrap()
+            .push((method.to_string(), url.to_string()));
+        let route = self
+            .routes
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|route| {
+                route.method.as_ref().is_none_or(|m| m == method)
+                    && wildcard_match(&route.pattern, url)
+            })
+            .cloned()
+            .ok_or_else(|| anyhow!("MockTransport: no response registered for {method} {url}"))?;
+        if let Some(delay) = route.delay {
+            tokio::time::sleep(delay).await;
+        }
+        Ok((
+            route.body,
+            route.headers,
+            route.status_code,
+            url.to_string(),
+            Vec::new(),
+        ))
+    }
+}
+
+/// Match `text` against `pattern`, where `*` matches any (possibly empty) sequence of characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod mock_tests {
+    use super::*;
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("https://a.com/", "https://a.com/"));
+        assert!(!wildcard_match("https://a.com/", "https://a.com/x"));
+        assert!(wildcard_match("https://a.com/*", "https://a.com/x/y?z=1"));
+        assert!(wildcard_match("https://*.com/*/y", "https://a.com/x/y"));
+        assert!(!wildcard_match("https://*.com/*/y", "https://a.org/x/y"));
+        assert!(wildcard_match("*", "anything"));
+        assert!(wildcard_match("*a*a", "aa"));
+        assert!(!wildcard_match("*a*a", "a"));
+    }
+}
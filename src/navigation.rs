@@ -0,0 +1,165 @@
+use std::sync::Mutex;
+
+use rquest::header::{HeaderMap, HeaderValue, REFERER};
+use rquest::Url;
+
+/// Browsing state of a client: the last visited page, used for `sec-fetch-site` and `Referer`
+/// of the following requests like a browser following links.
+#[derive(Debug)]
+pub struct Navigation {
+    previous: Mutex<Option<Url>>,
+    referer: bool,
+}
+
+/// `sec-fetch-site` values, from the most to the least trusted.
+const SEC_FETCH_SITES: [&str; 3] = ["same-origin", "same-site", "cross-site"];
+
+/// Registrable domain approximated by the last two labels of the host: there is no public suffix list,
+/// so hosts under a multi-label suffix (`a.co.uk` and `b.co.uk`) are taken for the same site.
+fn site(url: &Url) -> Option<String> {
+    let host = url.host_str()?;
+    if url.domain().is_none() {
+        return Some(format!("{}://{host}", url.scheme()));
+    }
+    let mut labels: Vec<&str> = host.rsplitn(3, '.').take(2).collect();
+    labels.reverse();
+    Some(format!("{}://{}", url.scheme(), labels.join(".")))
+}
+
+impl Navigation {
+    pub fn new(referer: bool) -> Self {
+        Navigation {
+            previous: Mutex::new(None),
+            referer,
+        }
+    }
+
+    /// `sec-fetch-site` and `Referer` (strict-origin-when-cross-origin policy) of a request to `url`.
+    pub fn headers(&self, url: &Url) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let previous = self.previous.lock().unwrap();
+        let Some(previous) = previous.as_ref() else {
+            return headers;
+        };
+
+        let same_origin = previous.origin() == url.origin();
+        let sec_fetch_site = if same_origin {
+            "same-origin"
+        } else if site(previous) == site(url) {
+            "same-site"
+        } else {
+            "cross-site"
+        };
+        headers.insert("sec-fetch-site", HeaderValue::from_static(sec_fetch_site));
+
+        let downgrade = previous.scheme() == "https" && url.scheme() != "https";
+        if self.referer && !downgrade {
+            let referer = if same_origin {
+                let mut referer = previous.clone();
+                referer.set_fragment(None);
+                let _ = referer.set_username("");
+                let _ = referer.set_password(None);
+                referer.to_string()
+            } else {
+                format!("{}/", previous.origin().ascii_serialization())
+            };
+            if let Ok(referer) = HeaderValue::from_str(&referer) {
+                headers.insert(REFERER, referer);
+            }
+        }
+        headers
+    }
+
+    /// Update the headers of a request redirected to `url`, like browsers: `sec-fetch-site` becomes
+    /// the least trusted value of the redirect chain (`none` of a user-initiated request is kept)
+    /// and `Referer` is the one of the current page for the new URL.
+    pub fn redirect(&self, headers: &mut HeaderMap, url: &Url) {
+        let hop = self.headers(url);
+        if let Some(site) = hop.get("sec-fetch-site") {
+            let rank = |value: &HeaderValue| SEC_FETCH_SITES.iter().position(|s| value == *s);
+            let keep = headers
+                .get("sec-fetch-site")
+                .is_some_and(|sent| sent == "none" || rank(sent) > rank(site));
+            if !keep {
+                headers.insert("sec-fetch-site", site.clone());
+            }
+        }
+        if self.referer {
+            match hop.get(REFERER) {
+                Some(referer) => headers.insert(REFERER, referer.clone()),
+                None => headers.remove(REFERER),
+            };
+        }
+    }
+
+    /// Remember the (final) URL of a response as the current page.
+    pub fn update(&self, url: &Url) {
+        *self.previous.lock().unwrap() = Some(url.clone());
+    }
+}
+
+#[cfg(test)]
+mod navigation_tests {
+    use super::*;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn test_navigation_first_request() {
+        let navigation = Navigation::new(true);
+        assert!(navigation.headers(&url("https://example.com/")).is_empty());
+    }
+
+    #[test]
+    fn test_navigation_sec_fetch_site() {
+        let navigation = Navigation::new(true);
+        navigation.update(&url("https://www.example.com/page?q=1#top"));
+
+        let headers = navigation.headers(&url("https://www.example.com/next"));
+        assert_eq!(headers["sec-fetch-site"], "same-origin");
+        assert_eq!(headers["referer"], "https://www.example.com/page?q=1");
+
+        let headers = navigation.headers(&url("https://api.example.com/data"));
+        assert_eq!(headers["sec-fetch-site"], "same-site");
+        assert_eq!(headers["referer"], "https://www.example.com/");
+
+        let headers = navigation.headers(&url("https://other.org/"));
+        assert_eq!(headers["sec-fetch-site"], "cross-site");
+        assert_eq!(headers["referer"], "https://www.example.com/");
+
+        let headers = navigation.headers(&url("http://other.org/"));
+        assert!(!headers.contains_key("referer"));
+    }
+
+    #[test]
+    fn test_navigation_redirect() {
+        let navigation = Navigation::new(true);
+        navigation.update(&url("https://www.example.com/page"));
+
+        let mut headers = navigation.headers(&url("https://www.example.com/login"));
+        navigation.redirect(&mut headers, &url("https://sso.example.com/auth"));
+        assert_eq!(headers["sec-fetch-site"], "same-site");
+        assert_eq!(headers["referer"], "https://www.example.com/");
+        navigation.redirect(&mut headers, &url("https://other.org/"));
+        assert_eq!(headers["sec-fetch-site"], "cross-site");
+        navigation.redirect(&mut headers, &url("https://www.example.com/done"));
+        assert_eq!(headers["sec-fetch-site"], "cross-site");
+        assert_eq!(headers["referer"], "https://www.example.com/page");
+
+        let mut headers = HeaderMap::new();
+        headers.insert("sec-fetch-site", HeaderValue::from_static("none"));
+        navigation.redirect(&mut headers, &url("https://other.org/"));
+        assert_eq!(headers["sec-fetch-site"], "none");
+    }
+
+    #[test]
+    fn test_navigation_without_referer() {
+        let navigation = Navigation::new(false);
+        navigation.update(&url("https://example.com/"));
+        let headers = navigation.headers(&url("https://example.com/next"));
+        assert_eq!(headers["sec-fetch-site"], "same-origin");
+        assert!(!headers.contains_key("referer"));
+    }
+}
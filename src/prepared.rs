@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use foldhash::fast::RandomState;
+use indexmap::IndexMap;
+use pyo3::prelude::*;
+use rquest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Method,
+};
+
+use crate::utils::parse_http_version;
+use crate::wire::sent_headers;
+
+type IndexMapSSR = IndexMap<String, String, RandomState>;
+
+/// Request built by `Client.build_request()` without sending it, to inspect or modify
+/// (e.g. to sign it) before `Client.send()`.
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct PreparedRequest {
+    #[pyo3(get, set)]
+    pub method: String,
+    #[pyo3(get, set)]
+    pub url: String,
+    #[pyo3(get, set)]
+    pub headers: IndexMapSSR,
+    pub content: Option<Vec<u8>>,
+    #[pyo3(get, set)]
+    pub timeout: Option<f64>,
+    #[pyo3(get, set)]
+    pub http_version: Option<String>,
+}
+
+#[pymethods]
+impl PreparedRequest {
+    /// Body of the request.
+    #[getter]
+    fn get_content(&self) -> Option<&[u8]> {
+        self.content.as_deref()
+    }
+
+    #[setter]
+    fn set_content(&mut self, content: Option<Vec<u8>>) {
+        self.content = content;
+    }
+
+    /// Size of the body in bytes.
+    #[getter]
+    fn body_length(&self) -> usize {
+        self.content.as_ref().map_or(0, |content| content.len())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<PreparedRequest [{} {}]>", self.method, self.url)
+    }
+}
+
+impl PreparedRequest {
+    /// Prepared request of a built request, with the default headers and the cookies added by the client.
+    pub fn new(
+        client: &rquest::Client,
+        request: &rquest::Request,
+        timeout: Option<f64>,
+        http_version: Option<String>,
+    ) -> Result<Self> {
+        let content = match request.body() {
+            Some(body) => Some(
+                body.as_bytes()
+                    .ok_or_else(|| anyhow!("Requests with files can not be prepared"))?
+                    .to_vec(),
+            ),
+            None => None,
+        };
+        let headers = sent_headers(client, request)
+            .iter()
+            .map(|(name, value)| {
+                let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+                (name.to_string(), value)
+            })
+            .collect();
+        Ok(PreparedRequest {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            headers,
+            content,
+            timeout,
+            http_version,
+        })
+    }
+
+    /// Build the request to send with `client`.
+    pub fn to_request(&self, client: &rquest::Client) -> Result<(rquest::Client, rquest::Request)> {
+        let mut headers = HeaderMap::with_capacity(self.headers.len());
+        for (name, value) in &self.headers {
+            headers.append(
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_str(value)?,
+            );
+        }
+        let method = Method::from_bytes(self.method.as_bytes())?;
+        let mut request_builder = client.request(method, &self.url).headers(headers);
+        if let Some(content) = &self.content {
+            request_builder = request_builder.body(content.clone());
+        }
+        if let Some(seconds) = self.timeout {
+            request_builder = request_builder.timeout(Duration::from_secs_f64(seconds));
+        }
+        if let Some(version) = &self.http_version {
+            request_builder = request_builder.version(parse_http_version(version)?);
+        }
+        let (client, request) = request_builder.build_split();
+        Ok((client, request?))
+    }
+}
@@ -0,0 +1,1232 @@
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use foldhash::fast::RandomState;
+use indexmap::IndexMap;
+use pyo3::exceptions::PyValueError;
+use rquest::header::{
+    HeaderName, HeaderValue, ACCEPT, ACCEPT_LANGUAGE, CONNECTION, UPGRADE_INSECURE_REQUESTS,
+    USER_AGENT,
+};
+use rquest::mimic::{self, ImpersonateSettings};
+use rquest::tls::Impersonate;
+use serde_json::{json, Map, Value};
+
+use crate::fingerprint::{
+    parse_client_hello, set_akamai_h2, set_http2_headers_priority, set_http2_priorities,
+    set_http2_settings,
+};
+use crate::traits::HeadersTraits;
+use crate::utils::{
+    impersonate_settings_mut, parse_alpn, parse_cert_compression, parse_curves,
+    parse_extension_order, tls_settings_mut,
+};
+
+type IndexMapSSR = IndexMap<String, String, RandomState>;
+
+/// Custom impersonation profile loaded from JSON.
+///
+/// ```json
+/// {
+///     "impersonate": "chrome_131",
+///     "headers": {"User-Agent": "...", "Accept": "..."},
+///     "headers_order": ["user-agent", "accept"],
+///     "tls": {
+///         "alpn": ["h2", "http/1.1"],
+///         "ciphers": ["TLS_AES_128_GCM_SHA256"],
+///         "sigalgs": ["ecdsa_secp256r1_sha256"],
+///         "curves": ["X25519", "P-256"],
+///         "cert_compression": ["brotli"],
+///         "extension_order": [0, 1, 2],
+///         "permute_extensions": true,
+///         "ech_grease": true
+///     },
+///     "http2": "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p"
+/// }
+/// ```
+///
+/// Every key is optional, `impersonate` is the base profile the other keys are applied on top of.
+#[derive(Debug, Default)]
+pub struct Profile {
+    pub impersonate: Option<String>,
+    headers: Option<IndexMapSSR>,
+    headers_order: Option<Vec<String>>,
+    alpn: Option<Vec<String>>,
+    ciphers: Option<Vec<String>>,
+    sigalgs: Option<Vec<String>>,
+    curves: Option<Vec<String>>,
+    cert_compression: Option<Vec<String>>,
+    extension_order: Option<Vec<u8>>,
+    permute_extensions: Option<bool>,
+    ech_grease: Option<bool>,
+    http2: Option<String>,
+}
+
+fn get_string(map: &Map<String, Value>, key: &str) -> Result<Option<String>> {
+    match map.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(anyhow!("Invalid profile: {key} must be a string")),
+    }
+}
+
+fn get_bool(map: &Map<String, Value>, key: &str) -> Result<Option<bool>> {
+    match map.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Bool(b)) => Ok(Some(*b)),
+        Some(_) => Err(anyhow!("Invalid profile: {key} must be a boolean")),
+    }
+}
+
+fn get_strings(map: &Map<String, Value>, key: &str) -> Result<Option<Vec<String>>> {
+    match map.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(String::from)
+                    .ok_or_else(|| anyhow!("Invalid profile: {key} must be a list of strings"))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Some),
+        Some(_) => Err(anyhow!("Invalid profile: {key} must be a list of strings")),
+    }
+}
+
+fn get_u8s(map: &Map<String, Value>, key: &str) -> Result<Option<Vec<u8>>> {
+    match map.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                item.as_u64()
+                    .and_then(|n| u8::try_from(n).ok())
+                    .ok_or_else(|| anyhow!("Invalid profile: {key} must be a list of integers"))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Some),
+        Some(_) => Err(anyhow!("Invalid profile: {key} must be a list of integers")),
+    }
+}
+
+impl Profile {
+    /// Parse a profile from a JSON object.
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let map = value
+            .as_object()
+            .ok_or_else(|| anyhow!("Invalid profile: expected a JSON object"))?;
+
+        let headers = match map.get("headers") {
+            None | Some(Value::Null) => None,
+            Some(Value::Object(headers)) => Some(
+                headers
+                    .iter()
+                    .map(|(k, v)| {
+                        v.as_str()
+                            .map(|v| (k.clone(), v.to_string()))
+                            .ok_or_else(|| anyhow!("Invalid profile: header {k} must be a string"))
+                    })
+                    .collect::<Result<IndexMapSSR>>()?,
+            ),
+            Some(_) => return Err(anyhow!("Invalid profile: headers must be an object")),
+        };
+
+        let empty = Map::new();
+        let tls = match map.get("tls") {
+            None | Some(Value::Null) => &empty,
+            Some(Value::Object(tls)) => tls,
+            Some(_) => return Err(anyhow!("Invalid profile: tls must be an object")),
+        };
+
+        Ok(Profile {
+            impersonate: get_string(map, "impersonate")?,
+            headers,
+            headers_order: get_strings(map, "headers_order")?,
+            alpn: get_strings(tls, "alpn")?,
+            ciphers: get_strings(tls, "ciphers")?,
+            sigalgs: get_strings(tls, "sigalgs")?,
+            curves: get_strings(tls, "curves")?,
+            cert_compression: get_strings(tls, "cert_compression")?,
+            extension_order: get_u8s(tls, "extension_order")?,
+            permute_extensions: get_bool(tls, "permute_extensions")?,
+            ech_grease: get_bool(tls, "ech_grease")?,
+            http2: get_string(map, "http2")?,
+        })
+    }
+
+    /// Read and parse a profile from a JSON file.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let content = std::fs::read(path)
+            .map_err(|e| anyhow!("Failed to read impersonate_profile_file {path}: {e}"))?;
+        let value: Value = serde_json::from_slice(&content)
+            .map_err(|e| anyhow!("Invalid impersonate_profile_file {path}: {e}"))?;
+        Profile::from_json(&value)
+    }
+
+    /// Apply the profile on top of the (base) impersonation settings.
+    pub fn apply(&self, settings: &mut Option<ImpersonateSettings>) -> Result<()> {
+        if let Some(headers) = &self.headers {
+            impersonate_settings_mut(settings).headers = Some(Cow::Owned(headers.to_headermap()));
+        }
+        if let Some(headers_order) = &self.headers_order {
+            let headers_order = headers_order
+                .iter()
+                .map(|name| {
+                    HeaderName::from_bytes(name.as_bytes())
+                        .map_err(|_| anyhow!("Invalid profile: header name {name}"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            impersonate_settings_mut(settings).headers_order = Some(Cow::Owned(headers_order));
+        }
+
+        if let Some(alpn) = &self.alpn {
+            tls_settings_mut(settings).alpn_protos = parse_alpn(alpn)?;
+        }
+        if let Some(ciphers) = &self.ciphers {
+            tls_settings_mut(settings).cipher_list = Some(ciphers.join(":").into());
+        }
+        if let Some(sigalgs) = &self.sigalgs {
+            tls_settings_mut(settings).sigalgs_list = Some(sigalgs.join(":").into());
+        }
+        if let Some(curves) = &self.curves {
+            tls_settings_mut(settings).curves = Some(parse_curves(curves)?);
+        }
+        if let Some(cert_compression) = &self.cert_compression {
+            tls_settings_mut(settings).cert_compression_algorithm =
+                parse_cert_compression(cert_compression)?;
+        }
+        if let Some(extension_order) = &self.extension_order {
+            tls_settings_mut(settings).extension_permutation_indices =
+                Some(parse_extension_order(extension_order)?);
+        }
+        if let Some(permute_extensions) = self.permute_extensions {
+            tls_settings_mut(settings).permute_extensions = Some(permute_extensions);
+        }
+        if let Some(ech_grease) = self.ech_grease {
+            tls_settings_mut(settings).enable_ech_grease = ech_grease;
+        }
+
+        if let Some(http2) = &self.http2 {
+            set_akamai_h2(settings, http2)?;
+        }
+        Ok(())
+    }
+}
+
+/// Built-in profiles of browsers derived from an rquest impersonation:
+/// the base impersonation and the headers that differ from it.
+fn builtin_profile(name: &str) -> Option<(Impersonate, &'static [(&'static str, &'static str)])> {
+    Some(match name {
+        // Brave 1.73 (Chromium 131): Chrome's TLS/HTTP2 and user agent, its own brands and Global Privacy Control
+        "brave_1.73" => (
+            Impersonate::Chrome131,
+            &[
+                (
+                    "sec-ch-ua",
+                    r#""Brave";v="131", "Chromium";v="131", "Not_A Brand";v="24""#,
+                ),
+                ("sec-gpc", "1"),
+            ],
+        ),
+        // Samsung Internet 27 (Chromium 125) on Android
+        "samsung_internet_27" => (
+            Impersonate::Chrome124,
+            &[
+                (
+                    "sec-ch-ua",
+                    r#""Chromium";v="125", "Not.A/Brand";v="24", "Samsung Internet";v="27.0""#,
+                ),
+                ("sec-ch-ua-mobile", "?1"),
+                ("sec-ch-ua-platform", r#""Android""#),
+                (
+                    "user-agent",
+                    "Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) SamsungBrowser/27.0 Chrome/125.0.0.0 Mobile Safari/537.36",
+                ),
+            ],
+        ),
+        // Android System WebView 131
+        "android_webview_131" => (
+            Impersonate::Chrome131,
+            &[
+                (
+                    "sec-ch-ua",
+                    r#""Android WebView";v="131", "Chromium";v="131", "Not_A Brand";v="24""#,
+                ),
+                ("sec-ch-ua-mobile", "?1"),
+                ("sec-ch-ua-platform", r#""Android""#),
+                (
+                    "user-agent",
+                    "Mozilla/5.0 (Linux; Android 10; K; wv) AppleWebKit/537.36 (KHTML, like Gecko) Version/4.0 Chrome/131.0.0.0 Mobile Safari/537.36",
+                ),
+            ],
+        ),
+        // Tor Browser 14 (Firefox ESR 128): the same Windows user agent and English locale for all users
+        "tor_14" => (
+            Impersonate::Firefox128,
+            &[
+                (
+                    "user-agent",
+                    "Mozilla/5.0 (Windows NT 10.0; rv:128.0) Gecko/20100101 Firefox/128.0",
+                ),
+                ("accept-language", "en-US,en;q=0.5"),
+            ],
+        ),
+        _ => return None,
+    })
+}
+
+/// Built-in profiles of non-browser clients, in the `Profile::from_json` format.
+fn builtin_json_profile(name: &str) -> Option<Value> {
+    Some(match name {
+        // curl 8 with OpenSSL 3 defaults, minus the ciphers, groups and sigalgs BoringSSL doesn't implement
+        "curl_8" => json!({
+            "headers": {"user-agent": "curl/8.11.0", "accept": "*/*"},
+            "headers_order": ["user-agent", "accept"],
+            "tls": {
+                "alpn": ["h2", "http/1.1"],
+                "ciphers": [
+                    "TLS_AES_256_GCM_SHA384", "TLS_CHACHA20_POLY1305_SHA256", "TLS_AES_128_GCM_SHA256",
+                    "ECDHE-ECDSA-AES256-GCM-SHA384", "ECDHE-RSA-AES256-GCM-SHA384",
+                    "ECDHE-ECDSA-CHACHA20-POLY1305", "ECDHE-RSA-CHACHA20-POLY1305",
+                    "ECDHE-ECDSA-AES128-GCM-SHA256", "ECDHE-RSA-AES128-GCM-SHA256",
+                    "ECDHE-ECDSA-AES128-SHA256", "ECDHE-RSA-AES128-SHA256",
+                    "ECDHE-ECDSA-AES256-SHA", "ECDHE-RSA-AES256-SHA",
+                    "ECDHE-ECDSA-AES128-SHA", "ECDHE-RSA-AES128-SHA",
+                    "AES256-GCM-SHA384", "AES128-GCM-SHA256",
+                    "AES256-SHA", "AES128-SHA"
+                ],
+                "sigalgs": [
+                    "ecdsa_secp256r1_sha256", "ecdsa_secp384r1_sha384", "ecdsa_secp521r1_sha512",
+                    "ed25519", "rsa_pss_rsae_sha256", "rsa_pss_rsae_sha384", "rsa_pss_rsae_sha512",
+                    "rsa_pkcs1_sha256", "rsa_pkcs1_sha384", "rsa_pkcs1_sha512"
+                ],
+                "curves": ["X25519", "P-256", "P-521", "P-384"]
+            },
+            // nghttp2 as configured by curl
+            "http2": "3:100;4:10485760;2:0|1048510465|0|m,p,s,a"
+        }),
+        // python-requests 2.32 (urllib3 2 on OpenSSL 3), HTTP/1.1 only, same BoringSSL caveats as curl
+        "requests_2" => json!({
+            "headers": {
+                "user-agent": "python-requests/2.32.3",
+                "accept-encoding": "gzip, deflate",
+                "accept": "*/*",
+                "connection": "keep-alive"
+            },
+            "headers_order": ["user-agent", "accept-encoding", "accept", "connection"],
+            "tls": {
+                "alpn": ["http/1.1"],
+                "ciphers": [
+                    "TLS_AES_256_GCM_SHA384", "TLS_CHACHA20_POLY1305_SHA256", "TLS_AES_128_GCM_SHA256",
+                    "ECDHE-ECDSA-AES256-GCM-SHA384", "ECDHE-RSA-AES256-GCM-SHA384",
+                    "ECDHE-ECDSA-AES128-GCM-SHA256", "ECDHE-RSA-AES128-GCM-SHA256",
+                    "ECDHE-ECDSA-CHACHA20-POLY1305", "ECDHE-RSA-CHACHA20-POLY1305",
+                    "ECDHE-ECDSA-AES128-SHA256", "ECDHE-RSA-AES128-SHA256"
+                ],
+                "sigalgs": [
+                    "ecdsa_secp256r1_sha256", "ecdsa_secp384r1_sha384", "ecdsa_secp521r1_sha512",
+                    "ed25519", "rsa_pss_rsae_sha256", "rsa_pss_rsae_sha384", "rsa_pss_rsae_sha512",
+                    "rsa_pkcs1_sha256", "rsa_pkcs1_sha384", "rsa_pkcs1_sha512"
+                ],
+                "curves": ["X25519", "P-256", "P-521", "P-384"]
+            }
+        }),
+        // Googlebot: no client hints, crawls over HTTP/2 when the site supports it
+        "googlebot" => json!({
+            "headers": {
+                "user-agent": "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+                "accept": "text/html,application/xhtml+xml,application/signed-exchange;v=b3,application/xml;q=0.9,*/*;q=0.8",
+                "accept-encoding": "gzip, deflate, br",
+                "from": "googlebot(at)googlebot.com"
+            },
+            "headers_order": ["user-agent", "accept", "accept-encoding", "from"],
+            "tls": {"alpn": ["h2", "http/1.1"]}
+        }),
+        // Bingbot: no client hints, HTTP/1.1 only
+        "bingbot" => json!({
+            "headers": {
+                "user-agent": "Mozilla/5.0 (compatible; bingbot/2.0; +http://www.bing.com/bingbot.htm)",
+                "accept": "*/*",
+                "accept-encoding": "gzip, deflate"
+            },
+            "headers_order": ["user-agent", "accept", "accept-encoding"],
+            "tls": {"alpn": ["http/1.1"]}
+        }),
+        _ => return None,
+    })
+}
+
+/// Supported impersonation names: the rquest browsers followed by the built-in profiles.
+pub const IMPERSONATE_LIST: [&str; 61] = [
+    "chrome_100",
+    "chrome_101",
+    "chrome_104",
+    "chrome_105",
+    "chrome_106",
+    "chrome_107",
+    "chrome_108",
+    "chrome_109",
+    "chrome_114",
+    "chrome_116",
+    "chrome_117",
+    "chrome_118",
+    "chrome_119",
+    "chrome_120",
+    "chrome_123",
+    "chrome_124",
+    "chrome_126",
+    "chrome_127",
+    "chrome_128",
+    "chrome_129",
+    "chrome_130",
+    "chrome_131",
+    "safari_ios_16.5",
+    "safari_ios_17.2",
+    "safari_ios_17.4.1",
+    "safari_ios_18.1.1",
+    "safari_15.3",
+    "safari_15.5",
+    "safari_15.6.1",
+    "safari_16",
+    "safari_16.5",
+    "safari_17.0",
+    "safari_17.2.1",
+    "safari_17.4.1",
+    "safari_17.5",
+    "safari_18",
+    "safari_18.2",
+    "safari_ipad_18",
+    "okhttp_3.9",
+    "okhttp_3.11",
+    "okhttp_3.13",
+    "okhttp_3.14",
+    "okhttp_4.9",
+    "okhttp_4.10",
+    "okhttp_5",
+    "edge_101",
+    "edge_122",
+    "edge_127",
+    "edge_131",
+    "firefox_109",
+    "firefox_117",
+    "firefox_128",
+    "firefox_133",
+    "brave_1.73",
+    "samsung_internet_27",
+    "android_webview_131",
+    "tor_14",
+    "curl_8",
+    "requests_2",
+    "googlebot",
+    "bingbot",
+];
+
+/// Supported `impersonate_os` values.
+pub const IMPERSONATE_OS_LIST: [&str; 5] = ["windows", "macos", "linux", "android", "ios"];
+
+/// Family aliases resolving to the newest available impersonation of the family.
+pub const IMPERSONATE_ALIASES: [(&str, &str); 13] = [
+    ("chrome", "chrome_131"),
+    ("chrome_latest", "chrome_131"),
+    ("edge", "edge_131"),
+    ("edge_latest", "edge_131"),
+    ("firefox", "firefox_133"),
+    ("firefox_latest", "firefox_133"),
+    ("firefox_esr", "firefox_128"),
+    ("safari", "safari_18.2"),
+    ("safari_latest", "safari_18.2"),
+    ("safari_ios", "safari_ios_18.1.1"),
+    ("okhttp", "okhttp_5"),
+    ("brave", "brave_1.73"),
+    ("tor", "tor_14"),
+];
+
+/// Impersonation name an alias points to, other names are returned as is.
+fn resolve_alias(name: &str) -> &str {
+    IMPERSONATE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name, |(_, target)| target)
+}
+
+/// Impersonation settings of an rquest browser or of a built-in profile.
+pub fn impersonate_settings(name: &str) -> Result<ImpersonateSettings> {
+    let name = resolve_alias(name);
+    if let Some(profile) = builtin_json_profile(name) {
+        let mut settings = None;
+        Profile::from_json(&profile)?.apply(&mut settings)?;
+        return settings.ok_or_else(|| anyhow!("Empty built-in profile: {name}"));
+    }
+    let Some((base, headers)) = builtin_profile(name) else {
+        let impersonate = Impersonate::from_str(name).map_err(PyValueError::new_err)?;
+        return Ok(mimic::impersonate(impersonate, true));
+    };
+    let mut settings = mimic::impersonate(base, true);
+    if let Some(headermap) = settings.headers.as_mut() {
+        let headermap = headermap.to_mut();
+        for (name, value) in headers {
+            headermap.insert(
+                HeaderName::from_static(name),
+                HeaderValue::from_static(value),
+            );
+        }
+    }
+    Ok(settings)
+}
+
+/// `Accept-Language` for a locale, with the q-values the browser of the user agent sends.
+fn accept_language(user_agent: &str, locale: &str) -> String {
+    let language = locale.split('-').next().unwrap_or(locale);
+    let english = language.eq_ignore_ascii_case("en");
+    if user_agent.contains("Firefox/") {
+        match (english, language == locale) {
+            (true, true) => locale.to_string(),
+            (true, false) => format!("{locale},en;q=0.5"),
+            (false, true) => format!("{locale},en-US;q=0.7,en;q=0.3"),
+            (false, false) => format!("{locale},{language};q=0.8,en-US;q=0.5,en;q=0.3"),
+        }
+    } else if user_agent.contains("Chrome/") {
+        match (english, language == locale) {
+            (true, true) => locale.to_string(),
+            (true, false) => format!("{locale},en;q=0.9"),
+            (false, true) => format!("{locale},en-US;q=0.9,en;q=0.8"),
+            (false, false) => format!("{locale},{language};q=0.9,en-US;q=0.8,en;q=0.7"),
+        }
+    } else if user_agent.contains("Safari/") && language != locale {
+        format!("{locale},{language};q=0.9")
+    } else {
+        locale.to_string()
+    }
+}
+
+/// Version following `marker` in a user agent, e.g. "131.0.0.0" for "Chrome/".
+fn ua_version<'a>(user_agent: &'a str, marker: &str) -> Option<&'a str> {
+    user_agent
+        .split(marker)
+        .nth(1)
+        .and_then(|rest| rest.split([' ', ')', ';']).next())
+}
+
+/// Mobile variant of a desktop browser user agent: Android for Chromium and Firefox, iPhone for Safari.
+fn mobile_user_agent(user_agent: &str) -> Option<String> {
+    if let Some(chrome) = ua_version(user_agent, "Chrome/") {
+        let mut mobile = format!("Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/{chrome} Mobile Safari/537.36");
+        if let Some(edge) = ua_version(user_agent, "Edg/") {
+            mobile.push_str(&format!(" EdgA/{edge}"));
+        }
+        Some(mobile)
+    } else if let Some(firefox) = ua_version(user_agent, "Firefox/") {
+        Some(format!(
+            "Mozilla/5.0 (Android 14; Mobile; rv:{firefox}) Gecko/{firefox} Firefox/{firefox}"
+        ))
+    } else {
+        let safari = ua_version(user_agent, "Version/")?;
+        Some(format!("Mozilla/5.0 (iPhone; CPU iPhone OS {} like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/{safari} Mobile/15E148 Safari/604.1", safari.replace('.', "_")))
+    }
+}
+
+/// Switch the impersonation headers to the mobile variant of the browser.
+pub fn set_mobile(settings: &mut Option<ImpersonateSettings>) -> Result<()> {
+    let headers = impersonate_settings_mut(settings)
+        .headers
+        .get_or_insert_with(Default::default)
+        .to_mut();
+    let user_agent = headers
+        .get(USER_AGENT)
+        .and_then(|ua| ua.to_str().ok())
+        .unwrap_or_default();
+    if user_agent.contains("Mobile") {
+        return Ok(());
+    }
+    let mobile = mobile_user_agent(user_agent)
+        .ok_or_else(|| anyhow!("mobile requires a browser impersonation, got: {user_agent:?}"))?;
+    headers.insert(USER_AGENT, HeaderValue::from_str(&mobile)?);
+    if headers.contains_key("sec-ch-ua") {
+        headers.insert("sec-ch-ua-mobile", HeaderValue::from_static("?1"));
+        headers.insert(
+            "sec-ch-ua-platform",
+            HeaderValue::from_static("\"Android\""),
+        );
+    }
+    Ok(())
+}
+
+/// Desktop browser user agent on another OS ("windows", "macos" or "linux"), Safari only on macOS.
+fn os_user_agent(user_agent: &str, os: &str) -> Option<String> {
+    let (product, rest) = user_agent.split_once(" (")?;
+    let (_, engine) = rest.split_once(')')?;
+    if rest.starts_with("compatible") || user_agent.contains("Mobile") {
+        return None;
+    }
+    let firefox = ua_version(user_agent, "rv:");
+    let safari = !user_agent.contains("Chrome/") && firefox.is_none();
+    let platform = match (os, firefox) {
+        _ if safari && os != "macos" => return None,
+        ("windows", _) => "Windows NT 10.0; Win64; x64",
+        ("macos", Some(_)) => "Macintosh; Intel Mac OS X 10.15",
+        ("macos", None) => "Macintosh; Intel Mac OS X 10_15_7",
+        _ => "X11; Linux x86_64",
+    };
+    let rv = firefox.map(|rv| format!("; rv:{rv}")).unwrap_or_default();
+    Some(format!("{product} ({platform}{rv}){engine}"))
+}
+
+/// Switch the impersonation headers to the browser on an OS: "windows", "macos", "linux",
+/// or "android" and "ios" for the mobile variant.
+pub fn set_os(settings: &mut Option<ImpersonateSettings>, os: &str) -> Result<()> {
+    let platform = match os {
+        "windows" => "\"Windows\"",
+        "macos" => "\"macOS\"",
+        "linux" => "\"Linux\"",
+        "android" | "ios" => {
+            let user_agent = settings
+                .as_ref()
+                .and_then(|s| s.headers.as_ref())
+                .and_then(|headers| headers.get(USER_AGENT))
+                .and_then(|ua| ua.to_str().ok())
+                .unwrap_or_default();
+            let safari = user_agent.contains("Safari/") && !user_agent.contains("Chrome/");
+            if (os == "ios") != safari {
+                return Err(anyhow!(
+                    "impersonate_os={os:?} is not available for {user_agent:?}"
+                ));
+            }
+            return set_mobile(settings);
+        }
+        _ => {
+            return Err(anyhow!(
+                "Invalid impersonate_os: {os}. \
+                 Expected \"windows\", \"macos\", \"linux\", \"android\" or \"ios\""
+            ))
+        }
+    };
+    let headers = impersonate_settings_mut(settings)
+        .headers
+        .get_or_insert_with(Default::default)
+        .to_mut();
+    let user_agent = headers
+        .get(USER_AGENT)
+        .and_then(|ua| ua.to_str().ok())
+        .unwrap_or_default();
+    let os_user_agent = os_user_agent(user_agent, os)
+        .ok_or_else(|| anyhow!("impersonate_os={os:?} is not available for {user_agent:?}"))?;
+    headers.insert(USER_AGENT, HeaderValue::from_str(&os_user_agent)?);
+    if headers.contains_key("sec-ch-ua-platform") {
+        headers.insert("sec-ch-ua-platform", HeaderValue::from_static(platform));
+    }
+    Ok(())
+}
+
+/// Default headers of an impersonation, in the order they are sent.
+pub fn default_headers(name: &str, os: Option<&str>) -> Result<IndexMapSSR> {
+    let mut settings = Some(impersonate_settings(name)?);
+    if let Some(os) = os {
+        set_os(&mut settings, os)?;
+    }
+    let settings = impersonate_settings_mut(&mut settings);
+    let mut headers = settings
+        .headers
+        .take()
+        .map(Cow::into_owned)
+        .unwrap_or_default();
+    let order = settings.headers_order.take().unwrap_or_default();
+    let value = |value: HeaderValue| String::from_utf8_lossy(value.as_bytes()).into_owned();
+    let mut sorted: IndexMapSSR = order
+        .iter()
+        .filter_map(|name| Some((name.to_string(), value(headers.remove(name)?))))
+        .collect();
+    for (name, header_value) in headers {
+        if let Some(name) = name {
+            sorted.insert(name.to_string(), value(header_value));
+        }
+    }
+    Ok(sorted)
+}
+
+/// `Accept` and `Priority` a browser sends for a subresource request context ("xhr", "fetch" or "image").
+fn context_accept_priority(
+    user_agent: &str,
+    context: &str,
+) -> (&'static str, Option<&'static str>) {
+    let image = context == "image";
+    if user_agent.contains("Chrome/") {
+        if image {
+            (
+                "image/avif,image/webp,image/apng,image/svg+xml,image/*,*/*;q=0.8",
+                Some("i"),
+            )
+        } else {
+            ("*/*", Some("u=1, i"))
+        }
+    } else if user_agent.contains("Firefox/") {
+        if image {
+            (
+                "image/avif,image/webp,image/png,image/svg+xml,image/*;q=0.8,*/*;q=0.5",
+                Some("u=5, i"),
+            )
+        } else {
+            ("*/*", Some("u=4"))
+        }
+    } else if image && user_agent.contains("Safari/") {
+        ("image/webp,image/avif,image/jxl,image/heic,image/heic-sequence,video/*;q=0.8,image/png,image/svg+xml,image/*;q=0.8,*/*;q=0.5", None)
+    } else {
+        ("*/*", None)
+    }
+}
+
+/// Switch the impersonation headers from a document navigation to a subresource request context:
+/// "document" (default), "xhr", "fetch" or "image".
+pub fn set_request_context(
+    settings: &mut Option<ImpersonateSettings>,
+    context: &str,
+) -> Result<()> {
+    let (dest, mode) = match context {
+        "document" => return Ok(()),
+        // XMLHttpRequest and fetch() send the same headers
+        "xhr" | "fetch" => ("empty", "cors"),
+        "image" => ("image", "no-cors"),
+        _ => {
+            return Err(anyhow!(
+                "Invalid request_context: {context}. Expected \"document\", \"xhr\", \"fetch\" or \"image\""
+            ))
+        }
+    };
+    let headers = impersonate_settings_mut(settings)
+        .headers
+        .get_or_insert_with(Default::default)
+        .to_mut();
+    let user_agent = headers
+        .get(USER_AGENT)
+        .and_then(|ua| ua.to_str().ok())
+        .unwrap_or_default();
+    let (accept, priority) = context_accept_priority(user_agent, context);
+
+    headers.insert(ACCEPT, HeaderValue::from_static(accept));
+    if let Some(priority) = priority.filter(|_| headers.contains_key("priority")) {
+        headers.insert("priority", HeaderValue::from_static(priority));
+    }
+    if headers.contains_key("sec-fetch-mode") {
+        headers.insert("sec-fetch-dest", HeaderValue::from_static(dest));
+        headers.insert("sec-fetch-mode", HeaderValue::from_static(mode));
+        headers.insert("sec-fetch-site", HeaderValue::from_static("same-origin"));
+    }
+    headers.remove("sec-fetch-user");
+    headers.remove(UPGRADE_INSECURE_REQUESTS);
+    Ok(())
+}
+
+/// Add the `Connection: keep-alive` header browsers send over HTTP/1.1 to a browser impersonation.
+pub fn set_keep_alive(settings: &mut Option<ImpersonateSettings>) {
+    let Some(headers) = settings.as_mut().and_then(|s| s.headers.as_mut()) else {
+        return;
+    };
+    if headers.contains_key("sec-fetch-mode") && !headers.contains_key(CONNECTION) {
+        headers
+            .to_mut()
+            .insert(CONNECTION, HeaderValue::from_static("keep-alive"));
+    }
+}
+
+/// Drop the default headers and/or the HTTP/2 settings of an impersonation, keeping its TLS
+/// fingerprint. The header order is kept, the headers of the client are sent in the browser's order.
+pub fn skip_emulation(settings: &mut ImpersonateSettings, skip_headers: bool, skip_http2: bool) {
+    if skip_headers {
+        settings.headers = None;
+    }
+    if skip_http2 {
+        settings.http2 = None;
+    }
+}
+
+/// Rewrite `Accept-Language` of the impersonation headers for a locale (e.g. "de-DE").
+pub fn set_locale(settings: &mut Option<ImpersonateSettings>, locale: &str) -> Result<()> {
+    let valid = locale.split('-').enumerate().all(|(i, part)| {
+        let len = if i == 0 { 2..=3 } else { 2..=8 };
+        len.contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+    if !valid {
+        return Err(anyhow!("Invalid locale: {locale}. Expected e.g. \"de-DE\""));
+    }
+
+    let headers = impersonate_settings_mut(settings)
+        .headers
+        .get_or_insert_with(Default::default)
+        .to_mut();
+    let user_agent = headers
+        .get(USER_AGENT)
+        .and_then(|ua| ua.to_str().ok())
+        .unwrap_or_default();
+    let value = HeaderValue::from_str(&accept_language(user_agent, locale))?;
+    headers.insert(ACCEPT_LANGUAGE, value);
+    Ok(())
+}
+
+/// Client options editing the impersonation settings, kept to apply them again to another impersonation.
+#[derive(Debug, Default)]
+pub struct SettingsOptions {
+    pub profile: Option<Profile>,
+    pub skip_headers: bool,
+    pub skip_http2: bool,
+    pub os: Option<String>,
+    pub mobile: bool,
+    pub request_context: Option<String>,
+    pub locale: Option<String>,
+    pub keep_alive: bool,
+    pub alpn: Option<Vec<String>>,
+    pub cert_compression: Option<Vec<String>>,
+    pub tls_ciphers: Option<Vec<String>>,
+    pub tls_sigalgs: Option<Vec<String>>,
+    pub tls_curves: Option<Vec<String>>,
+    pub tls_extension_order: Option<Vec<u8>>,
+    pub http2_fingerprint: Option<String>,
+    pub http2_priorities: Option<Vec<(u32, u32, u16, bool)>>,
+    pub http2_headers_priority: Option<(u32, u16, bool)>,
+    pub http2_settings: Option<Value>,
+}
+
+impl SettingsOptions {
+    /// Settings of the impersonation (the profile's base when None) with the options applied,
+    /// the profile first and the client arguments taking precedence over it.
+    pub fn settings(&self, impersonate: Option<&str>) -> Result<Option<ImpersonateSettings>> {
+        let impersonate = impersonate.or(self
+            .profile
+            .as_ref()
+            .and_then(|profile| profile.impersonate.as_deref()));
+        let mut settings = impersonate.map(impersonate_settings).transpose()?;
+        if let Some(profile) = &self.profile {
+            profile.apply(&mut settings)?;
+        }
+
+        // Before the options editing the impersonation
+        if let Some(settings) = &mut settings {
+            skip_emulation(settings, self.skip_headers, self.skip_http2);
+        }
+        if let Some(os) = &self.os {
+            set_os(&mut settings, os)?;
+        }
+        if self.mobile {
+            set_mobile(&mut settings)?;
+        }
+        if let Some(request_context) = &self.request_context {
+            set_request_context(&mut settings, request_context)?;
+        }
+        if let Some(locale) = &self.locale {
+            set_locale(&mut settings, locale)?;
+        }
+        // The HTTP/2 stack drops (and logs) connection headers, so only when HTTP/1.1 is certain
+        if self.keep_alive {
+            set_keep_alive(&mut settings);
+        }
+
+        if let Some(alpn) = &self.alpn {
+            tls_settings_mut(&mut settings).alpn_protos = parse_alpn(alpn)?;
+        }
+        if let Some(cert_compression) = &self.cert_compression {
+            tls_settings_mut(&mut settings).cert_compression_algorithm =
+                parse_cert_compression(cert_compression)?;
+        }
+        if let Some(tls_ciphers) = &self.tls_ciphers {
+            tls_settings_mut(&mut settings).cipher_list = Some(tls_ciphers.join(":").into());
+        }
+        if let Some(tls_sigalgs) = &self.tls_sigalgs {
+            tls_settings_mut(&mut settings).sigalgs_list = Some(tls_sigalgs.join(":").into());
+        }
+        if let Some(tls_curves) = &self.tls_curves {
+            tls_settings_mut(&mut settings).curves = Some(parse_curves(tls_curves)?);
+        }
+        if let Some(tls_extension_order) = &self.tls_extension_order {
+            tls_settings_mut(&mut settings).extension_permutation_indices =
+                Some(parse_extension_order(tls_extension_order)?);
+        }
+
+        if let Some(http2_fingerprint) = &self.http2_fingerprint {
+            set_akamai_h2(&mut settings, http2_fingerprint)?;
+        }
+        if let Some(http2_priorities) = &self.http2_priorities {
+            set_http2_priorities(&mut settings, http2_priorities)?;
+        }
+        if let Some(http2_headers_priority) = self.http2_headers_priority {
+            set_http2_headers_priority(&mut settings, http2_headers_priority)?;
+        }
+        if let Some(http2_settings) = &self.http2_settings {
+            set_http2_settings(&mut settings, http2_settings)?;
+        }
+        Ok(settings)
+    }
+}
+
+/// Headers that belong to the request rather than to the browser.
+const HAR_SKIPPED_HEADERS: [&str; 5] = [
+    "cookie",
+    "host",
+    "content-length",
+    "content-type",
+    "referer",
+];
+
+/// Build a profile (in the `Profile::from_json` format) from the first request of a HAR archive
+/// and, optionally, a captured ClientHello.
+pub fn profile_from_har(har: &Value, client_hello: Option<&[u8]>) -> Result<Value> {
+    let headers = har
+        .pointer("/log/entries/0/request/headers")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("Invalid HAR: no request headers in log.entries[0]"))?;
+
+    let mut profile = Map::new();
+    let mut profile_headers = Map::new();
+    let mut headers_order = Vec::new();
+    for header in headers {
+        let (Some(name), Some(value)) = (
+            header.get("name").and_then(Value::as_str),
+            header.get("value").and_then(Value::as_str),
+        ) else {
+            return Err(anyhow!("Invalid HAR: header without name or value"));
+        };
+        let name = name.to_ascii_lowercase();
+        if name.starts_with(':') || HAR_SKIPPED_HEADERS.contains(&name.as_str()) {
+            continue;
+        }
+        if !profile_headers.contains_key(&name) {
+            headers_order.push(Value::String(name.clone()));
+        }
+        profile_headers.insert(name, Value::String(value.to_string()));
+    }
+    profile.insert("headers".to_string(), Value::Object(profile_headers));
+    profile.insert("headers_order".to_string(), Value::Array(headers_order));
+
+    if let Some(client_hello) = client_hello {
+        let client_hello = parse_client_hello(client_hello)?;
+        profile.insert(
+            "tls".to_string(),
+            json!({
+                "alpn": client_hello.alpn,
+                "ciphers": client_hello.ciphers,
+                "sigalgs": client_hello.sigalgs,
+                "curves": client_hello.curves,
+                "cert_compression": client_hello.cert_compression,
+                "ech_grease": client_hello.ech_grease,
+            }),
+        );
+    }
+    Ok(Value::Object(profile))
+}
+
+#[cfg(test)]
+mod profile_tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_profile_from_json() {
+        let profile = Profile::from_json(&json!({
+            "impersonate": "chrome_131",
+            "headers": {"User-Agent": "test", "Accept": "*/*"},
+            "headers_order": ["accept", "user-agent"],
+            "tls": {"curves": ["X25519"], "permute_extensions": false},
+            "http2": "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p"
+        }))
+        .unwrap();
+        assert_eq!(profile.impersonate.as_deref(), Some("chrome_131"));
+
+        let mut settings = None;
+        profile.apply(&mut settings).unwrap();
+        let settings = settings.unwrap();
+        assert_eq!(settings.headers.unwrap()["user-agent"], "test");
+        assert_eq!(settings.headers_order.unwrap()[0], "accept");
+        assert_eq!(settings.tls.curves.unwrap().len(), 1);
+        assert_eq!(settings.tls.permute_extensions, Some(false));
+        assert!(settings.http2.is_some());
+    }
+
+    #[test]
+    fn test_impersonate_settings_brave() {
+        let brave = impersonate_settings("brave_1.73").unwrap();
+        let chrome = impersonate_settings("chrome_131").unwrap();
+        let (brave_headers, chrome_headers) = (brave.headers.unwrap(), chrome.headers.unwrap());
+        assert!(brave_headers["sec-ch-ua"]
+            .to_str()
+            .unwrap()
+            .starts_with("\"Brave\""));
+        assert_eq!(brave_headers["sec-gpc"], "1");
+        assert_eq!(brave_headers["user-agent"], chrome_headers["user-agent"]);
+        assert_eq!(brave.tls.curves, chrome.tls.curves);
+        assert!(impersonate_settings("brave_0").is_err());
+    }
+
+    #[test]
+    fn test_impersonate_settings_tor() {
+        let tor = impersonate_settings("tor_14").unwrap();
+        let firefox = impersonate_settings("firefox_128").unwrap();
+        assert_eq!(
+            tor.headers.unwrap()["user-agent"],
+            "Mozilla/5.0 (Windows NT 10.0; rv:128.0) Gecko/20100101 Firefox/128.0"
+        );
+        assert_eq!(tor.tls.cipher_list, firefox.tls.cipher_list);
+    }
+
+    #[test]
+    fn test_impersonate_settings_honest() {
+        let curl = impersonate_settings("curl_8").unwrap();
+        assert_eq!(curl.headers.unwrap()["user-agent"], "curl/8.11.0");
+        assert_eq!(
+            crate::fingerprint::akamai_h2(&curl.http2.unwrap()),
+            "3:100;4:10485760;2:0|1048510465|0|m,p,s,a"
+        );
+        let requests = impersonate_settings("requests_2").unwrap();
+        assert_eq!(requests.headers_order.unwrap().len(), 4);
+        assert!(requests.http2.is_none());
+        assert!(!requests.tls.enable_ech_grease);
+    }
+
+    #[test]
+    fn test_skip_emulation() {
+        let chrome = impersonate_settings("chrome_131").unwrap();
+        let mut settings = impersonate_settings("chrome_131").unwrap();
+        skip_emulation(&mut settings, true, false);
+        assert!(settings.headers.is_none());
+        assert!(settings.headers_order.is_some());
+        assert!(settings.http2.is_some());
+        skip_emulation(&mut settings, false, true);
+        assert!(settings.http2.is_none());
+        assert_eq!(settings.tls.curves, chrome.tls.curves);
+    }
+
+    #[test]
+    fn test_impersonate_settings_crawlers() {
+        for name in ["googlebot", "bingbot"] {
+            let headers = impersonate_settings(name).unwrap().headers.unwrap();
+            assert!(headers["user-agent"].to_str().unwrap().contains("bot"));
+            assert!(!headers.contains_key("sec-ch-ua"));
+        }
+    }
+
+    #[test]
+    fn test_impersonate_aliases() {
+        assert_eq!(resolve_alias("chrome"), "chrome_131");
+        assert_eq!(resolve_alias("chrome_130"), "chrome_130");
+        for (alias, _) in IMPERSONATE_ALIASES {
+            assert!(impersonate_settings(alias).is_ok(), "{alias}");
+        }
+    }
+
+    #[test]
+    fn test_impersonate_list() {
+        for name in IMPERSONATE_LIST {
+            assert!(impersonate_settings(name).is_ok(), "{name}");
+        }
+        for (_, target) in IMPERSONATE_ALIASES {
+            assert!(IMPERSONATE_LIST.contains(&target), "{target}");
+        }
+    }
+
+    #[test]
+    fn test_set_mobile() {
+        for (name, expected) in [
+            ("chrome_131", "Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Mobile Safari/537.36"),
+            ("edge_131", "Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Mobile Safari/537.36 EdgA/131.0.0.0"),
+            ("firefox_133", "Mozilla/5.0 (Android 14; Mobile; rv:133.0) Gecko/133.0 Firefox/133.0"),
+            ("safari_18", "Mozilla/5.0 (iPhone; CPU iPhone OS 18_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.0 Mobile/15E148 Safari/604.1"),
+        ] {
+            let mut settings = Some(impersonate_settings(name).unwrap());
+            set_mobile(&mut settings).unwrap();
+            let headers = settings.unwrap().headers.unwrap();
+            assert_eq!(headers["user-agent"], expected, "{name}");
+            if name.starts_with("chrome") {
+                assert_eq!(headers["sec-ch-ua-mobile"], "?1");
+                assert_eq!(headers["sec-ch-ua-platform"], "\"Android\"");
+            }
+        }
+        assert!(set_mobile(&mut Some(impersonate_settings("okhttp_5").unwrap())).is_err());
+        assert!(set_mobile(&mut None).is_err());
+    }
+
+    #[test]
+    fn test_set_os() {
+        for (name, os, expected) in [
+            ("chrome_131", "windows", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36"),
+            ("edge_131", "linux", "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36 Edg/131.0.0.0"),
+            ("firefox_133", "windows", "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:133.0) Gecko/20100101 Firefox/133.0"),
+            ("firefox_133", "android", "Mozilla/5.0 (Android 14; Mobile; rv:133.0) Gecko/133.0 Firefox/133.0"),
+        ] {
+            let mut settings = Some(impersonate_settings(name).unwrap());
+            set_os(&mut settings, os).unwrap();
+            let headers = settings.unwrap().headers.unwrap();
+            assert_eq!(headers["user-agent"], expected, "{name}");
+        }
+        let mut settings = Some(impersonate_settings("chrome_131").unwrap());
+        set_os(&mut settings, "windows").unwrap();
+        assert_eq!(
+            settings.unwrap().headers.unwrap()["sec-ch-ua-platform"],
+            "\"Windows\""
+        );
+        for os in IMPERSONATE_OS_LIST {
+            let name = if os == "ios" {
+                "safari_18"
+            } else {
+                "chrome_131"
+            };
+            let mut settings = Some(impersonate_settings(name).unwrap());
+            assert!(set_os(&mut settings, os).is_ok(), "{os}");
+        }
+        for (name, os) in [
+            ("safari_18", "windows"),
+            ("chrome_131", "ios"),
+            ("safari_ios_18.1.1", "macos"),
+            ("googlebot", "linux"),
+            ("chrome_131", "beos"),
+        ] {
+            let mut settings = Some(impersonate_settings(name).unwrap());
+            assert!(set_os(&mut settings, os).is_err(), "{name} {os}");
+        }
+    }
+
+    #[test]
+    fn test_default_headers() {
+        let headers = default_headers("chrome_131", None).unwrap();
+        let chrome = impersonate_settings("chrome_131").unwrap().headers.unwrap();
+        assert_eq!(
+            headers.keys().collect::<Vec<_>>(),
+            chrome.keys().map(|name| name.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!(headers["sec-ch-ua-platform"], "\"macOS\"");
+        let headers = default_headers("chrome", Some("linux")).unwrap();
+        assert_eq!(headers["sec-ch-ua-platform"], "\"Linux\"");
+        assert!(default_headers("chrome_0", None).is_err());
+    }
+
+    #[test]
+    fn test_set_keep_alive() {
+        let mut settings = Some(impersonate_settings("firefox_133").unwrap());
+        set_keep_alive(&mut settings);
+        assert_eq!(
+            settings.unwrap().headers.unwrap()["connection"],
+            "keep-alive"
+        );
+
+        let mut settings = Some(impersonate_settings("okhttp_5").unwrap());
+        set_keep_alive(&mut settings);
+        assert!(!settings
+            .unwrap()
+            .headers
+            .unwrap()
+            .contains_key("connection"));
+        set_keep_alive(&mut None);
+    }
+
+    #[test]
+    fn test_set_request_context() {
+        let mut settings = Some(impersonate_settings("chrome_131").unwrap());
+        set_request_context(&mut settings, "fetch").unwrap();
+        let headers = settings.unwrap().headers.unwrap();
+        assert_eq!(headers["accept"], "*/*");
+        assert_eq!(headers["sec-fetch-dest"], "empty");
+        assert_eq!(headers["sec-fetch-mode"], "cors");
+        assert_eq!(headers["sec-fetch-site"], "same-origin");
+        assert_eq!(headers["priority"], "u=1, i");
+        assert!(!headers.contains_key("sec-fetch-user"));
+        assert!(!headers.contains_key("upgrade-insecure-requests"));
+
+        let mut settings = Some(impersonate_settings("firefox_133").unwrap());
+        set_request_context(&mut settings, "image").unwrap();
+        let headers = settings.unwrap().headers.unwrap();
+        assert_eq!(headers["sec-fetch-dest"], "image");
+        assert_eq!(headers["priority"], "u=5, i");
+
+        let mut settings = Some(impersonate_settings("chrome_131").unwrap());
+        set_request_context(&mut settings, "document").unwrap();
+        assert_eq!(
+            settings.unwrap().headers.unwrap()["sec-fetch-dest"],
+            "document"
+        );
+        assert!(set_request_context(&mut None, "script").is_err());
+    }
+
+    #[test]
+    fn test_set_locale() {
+        for (name, expected) in [
+            ("chrome_131", "de-DE,de;q=0.9,en-US;q=0.8,en;q=0.7"),
+            ("firefox_133", "de-DE,de;q=0.8,en-US;q=0.5,en;q=0.3"),
+            ("safari_18", "de-DE,de;q=0.9"),
+            ("okhttp_5", "de-DE"),
+        ] {
+            let mut settings = Some(impersonate_settings(name).unwrap());
+            set_locale(&mut settings, "de-DE").unwrap();
+            assert_eq!(
+                settings.unwrap().headers.unwrap()["accept-language"],
+                expected
+            );
+        }
+        let mut settings = Some(impersonate_settings("chrome_131").unwrap());
+        set_locale(&mut settings, "en-GB").unwrap();
+        assert_eq!(
+            settings.unwrap().headers.unwrap()["accept-language"],
+            "en-GB,en;q=0.9"
+        );
+        assert!(set_locale(&mut None, "german").is_err());
+        assert!(set_locale(&mut None, "de_DE").is_err());
+    }
+
+    #[test]
+    fn test_impersonate_settings_mobile() {
+        for name in ["samsung_internet_27", "android_webview_131"] {
+            let headers = impersonate_settings(name).unwrap().headers.unwrap();
+            assert_eq!(headers["sec-ch-ua-mobile"], "?1");
+            assert_eq!(headers["sec-ch-ua-platform"], "\"Android\"");
+            assert!(headers["user-agent"].to_str().unwrap().contains("Mobile"));
+        }
+    }
+
+    #[test]
+    fn test_settings_options() {
+        let options = SettingsOptions {
+            profile: Some(Profile::from_json(&json!({"impersonate": "chrome_131"})).unwrap()),
+            os: Some("linux".to_string()),
+            locale: Some("de-DE".to_string()),
+            ..Default::default()
+        };
+        for (name, user_agent) in [(None, "Chrome/131"), (Some("firefox_133"), "Firefox/133")] {
+            let settings = options.settings(name).unwrap().unwrap();
+            let headers = settings.headers.unwrap();
+            let ua = headers["user-agent"].to_str().unwrap();
+            assert!(
+                ua.contains(user_agent) && ua.contains("X11; Linux x86_64"),
+                "{ua}"
+            );
+            assert!(headers["accept-language"]
+                .to_str()
+                .unwrap()
+                .starts_with("de-DE,"));
+        }
+        assert!(SettingsOptions::default().settings(None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_profile_from_har() {
+        let har = json!({"log": {"entries": [{"request": {"headers": [
+            {"name": ":method", "value": "GET"},
+            {"name": "User-Agent", "value": "test"},
+            {"name": "Cookie", "value": "a=b"},
+            {"name": "Accept", "value": "*/*"}
+        ]}}]}});
+        let profile = profile_from_har(&har, None).unwrap();
+        assert_eq!(
+            profile,
+            json!({
+                "headers": {"user-agent": "test", "accept": "*/*"},
+                "headers_order": ["user-agent", "accept"]
+            })
+        );
+        assert!(Profile::from_json(&profile).is_ok());
+        assert!(profile_from_har(&json!({"log": {"entries": []}}), None).is_err());
+    }
+
+    #[test]
+    fn test_profile_from_json_invalid() {
+        assert!(Profile::from_json(&json!([])).is_err());
+        assert!(Profile::from_json(&json!({"headers": []})).is_err());
+        assert!(Profile::from_json(&json!({"tls": {"curves": "X25519"}})).is_err());
+        assert!(Profile::from_json(&json!({"tls": {"extension_order": [300]}})).is_err());
+    }
+}
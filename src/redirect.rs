@@ -0,0 +1,465 @@
+use anyhow::{anyhow, Result};
+use indexmap::IndexMap;
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyList, PyString, PyTuple},
+};
+use rquest::{
+    header::{
+        HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_ENCODING, CONTENT_LENGTH,
+        CONTENT_TYPE, COOKIE, LOCATION, PROXY_AUTHORIZATION, REFERER, TRANSFER_ENCODING,
+    },
+    multipart, Body, Client, Method, Request, RequestBuilder, StatusCode, Url,
+};
+use tokio::fs::File;
+use tokio_util::codec::{BytesCodec, FramedRead};
+
+use crate::error::TooManyRedirects;
+
+/// Which redirects are followed, passed as `Client(redirect_policy=...)`: "same_origin",
+/// "same_host", "https_upgrade_only", a `callback(from_url, to_url, status)` returning whether to
+/// follow, or a list of them that must all allow the redirect.
+///
+/// A redirect that is not allowed is not followed, the 3xx response is returned.
+#[derive(Debug)]
+pub struct RedirectPolicy {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug)]
+enum Rule {
+    /// Same scheme, host and port
+    SameOrigin,
+    /// Same host, any scheme and port
+    SameHost,
+    /// Only from http:// to https:// on the same host
+    HttpsUpgradeOnly,
+    Callback(PyObject),
+}
+
+impl<'py> FromPyObject<'py> for RedirectPolicy {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let rules = if ob.is_instance_of::<PyList>() || ob.is_instance_of::<PyTuple>() {
+            ob.try_iter()?
+                .map(|rule| Rule::from_py(&rule?))
+                .collect::<PyResult<_>>()?
+        } else {
+            vec![Rule::from_py(ob)?]
+        };
+        Ok(RedirectPolicy { rules })
+    }
+}
+
+impl Rule {
+    fn from_py(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(name) = ob.downcast::<PyString>() {
+            return match name.to_str()? {
+                "same_origin" => Ok(Rule::SameOrigin),
+                "same_host" => Ok(Rule::SameHost),
+                "https_upgrade_only" => Ok(Rule::HttpsUpgradeOnly),
+                name => Err(PyValueError::new_err(format!(
+                    "Invalid redirect_policy: {name}. Expected \"same_origin\", \"same_host\", \
+                     \"https_upgrade_only\" or a callable"
+                ))),
+            };
+        }
+        if ob.is_callable() {
+            return Ok(Rule::Callback(ob.clone().unbind()));
+        }
+        Err(PyValueError::new_err(
+            "redirect_policy must be a str, a callable or a list of them",
+        ))
+    }
+
+    fn allows(&self, from: &Url, to: &Url, status: u16) -> PyResult<bool> {
+        Ok(match self {
+            Rule::SameOrigin => from.origin() == to.origin(),
+            Rule::SameHost => from.host_str() == to.host_str(),
+            Rule::HttpsUpgradeOnly => {
+                from.scheme() == "http"
+                    && to.scheme() == "https"
+                    && from.host_str() == to.host_str()
+            }
+            Rule::Callback(callback) => Python::with_gil(|py| {
+                callback
+                    .bind(py)
+                    .call1((from.as_str(), to.as_str(), status))?
+                    .is_truthy()
+            })?,
+        })
+    }
+}
+
+impl RedirectPolicy {
+    /// Whether all the rules allow the redirect from `from` to `to`.
+    pub fn allows(&self, from: &Url, to: &Url, status: u16) -> PyResult<bool> {
+        for rule in &self.rules {
+            if !rule.allows(from, to, status)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Redirects followed by primp rather than rquest, to control the request of each hop.
+#[derive(Debug)]
+pub struct Redirects {
+    max_redirects: usize,
+    policy: Option<RedirectPolicy>,
+    referer: bool,
+    method_policy: MethodPolicy,
+    /// Headers removed from the requests of cross-origin redirects
+    strip_headers: Vec<HeaderName>,
+}
+
+/// Method of the request following a 301 or 302 redirect of a POST request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MethodPolicy {
+    /// GET without body, like browsers
+    Browser,
+    /// The same method and body (RFC 9110)
+    Strict,
+}
+
+impl Redirects {
+    /// `method_policy` is "browser" or "strict", `forward_headers` are sensitive headers kept on
+    /// cross-origin redirects, `strip_headers` are removed on top of the sensitive headers.
+    pub fn new(
+        max_redirects: usize,
+        policy: Option<RedirectPolicy>,
+        referer: bool,
+        method_policy: Option<&str>,
+        forward_headers: Option<Vec<String>>,
+        strip_headers: Option<Vec<String>>,
+    ) -> Result<Self> {
+        let method_policy = match method_policy.unwrap_or("browser") {
+            "browser" => MethodPolicy::Browser,
+            "strict" => MethodPolicy::Strict,
+            method_policy => {
+                return Err(anyhow!(
+                    "Invalid redirect_method_policy: {method_policy}. \
+                     Expected \"browser\" or \"strict\""
+                ))
+            }
+        };
+        let forward_headers = header_names(forward_headers)?;
+        // The credentials are removed unless forwarded
+        let strip_headers = [AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION]
+            .into_iter()
+            .chain(header_names(strip_headers)?)
+            .filter(|name| !forward_headers.contains(name))
+            .collect();
+        Ok(Redirects {
+            max_redirects,
+            policy,
+            referer,
+            method_policy,
+            strip_headers,
+        })
+    }
+
+    /// Whether the redirect of a `method` request with `status` is followed with the same method
+    /// and body: 307 and 308, 301 and 302 except a POST with the "browser" method policy.
+    pub fn keeps_body(&self, status: StatusCode, method: &Method) -> bool {
+        match status {
+            StatusCode::SEE_OTHER => false,
+            StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND => {
+                self.method_policy != MethodPolicy::Browser || method != Method::POST
+            }
+            _ => true,
+        }
+    }
+
+    /// The request following a redirect response, None to return the response: not a redirect
+    /// or not allowed by the policy. `request` is a copy of the request sent (see `redirect_copy`),
+    /// `status` and `headers` those of its response, and `previous` the URLs already requested,
+    /// to which its URL is added.
+    pub fn next_request(
+        &self,
+        mut request: Request,
+        status: StatusCode,
+        headers: &HeaderMap,
+        previous: &mut Vec<Url>,
+    ) -> Result<Option<Request>> {
+        if !matches!(status.as_u16(), 301 | 302 | 303 | 307 | 308) {
+            return Ok(None);
+        }
+        let Some(location) = headers
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| request.url().join(location).ok())
+            .filter(|location| matches!(location.scheme(), "http" | "https"))
+        else {
+            return Ok(None);
+        };
+        let from = request.url().clone();
+        if let Some(policy) = &self.policy {
+            if !policy.allows(&from, &location, status.as_u16())? {
+                return Ok(None);
+            }
+        }
+        previous.push(from.clone());
+        if previous.len() > self.max_redirects {
+            return Err(TooManyRedirects::new_err(format!(
+                "Exceeded max_redirects ({}), redirect to {location}",
+                self.max_redirects
+            ))
+            .into());
+        }
+
+        // 303: a GET without body (HEAD stays HEAD), 301/302: the same for POST like browsers,
+        // other: the same method and body
+        if !self.keeps_body(status, request.method()) {
+            if request.method() != Method::HEAD {
+                *request.method_mut() = Method::GET;
+            }
+            *request.body_mut() = None;
+            for name in [
+                TRANSFER_ENCODING,
+                CONTENT_ENCODING,
+                CONTENT_TYPE,
+                CONTENT_LENGTH,
+            ] {
+                request.headers_mut().remove(name);
+            }
+        }
+
+        let headers = request.headers_mut();
+        if changes_origin(&from, &location) {
+            for name in &self.strip_headers {
+                headers.remove(name);
+            }
+        }
+        if self.referer {
+            match referer(&from, &location) {
+                Some(referer) => headers.insert(REFERER, referer),
+                None => headers.remove(REFERER),
+            };
+        }
+        *request.url_mut() = location;
+        Ok(Some(request))
+    }
+}
+
+/// Copy of a request to follow its redirect. A streamed body (the multipart `files`) can not be
+/// copied: the copy has none, and `UploadFiles::rewind` streams the files again if the redirect keeps it.
+pub fn redirect_copy(request: &Request) -> Request {
+    request.try_clone().unwrap_or_else(|| {
+        let mut copy = Request::new(request.method().clone(), request.url().clone());
+        *copy.headers_mut() = request.headers().clone();
+        *copy.timeout_mut() = request.timeout().copied();
+        *copy.version_mut() = request.version();
+        copy
+    })
+}
+
+/// Files of a multipart upload (`files`): field names and paths, streamed from the disk
+/// and opened again to send the body after a redirect instead of keeping it in memory.
+#[derive(Debug)]
+pub struct UploadFiles(IndexMap<String, String>);
+
+impl UploadFiles {
+    pub fn new(files: IndexMap<String, String>) -> Self {
+        UploadFiles(files)
+    }
+
+    /// Multipart form streaming the files.
+    pub async fn form(&self) -> Result<multipart::Form> {
+        let mut form = multipart::Form::new();
+        for (file_name, file_path) in &self.0 {
+            let file = File::open(file_path).await?;
+            let stream = FramedRead::new(file, BytesCodec::new());
+            let file_body = Body::wrap_stream(stream);
+            let part = multipart::Part::stream(file_body).file_name(file_name.clone());
+            form = form.part(file_name.clone(), part);
+        }
+        Ok(form)
+    }
+
+    /// Set the body of the request following a redirect, with the `Content-Type` of its new boundary.
+    pub async fn rewind(&self, client: &Client, request: &mut Request) -> Result<()> {
+        let form = self.form().await?;
+        let empty = Request::new(request.method().clone(), request.url().clone());
+        let mut built = RequestBuilder::from_parts(client.clone(), empty)
+            .multipart(form)
+            .build()?;
+        for name in [CONTENT_TYPE, CONTENT_LENGTH] {
+            match built.headers_mut().remove(&name) {
+                Some(value) => request.headers_mut().insert(name, value),
+                None => request.headers_mut().remove(name),
+            };
+        }
+        *request.body_mut() = built.body_mut().take();
+        Ok(())
+    }
+}
+
+/// Whether a redirect leaves the origin of the request, where credentials are not sent.
+/// The upgrade from http:// to https:// on the default ports keeps the origin, like requests.
+fn changes_origin(from: &Url, to: &Url) -> bool {
+    if from.host_str() != to.host_str() {
+        return true;
+    }
+    if from.scheme() == "http" && to.scheme() == "https" && from.port().is_none() {
+        return to.port().is_some();
+    }
+    from.scheme() != to.scheme() || from.port_or_known_default() != to.port_or_known_default()
+}
+
+/// `Referer` of a redirect: the previous URL without credentials and fragment,
+/// None from https:// to http://.
+fn referer(from: &Url, to: &Url) -> Option<HeaderValue> {
+    if from.scheme() == "https" && to.scheme() == "http" {
+        return None;
+    }
+    let mut referer = from.clone();
+    let _ = referer.set_username("");
+    let _ = referer.set_password(None);
+    referer.set_fragment(None);
+    HeaderValue::from_str(referer.as_str()).ok()
+}
+
+fn header_names(names: Option<Vec<String>>) -> Result<Vec<HeaderName>> {
+    names
+        .unwrap_or_default()
+        .iter()
+        .map(|name| {
+            HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| anyhow!("Invalid header name: {name}"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod redirect_tests {
+    use super::*;
+
+    fn allows(rule: Rule, from: &str, to: &str) -> bool {
+        let (from, to) = (Url::parse(from).unwrap(), Url::parse(to).unwrap());
+        RedirectPolicy { rules: vec![rule] }
+            .allows(&from, &to, 302)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_redirect_policy_rules() {
+        assert!(allows(
+            Rule::SameOrigin,
+            "https://a.com/x",
+            "https://a.com:443/y"
+        ));
+        assert!(!allows(Rule::SameOrigin, "https://a.com/", "http://a.com/"));
+        assert!(!allows(
+            Rule::SameOrigin,
+            "https://a.com/",
+            "https://b.a.com/"
+        ));
+        assert!(allows(
+            Rule::SameHost,
+            "https://a.com/",
+            "http://a.com:8080/"
+        ));
+        assert!(!allows(Rule::SameHost, "https://a.com/", "https://b.com/"));
+        assert!(allows(
+            Rule::HttpsUpgradeOnly,
+            "http://a.com/",
+            "https://a.com/"
+        ));
+        assert!(!allows(
+            Rule::HttpsUpgradeOnly,
+            "https://a.com/",
+            "https://a.com/x"
+        ));
+        assert!(!allows(
+            Rule::HttpsUpgradeOnly,
+            "http://a.com/",
+            "https://b.com/"
+        ));
+    }
+
+    #[test]
+    fn test_changes_origin() {
+        let url = |url: &str| Url::parse(url).unwrap();
+        assert!(!changes_origin(
+            &url("https://a.com/x"),
+            &url("https://a.com/y")
+        ));
+        assert!(!changes_origin(
+            &url("http://a.com/"),
+            &url("https://a.com/")
+        ));
+        assert!(changes_origin(
+            &url("https://a.com/"),
+            &url("http://a.com/")
+        ));
+        assert!(changes_origin(
+            &url("http://a.com/"),
+            &url("https://a.com:8443/")
+        ));
+        assert!(changes_origin(
+            &url("https://a.com/"),
+            &url("https://a.com:8443/")
+        ));
+        assert!(changes_origin(
+            &url("https://a.com/"),
+            &url("https://b.a.com/")
+        ));
+    }
+
+    #[test]
+    fn test_next_request() {
+        let strip_headers = Some(vec!["X-Api-Key".into()]);
+        let browser = Redirects::new(20, None, true, None, None, strip_headers).unwrap();
+        let strict = Redirects::new(20, None, true, Some("strict"), None, None).unwrap();
+        let redirect = |redirects: &Redirects, status: u16, method: Method, location: &str| {
+            let mut request = Request::new(method, Url::parse("https://a.com/x#f").unwrap());
+            let headers = request.headers_mut();
+            headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer t"));
+            headers.insert("x-api-key", HeaderValue::from_static("k"));
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+            *request.body_mut() = Some("body".into());
+            let mut headers = HeaderMap::new();
+            headers.insert(LOCATION, HeaderValue::from_str(location).unwrap());
+            let status = StatusCode::from_u16(status).unwrap();
+            redirects
+                .next_request(request, status, &headers, &mut vec![])
+                .unwrap()
+        };
+        let body = |request: &Request| request.body().and_then(|body| body.as_bytes()).is_some();
+
+        let next = redirect(&browser, 302, Method::POST, "/y").unwrap();
+        assert_eq!(
+            (next.method(), next.url().as_str()),
+            (&Method::GET, "https://a.com/y")
+        );
+        assert!(!body(&next) && next.headers().get(CONTENT_TYPE).is_none());
+        assert_eq!(next.headers()[AUTHORIZATION], "Bearer t");
+        assert_eq!(next.headers()[REFERER], "https://a.com/x");
+
+        let next = redirect(&browser, 307, Method::POST, "https://b.com/y").unwrap();
+        assert_eq!(next.method(), Method::POST);
+        assert!(body(&next));
+        assert!(next.headers().get(AUTHORIZATION).is_none());
+        assert!(next.headers().get("x-api-key").is_none());
+
+        let next = redirect(&browser, 301, Method::PUT, "/y").unwrap();
+        assert!(next.method() == Method::PUT && body(&next));
+        let next = redirect(&strict, 302, Method::POST, "/y").unwrap();
+        assert!(next.method() == Method::POST && body(&next));
+        let next = redirect(&strict, 303, Method::POST, "/y").unwrap();
+        assert!(next.method() == Method::GET && !body(&next));
+        let next = redirect(&strict, 303, Method::HEAD, "/y").unwrap();
+        assert_eq!(next.method(), Method::HEAD);
+
+        assert!(!browser.keeps_body(StatusCode::FOUND, &Method::POST));
+        assert!(browser.keeps_body(StatusCode::FOUND, &Method::PUT));
+        assert!(strict.keeps_body(StatusCode::FOUND, &Method::POST));
+        assert!(!strict.keeps_body(StatusCode::SEE_OTHER, &Method::PUT));
+
+        assert!(redirect(&browser, 200, Method::GET, "/y").is_none());
+        assert!(redirect(&browser, 302, Method::GET, "ftp://a.com/").is_none());
+        assert!(Redirects::new(20, None, true, Some("rfc"), None, None).is_err());
+    }
+}
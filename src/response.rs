@@ -1,5 +1,12 @@
-use crate::utils::{get_encoding_from_content, get_encoding_from_headers};
+use crate::error::HTTPError;
+use crate::headers::Headers;
+use crate::json::json_to_py;
+use crate::utils::{
+    content_disposition_filename, get_encoding_from_content, get_encoding_from_headers,
+    percent_decode, sanitize_filename,
+};
 use anyhow::{anyhow, Result};
+use bytes::Bytes;
 use encoding_rs::Encoding;
 use foldhash::fast::RandomState;
 use html2text::{
@@ -7,9 +14,15 @@ use html2text::{
     render::{RichDecorator, TrivialDecorator},
 };
 use indexmap::IndexMap;
-use pyo3::{prelude::*, types::PyBytes};
-use pythonize::pythonize;
-use serde_json::from_slice;
+use pyo3::{
+    prelude::*,
+    types::{PyBytes, PyList},
+};
+use rquest::{
+    header::{HeaderMap, CONTENT_DISPOSITION},
+    StatusCode, Url,
+};
+use std::path::PathBuf;
 
 /// A struct representing an HTTP response.
 ///
@@ -19,28 +32,158 @@ use serde_json::from_slice;
 pub struct Response {
     #[pyo3(get)]
     pub content: Py<PyBytes>,
-    #[pyo3(get)]
-    pub cookies: IndexMap<String, String, RandomState>,
     #[pyo3(get, set)]
     pub encoding: String,
     #[pyo3(get)]
-    pub headers: IndexMap<String, String, RandomState>,
+    pub headers: Py<Headers>,
     #[pyo3(get)]
     pub status_code: u16,
     #[pyo3(get)]
     pub url: String,
+    /// Responses of the followed redirects, oldest first, without their body.
+    #[pyo3(get)]
+    pub history: Py<PyList>,
+    pub error_body_snippet: usize,
+    /// Encoding when neither the `Content-Type` charset nor the `<meta charset>` tag gives one.
+    pub default_encoding: String,
+}
+
+/// Headers, status code and URL of a followed redirect.
+pub type RedirectHop = (HeaderMap, u16, String);
+
+/// Body, headers, status code, final URL and followed redirects read by a request future.
+pub type ResponseParts = (Bytes, HeaderMap, u16, String, Vec<RedirectHop>);
+
+impl Response {
+    /// Filename of the `Content-Disposition` header, else the last segment of the URL path,
+    /// else "download".
+    fn filename(&self) -> String {
+        self.headers
+            .get()
+            .headers
+            .get(CONTENT_DISPOSITION)
+            .and_then(|value| {
+                content_disposition_filename(&String::from_utf8_lossy(value.as_bytes()))
+            })
+            .and_then(|filename| sanitize_filename(&filename))
+            .or_else(|| {
+                let url = Url::parse(&self.url).ok()?;
+                let segment = url.path_segments()?.next_back()?;
+                sanitize_filename(&String::from_utf8_lossy(&percent_decode(
+                    segment.as_bytes(),
+                )))
+            })
+            .unwrap_or_else(|| "download".to_string())
+    }
+
+    pub fn from_parts(
+        py: Python,
+        (content, headers, status_code, url, history): ResponseParts,
+        error_body_snippet: usize,
+        default_encoding: &str,
+    ) -> Result<Self> {
+        let history = history
+            .into_iter()
+            .map(|(headers, status_code, url)| {
+                let parts = (Bytes::new(), headers, status_code, url, Vec::new());
+                let response =
+                    Response::from_parts(py, parts, error_body_snippet, default_encoding)?;
+                Py::new(py, response).map_err(Into::into)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Response {
+            content: PyBytes::new(py, &content).unbind(),
+            encoding: String::new(),
+            headers: Py::new(py, Headers::new(headers))?,
+            status_code,
+            url,
+            history: PyList::new(py, history)?.unbind(),
+            error_body_snippet,
+            default_encoding: default_encoding.to_string(),
+        })
+    }
 }
 
 #[pymethods]
 impl Response {
+    /// Raise `HTTPError` if the status code is 4xx or 5xx.
+    /// The exception has the `url`, `status_code` and `response` attributes.
+    fn raise_for_status(slf: &Bound<'_, Self>) -> PyResult<()> {
+        let py = slf.py();
+        let this = slf.borrow();
+        let kind = match this.status_code {
+            400..=499 => "Client Error",
+            500..=599 => "Server Error",
+            _ => return Ok(()),
+        };
+        let reason = this.reason().unwrap_or_default();
+        let mut message = format!(
+            "{} {kind}: {reason} for url: {}",
+            this.status_code, this.url
+        );
+        let content = this.content.as_bytes(py);
+        if this.error_body_snippet > 0 && !content.is_empty() {
+            let snippet = &content[..content.len().min(this.error_body_snippet)];
+            let ellipsis = if snippet.len() < content.len() {
+                "..."
+            } else {
+                ""
+            };
+            message.push_str(&format!(
+                ": {}{ellipsis}",
+                String::from_utf8_lossy(snippet).trim()
+            ));
+        }
+        let err = HTTPError::new_err(message);
+        let value = err.value(py);
+        for name in [
+            "request_id",
+            "method",
+            "timeout",
+            "attempt",
+            "errno",
+            "os_error",
+        ] {
+            value.setattr(name, py.None())?;
+        }
+        value.setattr("url", &this.url)?;
+        value.setattr("status_code", this.status_code)?;
+        value.setattr("response", slf)?;
+        Err(err)
+    }
+
+    /// True if the status code is less than 400.
+    #[getter]
+    fn ok(&self) -> bool {
+        self.status_code < 400
+    }
+
+    /// Reason phrase of the status code, e.g. "Not Found".
+    #[getter]
+    fn reason(&self) -> Option<&'static str> {
+        StatusCode::from_u16(self.status_code)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+    }
+
+    #[getter]
+    fn cookies(&self) -> IndexMap<String, String, RandomState> {
+        self.headers.get().cookies().collect()
+    }
+
     #[getter]
     fn get_encoding(&mut self, py: Python) -> Result<&String> {
         if !self.encoding.is_empty() {
             return Ok(&self.encoding);
         }
-        self.encoding = get_encoding_from_headers(&self.headers)
-            .or_else(|| get_encoding_from_content(self.content.as_bytes(py)))
-            .unwrap_or_else(|| "utf-8".to_string());
+        let raw_bytes = self.content.as_bytes(py);
+        let headers = &self.headers.get().headers;
+        let default_encoding = &self.default_encoding;
+        self.encoding = py.allow_threads(|| {
+            get_encoding_from_headers(headers)
+                .or_else(|| get_encoding_from_content(raw_bytes))
+                .unwrap_or_else(|| default_encoding.clone())
+        });
         Ok(&self.encoding)
     }
 
@@ -65,16 +208,38 @@ impl Response {
                 self.encoding = detected_encoding.name().to_string();
             }
 
-            Ok(decoded_str.to_string())
+            Ok(decoded_str.into_owned())
         })
     }
 
     fn json(&mut self, py: Python) -> Result<PyObject> {
-        let json_value: serde_json::Value = from_slice(self.content.as_bytes(py))?;
-        let result = pythonize(py, &json_value).unwrap().unbind();
+        // Parse with the GIL released, only building the Python objects holds it
+        let result = json_to_py(py, self.content.as_bytes(py))?.unbind();
         Ok(result)
     }
 
+    /// Write the body to a file and return its path.
+    ///
+    /// The filename is taken from the `Content-Disposition` header (`filename*` preferred), else from
+    /// the URL path, without directories and characters reserved on Windows, and prefixed with `_` if
+    /// it is a Windows device name (`CON`, `NUL.txt`). An existing file is overwritten.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir_or_path` - Directory to save the file in, or path of the file. Default is None (the current directory).
+    #[pyo3(signature = (dir_or_path=None))]
+    fn save(&self, py: Python, dir_or_path: Option<PathBuf>) -> Result<String> {
+        let path = match dir_or_path {
+            Some(dir) if dir.is_dir() => dir.join(self.filename()),
+            Some(path) => path,
+            None => PathBuf::from(self.filename()),
+        };
+        let content = self.content.as_bytes(py);
+        py.allow_threads(|| std::fs::write(&path, content))
+            .map_err(|e| anyhow!("Failed to write {}: {e}", path.display()))?;
+        Ok(path.to_string_lossy().into_owned())
+    }
+
     #[getter]
     fn text_markdown(&mut self, py: Python) -> Result<String> {
         let raw_bytes = self.content.bind(py).as_bytes();
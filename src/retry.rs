@@ -0,0 +1,217 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, Result};
+use pyo3::prelude::*;
+use rquest::{
+    header::{HeaderMap, RETRY_AFTER},
+    Method,
+};
+
+// Upper bound of the backoff between two attempts
+const BACKOFF_MAX: Duration = Duration::from_secs(120);
+
+/// Retry policy for failed requests, passed as `Client(retries=Retry(...))`.
+///
+/// Connect errors, timeouts and responses with a status in `status_forcelist` are retried,
+/// waiting `backoff_factor * 2 ** (retry - 1)` seconds (at most 120) before each retry,
+/// or the time requested by the `Retry-After` header of 413, 429 and 503 responses.
+#[pyclass(frozen)]
+#[derive(Clone, Debug)]
+pub struct Retry {
+    #[pyo3(get)]
+    pub total: u32,
+    #[pyo3(get)]
+    pub backoff_factor: f64,
+    #[pyo3(get)]
+    pub status_forcelist: Vec<u16>,
+    #[pyo3(get)]
+    pub allowed_methods: Option<Vec<String>>,
+    #[pyo3(get)]
+    pub respect_retry_after: bool,
+    #[pyo3(get)]
+    pub retry_after_max: f64,
+    pub throttle_callback: Option<Arc<PyObject>>,
+}
+
+#[pymethods]
+impl Retry {
+    /// Initializes a retry policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `total` - Maximum number of retries. Default is 3.
+    /// * `backoff_factor` - Base of the exponential wait between retries in seconds. Default is 0 (no wait).
+    /// * `status_forcelist` - Response status codes to retry, e.g. [429, 502, 503]. Default is None.
+    /// * `allowed_methods` - Methods to retry. Default is None (idempotent methods: GET, HEAD, OPTIONS, PUT, DELETE, TRACE).
+    /// * `respect_retry_after` - Wait as requested by the `Retry-After` header (seconds or HTTP-date)
+    ///         of 413, 429 and 503 responses instead of the backoff. Default is True.
+    /// * `retry_after_max` - Longest `Retry-After` wait in seconds, the response is returned
+    ///         without retrying if the server asks for more. Default is 120.
+    /// * `throttle_callback` - Called as `throttle_callback(url, status_code, seconds)` before waiting
+    ///         for a `Retry-After` header, e.g. to log throttling. Default is None.
+    #[new]
+    #[pyo3(signature = (total=3, backoff_factor=0.0, status_forcelist=None, allowed_methods=None,
+        respect_retry_after=true, retry_after_max=120.0, throttle_callback=None))]
+    fn new(
+        total: u32,
+        backoff_factor: f64,
+        status_forcelist: Option<Vec<u16>>,
+        allowed_methods: Option<Vec<String>>,
+        respect_retry_after: bool,
+        retry_after_max: f64,
+        throttle_callback: Option<PyObject>,
+    ) -> Result<Self> {
+        if !backoff_factor.is_finite() || backoff_factor < 0.0 {
+            return Err(anyhow!("backoff_factor must be a non-negative number"));
+        }
+        if !retry_after_max.is_finite() || retry_after_max < 0.0 {
+            return Err(anyhow!("retry_after_max must be a non-negative number"));
+        }
+        Ok(Retry {
+            total,
+            backoff_factor,
+            status_forcelist: status_forcelist.unwrap_or_default(),
+            allowed_methods: allowed_methods
+                .map(|methods| methods.iter().map(|m| m.to_ascii_uppercase()).collect()),
+            respect_retry_after,
+            retry_after_max,
+            throttle_callback: throttle_callback.map(Arc::new),
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Retry(total={}, backoff_factor={}, status_forcelist={:?}, allowed_methods={:?}, \
+             respect_retry_after={}, retry_after_max={})",
+            self.total,
+            self.backoff_factor,
+            self.status_forcelist,
+            self.allowed_methods,
+            self.respect_retry_after,
+            self.retry_after_max
+        )
+    }
+}
+
+impl Retry {
+    pub fn is_method_allowed(&self, method: &Method) -> bool {
+        match &self.allowed_methods {
+            Some(methods) => methods.iter().any(|m| m == method.as_str()),
+            None => method.is_idempotent(),
+        }
+    }
+
+    pub fn is_retry_status(&self, status: u16) -> bool {
+        self.status_forcelist.contains(&status)
+    }
+
+    pub fn is_retry_error(&self, error: &rquest::Error) -> bool {
+        error.is_connect() || error.is_timeout()
+    }
+
+    /// Wait before the given retry (1 for the first one).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let seconds = self.backoff_factor * 2f64.powi(retry.saturating_sub(1).min(63) as i32);
+        Duration::try_from_secs_f64(seconds)
+            .unwrap_or(BACKOFF_MAX)
+            .min(BACKOFF_MAX)
+    }
+
+    /// Wait requested by the `Retry-After` header of a 413, 429 or 503 response.
+    pub fn retry_after(&self, status: u16, headers: &HeaderMap) -> Option<Duration> {
+        if !self.respect_retry_after || !matches!(status, 413 | 429 | 503) {
+            return None;
+        }
+        let value = headers.get(RETRY_AFTER)?.to_str().ok()?;
+        parse_retry_after(value, SystemTime::now())
+    }
+
+    /// Whether a `Retry-After` wait is longer than `retry_after_max`.
+    pub fn exceeds_retry_after_max(&self, wait: Duration) -> bool {
+        wait.as_secs_f64() > self.retry_after_max
+    }
+
+    /// Call `throttle_callback`, if any, before waiting for a `Retry-After` header.
+    pub fn on_throttle(&self, url: &str, status: u16, wait: Duration) -> Result<()> {
+        if let Some(callback) = &self.throttle_callback {
+            Python::with_gil(|py| callback.call1(py, (url, status, wait.as_secs_f64())))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a `Retry-After` value: delay in seconds or HTTP-date.
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(now).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod retry_tests {
+    use super::*;
+
+    fn policy(status_forcelist: Option<Vec<u16>>, allowed_methods: Option<Vec<String>>) -> Retry {
+        Retry::new(3, 0.5, status_forcelist, allowed_methods, true, 120.0, None).unwrap()
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let retry = policy(None, None);
+        assert_eq!(retry.backoff(1), Duration::from_millis(500));
+        assert_eq!(retry.backoff(2), Duration::from_secs(1));
+        assert_eq!(retry.backoff(3), Duration::from_secs(2));
+        assert_eq!(retry.backoff(100), BACKOFF_MAX);
+        assert!(Retry::new(3, -1.0, None, None, true, 120.0, None).is_err());
+        assert!(Retry::new(3, 0.5, None, None, true, f64::NAN, None).is_err());
+    }
+
+    #[test]
+    fn test_retry_methods() {
+        let retry = policy(Some(vec![503]), None);
+        assert!(retry.is_method_allowed(&Method::GET));
+        assert!(!retry.is_method_allowed(&Method::POST));
+        assert!(retry.is_retry_status(503));
+        assert!(!retry.is_retry_status(500));
+
+        let retry = policy(None, Some(vec!["post".to_string()]));
+        assert!(retry.is_method_allowed(&Method::POST));
+        assert!(!retry.is_method_allowed(&Method::GET));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = httpdate::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+        assert_eq!(
+            parse_retry_after(" 120 ", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:50:07 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:49:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "5".parse().unwrap());
+        let retry = policy(Some(vec![429, 500]), None);
+        assert_eq!(
+            retry.retry_after(429, &headers),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(retry.retry_after(500, &headers), None);
+        assert!(!retry.exceeds_retry_after_max(Duration::from_secs(120)));
+        assert!(retry.exceeds_retry_after_max(Duration::from_secs(121)));
+    }
+}
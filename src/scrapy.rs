@@ -0,0 +1,194 @@
+use pyo3::{
+    prelude::*,
+    types::{PyBytes, PyDict, PyList, PyType},
+};
+
+use crate::error::{ConnectError, Timeout};
+use crate::response::Response;
+use crate::Client;
+
+/// Scrapy download handler sending the requests with primp:
+/// `DOWNLOAD_HANDLERS = {"https": "primp.scrapy.PrimpDownloadHandler"}`.
+///
+/// The clients are created with the `PRIMP_CLIENT_KWARGS` setting and the `PRIMP_IMPERSONATE`
+/// setting, overridden per request by `request.meta["impersonate"]`. Redirects and cookies are
+/// left to the Scrapy middlewares, `request.meta["download_timeout"]` is the request timeout.
+#[pyclass(module = "primp.scrapy")]
+pub struct PrimpDownloadHandler {
+    config: Py<PyDict>,
+    clients: Py<PyDict>,
+}
+
+#[pymethods]
+impl PrimpDownloadHandler {
+    /// Created at startup, not on the first request.
+    #[classattr]
+    fn lazy() -> bool {
+        false
+    }
+
+    #[new]
+    #[pyo3(signature = (settings, crawler=None))]
+    fn new(
+        py: Python,
+        settings: &Bound<'_, PyAny>,
+        crawler: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        let _ = crawler;
+        let config = PyDict::new(py);
+        config.set_item("follow_redirects", false)?;
+        config.set_item("cookie_store", false)?;
+        let impersonate = settings.call_method1("get", ("PRIMP_IMPERSONATE",))?;
+        if !impersonate.is_none() {
+            config.set_item("impersonate", impersonate)?;
+        }
+        let kwargs = settings.call_method1("getdict", ("PRIMP_CLIENT_KWARGS",))?;
+        config.update(kwargs.downcast::<PyDict>()?.as_mapping())?;
+        Ok(PrimpDownloadHandler {
+            config: config.unbind(),
+            clients: PyDict::new(py).unbind(),
+        })
+    }
+
+    #[classmethod]
+    fn from_crawler<'py>(
+        cls: &Bound<'py, PyType>,
+        crawler: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        cls.call1((crawler.getattr("settings")?, crawler))
+    }
+
+    /// Deferred firing with the Scrapy response, the request is sent in the reactor thread pool.
+    fn download_request<'py>(
+        slf: &Bound<'py, Self>,
+        request: &Bound<'py, PyAny>,
+        spider: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let _ = spider;
+        slf.py()
+            .import("twisted.internet.threads")?
+            .call_method1("deferToThread", (slf.getattr("_download")?, request))
+    }
+
+    /// Send a Scrapy request and return the Scrapy response, blocking.
+    fn _download<'py>(
+        &self,
+        py: Python<'py>,
+        request: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let meta = request.getattr("meta")?;
+        let impersonate = meta.call_method1("get", ("impersonate",))?;
+        let client = self.client(py, &impersonate)?;
+
+        let kwargs = PyDict::new(py);
+        let headers = request
+            .getattr("headers")?
+            .call_method0("to_unicode_dict")?;
+        kwargs.set_item(
+            "headers",
+            PyDict::from_sequence(&headers.call_method0("items")?)?,
+        )?;
+        let body = request.getattr("body")?;
+        if body.is_truthy()? {
+            kwargs.set_item("content", body)?;
+        }
+        kwargs.set_item("timeout", meta.call_method1("get", ("download_timeout",))?)?;
+        let method = request.getattr("method")?;
+        let url = request.getattr("url")?;
+        let response = client
+            .call_method("request", (method, url), Some(&kwargs))
+            .map_err(|e| twisted_error(py, e))?
+            .downcast_into::<Response>()?;
+        scrapy_response(py, &response.borrow(), request)
+    }
+
+    fn close(&self) {}
+}
+
+impl PrimpDownloadHandler {
+    /// Client of an impersonation, None for the one of the settings.
+    fn client<'py>(
+        &self,
+        py: Python<'py>,
+        impersonate: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let clients = self.clients.bind(py);
+        if let Some(client) = clients.get_item(impersonate)? {
+            return Ok(client);
+        }
+        let config = self.config.bind(py).copy()?;
+        if !impersonate.is_none() {
+            config.set_item("impersonate", impersonate)?;
+        }
+        let client = py.get_type::<Client>().call((), Some(&config))?;
+        clients.set_item(impersonate, &client)?;
+        Ok(client)
+    }
+}
+
+fn scrapy_response<'py>(
+    py: Python<'py>,
+    response: &Response,
+    request: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let headers = PyDict::new(py);
+    let items = response.headers.bind(py).call_method0("grouped_items")?;
+    for item in items.try_iter()? {
+        let (name, value): (String, String) = item?.extract()?;
+        match headers.get_item(&name)? {
+            Some(values) => values.downcast::<PyList>()?.append(value)?,
+            None => headers.set_item(name, PyList::new(py, [value])?)?,
+        }
+    }
+    let headers = py
+        .import("scrapy.http")?
+        .getattr("Headers")?
+        .call1((headers,))?;
+    let body: &Bound<'py, PyBytes> = response.content.bind(py);
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("headers", &headers)?;
+    kwargs.set_item("url", &response.url)?;
+    kwargs.set_item("body", body)?;
+    let respcls = py
+        .import("scrapy.responsetypes")?
+        .getattr("responsetypes")?
+        .call_method("from_args", (), Some(&kwargs))?;
+    kwargs.set_item("status", response.status_code)?;
+    kwargs.set_item("request", request)?;
+    respcls.call((), Some(&kwargs))
+}
+
+/// The Twisted counterpart of a primp timeout or connection error, retried by Scrapy's
+/// `RetryMiddleware`. Other exceptions are kept.
+fn twisted_error(py: Python, err: PyErr) -> PyErr {
+    let name = if err.is_instance_of::<Timeout>(py) {
+        "TimeoutError"
+    } else if err.is_instance_of::<ConnectError>(py) {
+        "ConnectError"
+    } else {
+        return err;
+    };
+    let result = (|| -> PyResult<PyErr> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("string", err.value(py).str()?)?;
+        let exception = py
+            .import("twisted.internet.error")?
+            .getattr(name)?
+            .call((), Some(&kwargs))?;
+        let twisted_err = PyErr::from_value(exception);
+        twisted_err.set_cause(py, Some(err.clone_ref(py)));
+        Ok(twisted_err)
+    })();
+    result.unwrap_or_else(|e| e)
+}
+
+/// Add the `primp.scrapy` submodule.
+pub fn register_scrapy(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    let scrapy = PyModule::new(py, "scrapy")?;
+    scrapy.add_class::<PrimpDownloadHandler>()?;
+    m.add_submodule(&scrapy)?;
+    py.import("sys")?
+        .getattr("modules")?
+        .set_item("primp.scrapy", scrapy)
+}
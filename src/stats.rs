@@ -0,0 +1,132 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use anyhow::Error;
+use foldhash::fast::RandomState;
+use indexmap::IndexMap;
+use pyo3::{prelude::*, types::PyDict};
+
+/// Request counters shared by all requests made with a `Client`.
+#[derive(Debug, Default)]
+pub struct ClientStats {
+    requests_total: AtomicU64,
+    requests_in_flight: AtomicU64,
+    requests_failed: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+}
+
+/// Keeps a request counted as in flight until dropped.
+pub struct InFlightGuard<'a>(&'a ClientStats);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.requests_in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl ClientStats {
+    /// Register a new request, counted as in flight while the returned guard is alive.
+    pub fn start_request(&self) -> InFlightGuard<'_> {
+        self.requests_total.fetch_add(1, Ordering::Relaxed);
+        self.requests_in_flight.fetch_add(1, Ordering::Relaxed);
+        InFlightGuard(self)
+    }
+
+    pub fn add_failed(&self) {
+        self.requests_failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn add_bytes_sent(&self, n: usize) {
+        self.bytes_sent.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    pub fn add_bytes_received(&self, n: usize) {
+        self.bytes_received.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    /// Snapshot of the counters.
+    pub fn to_indexmap(&self) -> IndexMap<String, u64, RandomState> {
+        [
+            ("requests_total", &self.requests_total),
+            ("requests_in_flight", &self.requests_in_flight),
+            ("requests_failed", &self.requests_failed),
+            ("bytes_sent", &self.bytes_sent),
+            ("bytes_received", &self.bytes_received),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.load(Ordering::Relaxed)))
+        .collect()
+    }
+}
+
+/// Measurements of a single request, passed to the `on_metrics` callback.
+#[derive(Debug, Default)]
+pub struct RequestMetrics {
+    pub url: Option<String>,
+    pub status_code: Option<u16>,
+    pub bytes_sent: usize,
+    pub bytes_received: usize,
+    /// Time until the response headers, retries included
+    pub wait: Option<Duration>,
+    /// Time to read the response body
+    pub receive: Option<Duration>,
+}
+
+impl RequestMetrics {
+    /// Call `on_metrics` with a dict of the metrics, a failing callback is logged and ignored.
+    pub fn report(
+        &self,
+        on_metrics: &PyObject,
+        request_id: u64,
+        method: &str,
+        url: &str,
+        retries: u32,
+        total: Duration,
+        error: Option<&Error>,
+    ) {
+        let result = Python::with_gil(|py| {
+            let timings = PyDict::new(py);
+            timings.set_item("total", total.as_secs_f64())?;
+            timings.set_item("wait", self.wait.map(|d| d.as_secs_f64()))?;
+            timings.set_item("receive", self.receive.map(|d| d.as_secs_f64()))?;
+            let metrics = PyDict::new(py);
+            metrics.set_item("request_id", request_id)?;
+            metrics.set_item("url", self.url.as_deref().unwrap_or(url))?;
+            metrics.set_item("method", method)?;
+            metrics.set_item("status_code", self.status_code)?;
+            metrics.set_item("bytes_sent", self.bytes_sent)?;
+            metrics.set_item("bytes_received", self.bytes_received)?;
+            metrics.set_item("timings", timings)?;
+            metrics.set_item("retries", retries)?;
+            metrics.set_item("error", error.map(|e| format!("{e:#}")))?;
+            on_metrics.call1(py, (metrics,))
+        });
+        if let Err(e) = result {
+            log::warn!("on_metrics callback failed: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
+
+    #[test]
+    fn test_client_stats() {
+        let stats = ClientStats::default();
+        {
+            let _guard = stats.start_request();
+            stats.add_bytes_sent(10);
+            stats.add_bytes_received(20);
+            assert_eq!(stats.to_indexmap()["requests_in_flight"], 1);
+        }
+        stats.add_failed();
+        let snapshot = stats.to_indexmap();
+        assert_eq!(snapshot["requests_total"], 1);
+        assert_eq!(snapshot["requests_in_flight"], 0);
+        assert_eq!(snapshot["requests_failed"], 1);
+        assert_eq!(snapshot["bytes_sent"], 10);
+        assert_eq!(snapshot["bytes_received"], 20);
+    }
+}
@@ -0,0 +1,127 @@
+use std::net::SocketAddr;
+
+use rquest::{
+    header::{HeaderMap, COOKIE},
+    StatusCode, Version,
+};
+
+/// Target of the `debug=True` logs, the Python logger is "primp.wire".
+pub const TARGET: &str = "primp::wire";
+
+/// Request headers with the default headers and the cookies the client adds when sending it.
+pub fn sent_headers(client: &rquest::Client, request: &rquest::Request) -> HeaderMap {
+    let mut headers = request.headers().clone();
+    let mut client = client.clone();
+    for (name, value) in client.headers_mut().iter() {
+        if !request.headers().contains_key(name) {
+            headers.append(name, value.clone());
+        }
+    }
+    if !headers.contains_key(COOKIE) {
+        if let Some(cookies) = client.get_cookies(request.url()) {
+            headers.insert(COOKIE, cookies);
+        }
+    }
+    headers
+}
+
+/// Log the request line and the headers, like `curl -v`.
+pub fn log_request(request: &rquest::Request, headers: &HeaderMap) {
+    for line in request_lines(request, headers) {
+        log::debug!(target: TARGET, "{line}");
+    }
+}
+
+/// Log the connection, the status line and the headers of a response, like `curl -v`.
+pub fn log_response(
+    remote_addr: Option<SocketAddr>,
+    version: Version,
+    status: u16,
+    headers: &HeaderMap,
+) {
+    for line in response_lines(remote_addr, version, status, headers) {
+        log::debug!(target: TARGET, "{line}");
+    }
+}
+
+fn request_lines(request: &rquest::Request, headers: &HeaderMap) -> Vec<String> {
+    let url = request.url();
+    let path = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    };
+    let mut lines = vec![format!("> {} {path}", request.method())];
+    if let Some(host) = url.host_str() {
+        match url.port() {
+            Some(port) => lines.push(format!("> host: {host}:{port}")),
+            None => lines.push(format!("> host: {host}")),
+        }
+    }
+    lines.extend(header_lines('>', headers));
+    lines
+}
+
+fn response_lines(
+    remote_addr: Option<SocketAddr>,
+    version: Version,
+    status: u16,
+    headers: &HeaderMap,
+) -> Vec<String> {
+    let mut lines = Vec::with_capacity(headers.len() + 2);
+    if let Some(addr) = remote_addr {
+        lines.push(format!("* Connected to {addr}"));
+    }
+    let reason = StatusCode::from_u16(status)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or_default();
+    lines.push(format!("< {version:?} {status} {reason}"));
+    lines.extend(header_lines('<', headers));
+    lines
+}
+
+fn header_lines<'a>(prefix: char, headers: &'a HeaderMap) -> impl Iterator<Item = String> + 'a {
+    headers.iter().map(move |(name, value)| {
+        format!(
+            "{prefix} {name}: {}",
+            String::from_utf8_lossy(value.as_bytes())
+        )
+    })
+}
+
+#[cfg(test)]
+mod wire_tests {
+    use super::*;
+    use rquest::{header::HeaderValue, Method, Url};
+
+    #[test]
+    fn test_request_lines() {
+        let url = Url::parse("https://example.com:8443/a/b?q=1").unwrap();
+        let request = rquest::Request::new(Method::POST, url);
+        let mut headers = HeaderMap::new();
+        headers.insert("user-agent", HeaderValue::from_static("primp"));
+        assert_eq!(
+            request_lines(&request, &headers),
+            vec![
+                "> POST /a/b?q=1",
+                "> host: example.com:8443",
+                "> user-agent: primp"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_response_lines() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-length", HeaderValue::from_static("0"));
+        let addr: SocketAddr = "192.0.2.1:443".parse().unwrap();
+        assert_eq!(
+            response_lines(Some(addr), Version::HTTP_2, 404, &headers),
+            vec![
+                "* Connected to 192.0.2.1:443",
+                "< HTTP/2.0 404 Not Found",
+                "< content-length: 0"
+            ]
+        );
+    }
+}
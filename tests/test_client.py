@@ -1,4 +1,13 @@
-from time import sleep
+import _thread
+import asyncio
+import json
+import logging
+import os
+import sys
+import threading
+from http.server import BaseHTTPRequestHandler, HTTPServer
+from time import sleep, time
+from urllib.parse import parse_qs, quote, urlsplit
 
 import pytest
 
@@ -73,6 +82,100 @@ def test_client_setters():
     assert b"Basic dXNlcjpwYXNzd29yZA==" in response.content
 
 
+@retry()
+def test_client_set_impersonate():
+    client = primp.Client(impersonate="chrome_131")
+    assert client.impersonate == "chrome_131"
+    client.impersonate = "firefox_133"
+    assert client.impersonate == "firefox_133"
+    assert client.headers["user-agent"].endswith("Firefox/133.0")
+    response = client.get("https://httpbin.org/headers")
+    assert response.json()["headers"]["User-Agent"].endswith("Firefox/133.0")
+    with pytest.raises(Exception):
+        client.impersonate = "chrome_0"
+
+
+def test_client_set_impersonate_keeps_options():
+    client = primp.Client(impersonate="chrome_131", impersonate_os="linux", locale="de-DE")
+    client.impersonate = "firefox_133"
+    assert "X11; Linux x86_64" in client.headers["user-agent"]
+    assert client.headers["accept-language"].startswith("de-DE")
+
+    client = primp.Client(impersonate="chrome_131", headers={"user-agent": "custom"})
+    client.impersonate = "firefox_133"
+    assert client.headers == {"user-agent": "custom"}
+
+
+@retry()
+def test_client_batch():
+    client = primp.Client(impersonate="chrome_131")
+    results = client.batch(
+        [
+            {"url": "https://httpbin.org/get", "params": {"a": "1"}},
+            {"method": "POST", "url": "https://httpbin.org/post", "json": {"b": 2}},
+            {"url": "https://unknown.invalid/"},
+        ]
+    )
+    assert results[0].json()["args"] == {"a": "1"}
+    assert results[1].json()["json"] == {"b": 2}
+    assert isinstance(results[2], Exception)
+    assert client.stats()["requests_failed"] == 1
+    with pytest.raises(Exception):
+        client.batch([{"url": "https://httpbin.org/get", "body": b""}])
+
+
+def test_configure_runtime():
+    with pytest.raises(Exception):
+        primp.configure_runtime(0)
+    primp.Client().head("https://httpbin.org/")
+    with pytest.raises(Exception, match="before the first request"):
+        primp.configure_runtime(4)
+
+
+@pytest.mark.skipif(not hasattr(os, "fork"), reason="os.fork is not available")
+@retry()
+def test_client_fork():
+    unused = primp.Client()
+    used = primp.Client()
+    used.head("https://httpbin.org/")
+    pid = os.fork()
+    if pid == 0:
+        ok = False
+        try:
+            ok = unused.get("https://httpbin.org/anything").status_code == 200
+            try:
+                used.get("https://httpbin.org/anything")
+                ok = False
+            except Exception:
+                pass
+        finally:
+            os._exit(0 if ok else 1)
+    _, status = os.waitpid(pid, 0)
+    assert os.waitstatus_to_exitcode(status) == 0
+
+
+def test_client_keyboard_interrupt():
+    client = primp.Client(timeout=30)
+    threading.Timer(1, _thread.interrupt_main).start()
+    start = time()
+    with pytest.raises(KeyboardInterrupt):
+        client.get("https://httpbin.org/delay/10")
+    assert time() - start < 5
+
+
+@retry()
+def test_client_response_headers():
+    client = primp.Client()
+    response = client.get("https://httpbin.org/response-headers?X-Test=a&X-Test=b")
+    assert response.headers["X-TEST"] == response.headers["x-test"] == "a, b"
+    assert "Content-Type" in response.headers
+    assert response.headers.get("x-missing") is None
+    assert dict(response.headers)["content-type"] == "application/json"
+    assert response.headers == dict(response.headers)
+    assert response.headers.get_all("X-Test") == ["a", "b"]
+    assert ("x-test", "b") in response.headers.grouped_items()
+
+
 @retry()
 def test_client_request_get():
     client = primp.Client()
@@ -254,6 +357,64 @@ def test_client_impersonate_chrome130():
     assert json_data["peetprint_hash"] == "b8ce945a4d9a7a9b5b6132e3658fe033"
 
 
+@retry()
+def test_client_impersonate_brave173():
+    client = primp.Client(
+        impersonate="brave_1.73",
+    )
+    response = client.get("https://tls.http.rw/api/clean")
+    assert response.status_code == 200
+    json_data = response.json()
+    assert json_data["ja4"] == "t13d1516h2_8daaf6152771_b1ff8ab2d16f"
+    assert json_data["akamai_hash"] == "90224459f8bf70b7d0a8797eb916dbc9"
+    response = client.get("https://httpbin.org/headers")
+    assert response.json()["headers"]["Sec-Ch-Ua"].startswith('"Brave"')
+
+
+@retry()
+def test_client_impersonate_tor14():
+    client = primp.Client(impersonate="tor_14")
+    response = client.get("https://httpbin.org/headers")
+    user_agent = response.json()["headers"]["User-Agent"]
+    assert user_agent == "Mozilla/5.0 (Windows NT 10.0; rv:128.0) Gecko/20100101 Firefox/128.0"
+
+
+@retry()
+def test_client_impersonate_honest():
+    response = primp.Client(impersonate="curl_8").get("https://tls.http.rw/api/all")
+    assert response.json()["http2"]["akamai_fingerprint"] == "3:100;4:10485760;2:0|1048510465|0|m,p,s,a"
+    response = primp.Client(impersonate="requests_2").get("https://httpbin.org/headers")
+    assert response.json()["headers"]["User-Agent"] == "python-requests/2.32.3"
+
+
+@retry()
+def test_client_impersonate_crawlers():
+    for impersonate in ["googlebot", "bingbot"]:
+        response = primp.Client(impersonate=impersonate).get("https://httpbin.org/headers")
+        headers = response.json()["headers"]
+        assert "bot" in headers["User-Agent"]
+        assert "Sec-Ch-Ua" not in headers
+
+
+@retry()
+def test_client_impersonate_hosts():
+    client = primp.Client(impersonate={"httpbin.org": "okhttp_5", "*": "chrome_131"})
+    response = client.get("https://httpbin.org/headers")
+    assert response.json()["headers"]["User-Agent"].startswith("okhttp")
+    response = client.get("https://tls.http.rw/api/clean")
+    assert response.json()["ja4"] == "t13d1516h2_8daaf6152771_b1ff8ab2d16f"
+
+
+@retry()
+def test_client_impersonate_mobile():
+    for impersonate in ["samsung_internet_27", "android_webview_131"]:
+        client = primp.Client(impersonate=impersonate)
+        response = client.get("https://httpbin.org/headers")
+        headers = response.json()["headers"]
+        assert headers["Sec-Ch-Ua-Mobile"] == "?1"
+        assert headers["Sec-Ch-Ua-Platform"] == '"Android"'
+
+
 @retry()
 def test_client_impersonate_chrome131():
     client = primp.Client(
@@ -266,3 +427,1097 @@ def test_client_impersonate_chrome131():
     assert json_data["ja4"] == "t13d1516h2_8daaf6152771_b1ff8ab2d16f"
     assert json_data["akamai_hash"] == "90224459f8bf70b7d0a8797eb916dbc9"
     assert json_data["peetprint_hash"] == "7466733991096b3f4e6c0e79b0083559"
+
+
+@retry()
+def test_client_ip_version_ipv4():
+    client = primp.Client(ip_version="ipv4")
+    response = client.get("https://httpbin.org/ip")
+    assert response.status_code == 200
+    assert ":" not in response.json()["origin"]
+
+
+def test_client_ip_version_invalid():
+    with pytest.raises(Exception):
+        primp.Client(ip_version="ipv5")
+
+
+def test_client_local_address_invalid():
+    with pytest.raises(Exception):
+        primp.Client(local_address="not-an-ip")
+
+
+@retry()
+def test_client_stats():
+    client = primp.Client()
+    response = client.post("https://httpbin.org/anything", content=b"test content")
+    assert response.status_code == 200
+    stats = client.stats()
+    assert stats["requests_total"] == 1
+    assert stats["requests_in_flight"] == 0
+    assert stats["bytes_sent"] == len(b"test content")
+    assert stats["bytes_received"] == len(response.content)
+
+
+@retry()
+def test_client_http_version():
+    client = primp.Client(impersonate="chrome_131")
+    response = client.get("https://tls.http.rw/api/all", http_version="1.1")
+    assert response.status_code == 200
+    assert response.json()["http_version"] == "HTTP/1.1"
+
+
+@retry()
+def test_client_alpn():
+    client = primp.Client(impersonate="chrome_131", alpn=["http/1.1"])
+    response = client.get("https://tls.http.rw/api/all")
+    assert response.status_code == 200
+    assert response.json()["http_version"] == "HTTP/1.1"
+
+
+def test_client_alpn_invalid():
+    with pytest.raises(Exception):
+        primp.Client(alpn=["h3"])
+
+
+@pytest.mark.skipif(sys.platform in ("win32", "darwin"), reason="Linux and BSD only")
+@retry()
+def test_client_verify_system():
+    client = primp.Client(verify="system")
+    response = client.get("https://httpbin.org/anything")
+    assert response.status_code == 200
+
+
+@pytest.mark.skipif(sys.platform not in ("win32", "darwin"), reason="Windows and macOS only")
+def test_client_verify_system_unsupported():
+    with pytest.raises(Exception, match="only supported on Linux and BSD"):
+        primp.Client(verify="system")
+
+
+def test_client_verify_invalid():
+    with pytest.raises(Exception):
+        primp.Client(verify="/nonexistent/ca.pem")
+
+
+@retry()
+def test_client_verify_path():
+    client = primp.Client(verify=certifi.where())
+    response = client.get("https://httpbin.org/anything")
+    assert response.status_code == 200
+
+
+@retry()
+def test_client_ca_cert_pem():
+    with open(certifi.where(), "rb") as f:
+        ca_cert = f.read()
+    client = primp.Client(ca_cert=ca_cert)
+    response = client.get("https://httpbin.org/anything")
+    assert response.status_code == 200
+    client = primp.Client(ca_cert=ca_cert.decode())
+    response = client.get("https://httpbin.org/anything")
+    assert response.status_code == 200
+
+
+@retry()
+def test_client_ca_cert_file_per_client(tmp_path):
+    with open(certifi.where(), "rb") as f:
+        certs = f.read()
+    # A bundle with a single unrelated root must not leak into other clients
+    other_bundle = tmp_path / "other.pem"
+    other_bundle.write_bytes(certs[: certs.index(b"-----END CERTIFICATE-----") + 25])
+    client_other = primp.Client(ca_cert_file=str(other_bundle))
+    client_certifi = primp.Client(ca_cert_file=certifi.where())
+    response = client_certifi.get("https://httpbin.org/anything")
+    assert response.status_code == 200
+    with pytest.raises(Exception):
+        client_other.get("https://httpbin.org/anything")
+
+
+@retry()
+def test_client_exceptions():
+    client = primp.Client(timeout=2)
+    with pytest.raises(primp.ConnectError) as exc_info:
+        client.get("http://127.0.0.1:1/")
+    assert exc_info.value.method == "GET"
+    assert exc_info.value.url == "http://127.0.0.1:1/"
+    assert exc_info.value.timeout == 2
+    assert exc_info.value.attempt == 1
+    assert exc_info.value.errno is not None
+    assert isinstance(exc_info.value.os_error, ConnectionRefusedError)
+    with pytest.raises(primp.SSLError):
+        client.get("https://self-signed.badssl.com/")
+    with pytest.raises(primp.ReadTimeout) as exc_info:
+        client.get("https://httpbin.org/delay/5")
+    assert isinstance(exc_info.value, primp.Timeout)
+    assert isinstance(exc_info.value, primp.PrimpError)
+    with pytest.raises(primp.TooManyRedirects):
+        primp.Client(max_redirects=1).get("https://httpbin.org/redirect/3")
+    with pytest.raises(primp.InvalidRequest):
+        client.get("not a url")
+    response = client.get("https://httpbin.org/status/404")
+    with pytest.raises(primp.HTTPError, match="404 Client Error: Not Found") as exc_info:
+        response.raise_for_status()
+    assert exc_info.value.status_code == 404
+    assert exc_info.value.response is response
+    client.get("https://httpbin.org/status/200").raise_for_status()
+
+
+@retry()
+def test_client_error_body_snippet():
+    client = primp.Client(error_body_snippet=20)
+    response = client.get("https://httpbin.org/status/418")
+    with pytest.raises(primp.HTTPError) as exc_info:
+        response.raise_for_status()
+    assert str(exc_info.value).endswith("...")
+    assert response.text.strip()[:10] in str(exc_info.value)
+
+
+@retry()
+def test_client_hooks():
+    def add_header(request):
+        request["headers"].append(("X-Hook", "test"))
+        request["url"] = request["url"].replace("/get", "/anything")
+
+    responses = []
+    with pytest.raises(Exception):
+        primp.Client(hooks={"other": [add_header]})
+    client = primp.Client(hooks={"request": [add_header], "response": [responses.append]})
+    response = client.get("https://httpbin.org/get")
+    assert response.url == "https://httpbin.org/anything"
+    assert response.json()["headers"]["X-Hook"] == "test"
+    assert responses == [response]
+
+    other = primp.Client().get("https://httpbin.org/status/204")
+    client = primp.Client(hooks={"response": [lambda response: other]})
+    assert client.get("https://httpbin.org/get") is other
+
+
+def test_client_hooks_header_list(redirect_server):
+    def edit_headers(request):
+        names = [name.lower() for name, _ in request["headers"]]
+        assert "accept-encoding" in names
+        request["headers"] += [("X-Dup", "1"), ("X-Dup", "2"), ("X-Raw", b"\xe9")]
+
+    client = primp.Client(hooks={"request": [edit_headers]})
+    response = client.get(f"http://127.0.0.1:{redirect_server}/echo")
+    header_list = [tuple(header) for header in response.json()["header_list"]]
+    assert ("x-dup", "1") in header_list and ("x-dup", "2") in header_list
+    # http.server decodes the header bytes as latin-1
+    assert ("x-raw", "\xe9") in header_list
+
+
+def test_client_mock_transport():
+    transport = primp.MockTransport()
+    transport.add("https://api.example.com/users/*", json={"name": "test"})
+    transport.add(
+        "https://api.example.com/users/0",
+        method="delete",
+        status_code=404,
+        headers={"x-test": "test"},
+        content="not found",
+        delay=0.2,
+    )
+    client = primp.Client(transport=transport)
+
+    response = client.get("https://api.example.com/users/1?page=2")
+    assert response.status_code == 200
+    assert response.json() == {"name": "test"}
+    assert response.headers["content-type"] == "application/json"
+
+    t0 = time()
+    response = client.delete("https://api.example.com/users/0")
+    assert time() - t0 >= 0.2
+    assert response.status_code == 404
+    assert response.text == "not found"
+    assert response.headers["x-test"] == "test"
+    assert client.get("https://api.example.com/users/0").status_code == 200
+
+    with pytest.raises(primp.RequestError):
+        client.get("https://api.example.com/other")
+    assert transport.calls == [
+        ("GET", "https://api.example.com/users/1?page=2"),
+        ("DELETE", "https://api.example.com/users/0"),
+        ("GET", "https://api.example.com/users/0"),
+        ("GET", "https://api.example.com/other"),
+    ]
+    transport.reset()
+    assert transport.calls == []
+
+
+def test_client_har_recording(tmp_path):
+    transport = primp.MockTransport()
+    transport.add("https://example.com/*", headers={"set-cookie": "a=1; Path=/"}, json={"b": 2})
+    transport.add("https://example.com/image", content=b"\xff\x00")
+    client = primp.Client(transport=transport, headers={"x-test": "test"})
+    path = tmp_path / "session.har"
+    with pytest.raises(Exception):
+        client.export_har(str(path))
+
+    client.get("https://example.com/before")
+    client.start_har_recording()
+    client.post("https://example.com/api?q=1", content=b"data", headers={"content-type": "text/plain"})
+    client.get("https://example.com/image")
+    client.export_har(str(path))
+
+    with open(path) as f:
+        har = json.load(f)
+    assert har["log"]["version"] == "1.2"
+    entries = har["log"]["entries"]
+    assert len(entries) == 2
+    request, response = entries[0]["request"], entries[0]["response"]
+    assert request["method"] == "POST"
+    assert request["url"] == "https://example.com/api?q=1"
+    assert {"name": "x-test", "value": "test"} in request["headers"]
+    assert request["queryString"] == [{"name": "q", "value": "1"}]
+    assert request["postData"] == {"mimeType": "text/plain", "text": "data"}
+    assert response["status"] == 200
+    assert response["cookies"] == [{"name": "a", "value": "1"}]
+    assert json.loads(response["content"]["text"]) == {"b": 2}
+    assert entries[1]["response"]["content"]["encoding"] == "base64"
+    assert entries[1]["timings"]["wait"] >= 0
+
+
+def test_response_save(tmp_path, monkeypatch):
+    transport = primp.MockTransport()
+    transport.add(
+        "https://example.com/report",
+        headers={"content-disposition": "attachment; filename=\"../r.txt\"; filename*=UTF-8''%E2%82%AC%20report.txt"},
+        content=b"report",
+    )
+    transport.add("https://example.com/files/data%20set.csv?v=1", content=b"a,b")
+    transport.add("https://example.com/", content=b"index")
+    client = primp.Client(transport=transport)
+
+    response = client.get("https://example.com/report")
+    path = response.save(tmp_path)
+    assert path == str(tmp_path / "\u20ac report.txt")
+    assert (tmp_path / "\u20ac report.txt").read_bytes() == b"report"
+    assert response.save(str(tmp_path / "custom.bin")) == str(tmp_path / "custom.bin")
+    assert (tmp_path / "custom.bin").read_bytes() == b"report"
+
+    assert client.get("https://example.com/files/data%20set.csv?v=1").save(tmp_path).endswith("data set.csv")
+    monkeypatch.chdir(tmp_path)
+    assert client.get("https://example.com/").save() == "download"
+    assert (tmp_path / "download").read_bytes() == b"index"
+
+
+def test_client_default_encoding():
+    transport = primp.MockTransport()
+    transport.add("https://example.com/legacy", content="Привет".encode("cp1251"))
+    transport.add(
+        "https://example.com/utf8",
+        headers={"content-type": "text/plain; charset=utf-8"},
+        content="Привет".encode(),
+    )
+    client = primp.Client(transport=transport, default_encoding="windows-1251")
+    response = client.get("https://example.com/legacy")
+    assert response.encoding == "windows-1251"
+    assert response.text == "Привет"
+    assert client.get("https://example.com/utf8").text == "Привет"
+    assert primp.Client(transport=transport).get("https://example.com/legacy").encoding == "utf-8"
+    with pytest.raises(Exception):
+        primp.Client(default_encoding="not-an-encoding")
+
+
+def test_client_debug(caplog):
+    caplog.set_level(logging.DEBUG, logger="primp.wire")
+    transport = primp.MockTransport()
+    transport.add("https://example.com/*", status_code=404, headers={"x-test": "test"})
+    client = primp.Client(transport=transport, headers={"user-agent": "primp"}, debug=True)
+    client.get("https://example.com/path", params={"q": "1"})
+    messages = [r.getMessage() for r in caplog.records if r.name == "primp.wire"]
+    assert messages[:2] == ["> GET /path?q=1", "> host: example.com"]
+    assert "> user-agent: primp" in messages
+    assert messages[-2:] == ["< HTTP/1.1 404 Not Found", "< x-test: test"]
+
+
+def test_client_on_metrics():
+    metrics = []
+    transport = primp.MockTransport()
+    transport.add("https://example.com/api", status_code=201, content=b"12345", delay=0.1)
+    client = primp.Client(transport=transport, on_metrics=metrics.append)
+    client.post("https://example.com/api", content=b"abc")
+    with pytest.raises(primp.RequestError):
+        client.get("https://example.com/other")
+
+    assert len(metrics) == 2
+    assert metrics[0]["url"] == "https://example.com/api"
+    assert metrics[0]["method"] == "POST"
+    assert metrics[0]["status_code"] == 201
+    assert metrics[0]["bytes_sent"] == 3
+    assert metrics[0]["bytes_received"] == 5
+    assert metrics[0]["retries"] == 0
+    assert metrics[0]["error"] is None
+    assert metrics[0]["timings"]["total"] >= metrics[0]["timings"]["wait"] >= 0.1
+    assert metrics[1]["status_code"] is None
+    assert "no response registered" in metrics[1]["error"]
+
+
+def test_client_request_id_logs(caplog):
+    caplog.set_level(logging.DEBUG, logger="primp.request")
+    primp.set_log_level("debug")
+    with pytest.raises(Exception):
+        primp.set_log_level("verbose")
+    metrics = []
+    transport = primp.MockTransport()
+    transport.add("https://example.com/ok")
+    client = primp.Client(transport=transport, on_metrics=metrics.append)
+    client.get("https://example.com/ok")
+    with pytest.raises(primp.RequestError) as exc_info:
+        client.get("https://example.com/missing")
+
+    request_id = metrics[0]["request_id"]
+    assert exc_info.value.request_id == metrics[1]["request_id"] > request_id
+    messages = [r.getMessage() for r in caplog.records if r.name == "primp.request"]
+    assert f"request_id={request_id} request: GET https://example.com/ok" in messages
+    assert f"request_id={request_id} mock response: https://example.com/ok 200 0" in messages
+    assert any(m.startswith(f"request_id={exc_info.value.request_id} error:") for m in messages)
+
+
+def test_client_build_request():
+    hook_requests = []
+    transport = primp.MockTransport()
+    transport.add("https://example.com/*", json={"ok": True})
+    client = primp.Client(
+        transport=transport,
+        headers={"x-client": "1"},
+        hooks={"request": [hook_requests.append]},
+    )
+    request = client.build_request(
+        "POST", "https://example.com/api", params={"q": "2"}, json={"a": 1}, cookies={"c": "3"}
+    )
+    assert request.method == "POST"
+    assert request.url == "https://example.com/api?q=2"
+    assert request.headers["x-client"] == "1"
+    assert request.headers["content-type"] == "application/json"
+    assert request.headers["cookie"] == "c=3"
+    assert request.content == b'{"a":1}'
+    assert request.body_length == 7
+    assert transport.calls == []
+    with pytest.raises(Exception):
+        client.build_request("POST", "https://example.com/api", files={"file": __file__})
+
+    request.headers = {**request.headers, "x-signature": "abc"}
+    request.url = "https://example.com/signed"
+    response = client.send(request)
+    assert response.json() == {"ok": True}
+    assert transport.calls == [("POST", "https://example.com/signed")]
+    assert hook_requests[0]["headers"]["x-signature"] == "abc"
+
+
+def test_client_file_url(tmp_path):
+    path = tmp_path / "page.html"
+    path.write_text("<p>ok</p>")
+    with pytest.raises(Exception, match="local_urls"):
+        primp.Client().get(path.as_uri())
+    with pytest.raises(Exception, match="https_only"):
+        primp.Client(local_urls=True, https_only=True).get(path.as_uri())
+    client = primp.Client(local_urls=True)
+    response = client.get(path.as_uri())
+    assert response.status_code == 200
+    assert response.text == "<p>ok</p>"
+    assert response.headers["content-type"] == "text/html"
+    assert response.headers["content-length"] == "9"
+    assert "last-modified" in response.headers
+    response = client.head(path.as_uri())
+    assert response.content == b""
+    assert client.get((tmp_path / "missing.json").as_uri()).status_code == 404
+    with pytest.raises(Exception):
+        client.post(path.as_uri(), content=b"x")
+
+
+def test_client_data_url():
+    with pytest.raises(Exception, match="local_urls"):
+        primp.Client().get("data:,ok")
+    client = primp.Client(local_urls=True)
+    response = client.get("data:text/html;charset=utf-8,%3Cp%3Eok%3C%2Fp%3E")
+    assert response.status_code == 200
+    assert response.text == "<p>ok</p>"
+    assert response.headers["content-type"] == "text/html;charset=utf-8"
+    response = client.get("data:;base64,SGVsbG8=")
+    assert response.content == b"Hello"
+    assert response.headers["content-type"] == "text/plain;charset=US-ASCII"
+    with pytest.raises(Exception):
+        client.get("data:text/plain")
+
+
+def test_client_idn_url():
+    transport = primp.MockTransport()
+    transport.add("https://xn--mnchen-3ya.de/*", json={"ok": True})
+    client = primp.Client(transport=transport)
+    response = client.get("https://münchen.de/straße?q=ü ä")
+    assert response.url == "https://xn--mnchen-3ya.de/stra%C3%9Fe?q=%C3%BC%20%C3%A4"
+    assert transport.calls == [("GET", "https://xn--mnchen-3ya.de/stra%C3%9Fe?q=%C3%BC%20%C3%A4")]
+
+    client = primp.Client(transport=transport, normalize_urls=False)
+    with pytest.raises(primp.InvalidRequest):
+        client.get("https://münchen.de/straße")
+    response = client.get("https://münchen.de/stra%C3%9Fe?q=%c3%bc")
+    assert response.url == "https://xn--mnchen-3ya.de/stra%C3%9Fe?q=%c3%bc"
+
+
+def test_client_ipv6_url():
+    transport = primp.MockTransport()
+    transport.add("https://[2001:db8::1]:8443/*", json={"ok": True})
+    transport.add("http://[::1]/*", content=b"local")
+    client = primp.Client(transport=transport)
+    response = client.get("https://[2001:db8::1]:8443/path")
+    assert response.json() == {"ok": True}
+    response = client.get("http://[::1%25lo]/zone")
+    assert response.content == b"local"
+    assert transport.calls[-1] == ("GET", "http://[::1]/zone")
+    with pytest.raises(Exception):
+        client.set_proxy("http://[fe80::1%25lo]:3128")
+
+
+def test_requests_adapter():
+    requests = pytest.importorskip("requests")
+    from primp.adapters import PrimpAdapter
+
+    transport = primp.MockTransport()
+    transport.add("https://example.com/old", status_code=302, headers={"location": "https://example.com/new"})
+    transport.add("https://example.com/new", headers={"content-type": "text/plain; charset=utf-8"}, content=b"new")
+    transport.add("https://example.com/fail", status_code=500)
+    session = requests.Session()
+    session.mount("https://", PrimpAdapter(transport=transport))
+
+    response = session.post("https://example.com/old", data={"a": "1"}, timeout=(3, 5))
+    assert response.status_code == 200
+    assert response.text == "new"
+    assert response.encoding == "utf-8"
+    assert response.url == "https://example.com/new"
+    assert response.history[0].status_code == 302
+    assert transport.calls == [("POST", "https://example.com/old"), ("GET", "https://example.com/new")]
+    with pytest.raises(requests.HTTPError):
+        session.get("https://example.com/fail").raise_for_status()
+    with pytest.raises(requests.RequestException):
+        session.get("https://example.com/missing")
+
+
+def test_curl_cffi_compat():
+    from primp import requests
+
+    transport = primp.MockTransport()
+    transport.add("https://example.com/*", json={"ok": True})
+    transport.add("https://example.com/missing", status_code=404)
+    with requests.Session(transport=transport, impersonate="chrome131", timeout=(3, 5)) as session:
+        assert isinstance(session.client, primp.Client)
+        response = session.post("https://example.com/api", data="a=1", timeout=(1, None))
+        assert response.ok
+        assert response.reason == "OK"
+        assert response.json() == {"ok": True}
+        response = session.get("https://example.com/missing", allow_redirects=False)
+        assert not response.ok
+        assert response.reason == "Not Found"
+    assert transport.calls == [("POST", "https://example.com/api"), ("GET", "https://example.com/missing")]
+    response = requests.get("https://example.com/api", transport=transport, impersonate="safari17_0")
+    assert response.status_code == 200
+
+
+def test_httpx_compat():
+    from primp.compat import httpx
+
+    transport = primp.MockTransport()
+    transport.add("https://api.example.com/v1/*", json={"ok": True})
+    transport.add("https://api.example.com/v1/old", status_code=302, headers={"location": "/v1/new"})
+    transport.add("https://api.example.com/v1/missing", status_code=404)
+    with httpx.Client(base_url="https://api.example.com/v1/", transport=transport, http2=True) as client:
+        response = client.get("/old")
+        assert response.status_code == 302
+        assert response.is_redirect
+        response = client.get("new", follow_redirects=True, timeout=5)
+        assert response.raise_for_status() is response
+        assert response.is_success
+        assert response.reason_phrase == "OK"
+        assert response.json() == {"ok": True}
+        assert response.url == "https://api.example.com/v1/new"
+        response = client.post("/missing", content="text")
+        assert response.is_client_error
+        with pytest.raises(primp.HTTPError):
+            response.raise_for_status()
+
+    async def main():
+        async with httpx.AsyncClient(base_url="https://api.example.com/v1", transport=transport) as client:
+            return await asyncio.gather(client.get("/a"), client.put("/b", json={"a": 1}))
+
+    responses = asyncio.run(main())
+    assert [response.json() for response in responses] == [{"ok": True}, {"ok": True}]
+    assert ("PUT", "https://api.example.com/v1/b") in transport.calls
+
+
+def test_scrapy_download_handler():
+    scrapy = pytest.importorskip("scrapy")
+    from scrapy.settings import Settings
+    from primp.scrapy import PrimpDownloadHandler
+
+    transport = primp.MockTransport()
+    transport.add("https://example.com/*", headers={"content-type": "text/html"}, content="<p>ok</p>")
+    transport.add("https://example.com/api", headers={"content-type": "application/json"}, json={"a": 1})
+    settings = Settings({"PRIMP_IMPERSONATE": "chrome_131", "PRIMP_CLIENT_KWARGS": {"transport": transport}})
+    handler = PrimpDownloadHandler(settings)
+
+    response = handler._download(scrapy.Request("https://example.com/page", meta={"impersonate": "firefox_133"}))
+    assert isinstance(response, scrapy.http.HtmlResponse)
+    assert response.css("p::text").get() == "ok"
+    request = scrapy.Request("https://example.com/api", method="POST", body=b"x", meta={"download_timeout": 5})
+    response = handler._download(request)
+    assert response.status == 200
+    assert response.request is request
+    assert response.headers[b"content-type"] == b"application/json"
+    assert transport.calls == [("GET", "https://example.com/page"), ("POST", "https://example.com/api")]
+
+
+@retry()
+def test_client_verify_hostname():
+    with pytest.raises(Exception):
+        primp.Client().get("https://wrong.host.badssl.com/")
+    client = primp.Client(verify_hostname=False)
+    response = client.get("https://wrong.host.badssl.com/")
+    assert response.status_code == 200
+
+
+@retry()
+def test_client_session_resumption():
+    client = primp.Client(session_resumption=True, pool_max_idle_per_host=0)
+    for _ in range(2):
+        response = client.get("https://httpbin.org/anything")
+        assert response.status_code == 200
+
+
+@retry()
+def test_client_ech_grease():
+    client = primp.Client(ech_grease=True)
+    response = client.get("https://tls.http.rw/api/all")
+    assert response.status_code == 200
+    extensions = response.json()["tls"]["extensions"]
+    assert any("encrypted_client_hello" in str(ext.get("name", "")) for ext in extensions)
+
+
+def test_client_cert_compression_invalid():
+    with pytest.raises(Exception):
+        primp.Client(cert_compression=["gzip"])
+
+
+@retry()
+def test_client_permute_extensions():
+    client = primp.Client(impersonate="chrome_131", permute_extensions=True, pool_max_idle_per_host=0)
+    ja4 = set()
+    for _ in range(2):
+        response = client.get("https://tls.http.rw/api/all")
+        assert response.status_code == 200
+        ja4.add(response.json()["tls"]["ja4"])
+    assert len(ja4) == 1
+
+
+@retry()
+def test_client_tls_lists():
+    client = primp.Client(
+        tls_ciphers=["TLS_AES_128_GCM_SHA256", "ECDHE-RSA-AES128-GCM-SHA256"],
+        tls_sigalgs=["ecdsa_secp256r1_sha256", "rsa_pss_rsae_sha256", "rsa_pkcs1_sha256"],
+        tls_curves=["X25519", "P-256"],
+    )
+    response = client.get("https://httpbin.org/anything")
+    assert response.status_code == 200
+
+
+def test_client_tls_curves_invalid():
+    with pytest.raises(Exception):
+        primp.Client(tls_curves=["brainpool"])
+
+
+def test_from_ja3():
+    kwargs = primp.from_ja3("771,2570-4865-49195,2570-0-23,2570-29-23,0")
+    assert kwargs == {
+        "tls_ciphers": ["TLS_AES_128_GCM_SHA256", "ECDHE-ECDSA-AES128-GCM-SHA256"],
+        "tls_curves": ["X25519", "P-256"],
+    }
+    primp.Client(**kwargs)
+    with pytest.raises(Exception):
+        primp.from_ja3("771,4865")
+
+
+@retry()
+def test_client_expected_fingerprints():
+    client = primp.Client(impersonate="chrome_131")
+    expected = client.expected_fingerprints()
+    assert expected["ja4"] == "t13d1516h2_8daaf6152771_02713d6af862"
+    response = client.get("https://tls.http.rw/api/all")
+    assert response.json()["http2"]["akamai_fingerprint"] == expected["akamai_h2"]
+    assert response.json()["tls"]["ja4"] == expected["ja4"]
+
+
+def test_client_expected_fingerprints_ja4h():
+    expected = primp.Client().expected_fingerprints()
+    assert expected["akamai_h2"] is None
+    assert expected["ja4"] is None
+    # Accept-Encoding only
+    assert expected["ja4h"].startswith("ge20nn010000_")
+    client = primp.Client(headers={"Accept-Language": "de-DE"}, cookies={"a": "1"}, http1_only=True)
+    # Accept-Language, Accept-Encoding and Host
+    assert client.expected_fingerprints()["ja4h"].startswith("ge11cn03dede_")
+
+
+@retry()
+def test_client_http2_fingerprint():
+    fingerprint = "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p"
+    client = primp.Client(http2_fingerprint=fingerprint)
+    assert client.expected_fingerprints()["akamai_h2"] == fingerprint
+    response = client.get("https://tls.http.rw/api/all")
+    assert response.json()["http2"]["akamai_fingerprint"] == fingerprint
+
+
+@retry()
+def test_client_http2_keep_alive():
+    client = primp.Client(
+        impersonate="chrome_131",
+        http2_keep_alive_interval=1,
+        http2_keep_alive_timeout=5,
+        http2_keep_alive_while_idle=True,
+    )
+    assert client.get("https://tls.http.rw/api/clean").status_code == 200
+    sleep(2)
+    assert client.get("https://tls.http.rw/api/clean").status_code == 200
+
+
+@retry()
+def test_client_http1_title_case():
+    client = primp.Client(impersonate="firefox_133", http1_only=True)
+    response = client.get("https://tls.http.rw/api/all")
+    headers = response.json()["http1"]["headers"]
+    assert any(header.startswith("User-Agent: ") for header in headers)
+    assert "Connection: keep-alive" in headers
+
+
+@retry()
+def test_client_retry_stale_connections():
+    client = primp.Client(impersonate="chrome_131", http1_only=True, pool_idle_timeout=60)
+    # httpbin closes idle keep-alive connections after a few seconds, the pooled one turns stale
+    assert client.get("https://httpbin.org/get").status_code == 200
+    sleep(8)
+    assert client.get("https://httpbin.org/get").status_code == 200
+    client = primp.Client(retry_stale_connections=False)
+    assert client.get("https://httpbin.org/get").status_code == 200
+
+
+@retry()
+def test_client_retries():
+    with pytest.raises(Exception):
+        primp.Retry(backoff_factor=-1)
+    retries = primp.Retry(total=2, backoff_factor=0.5, status_forcelist=[503])
+    client = primp.Client(retries=retries)
+    start = time()
+    response = client.get("https://httpbin.org/status/503")
+    assert response.status_code == 503
+    assert time() - start >= 1.5  # 0.5 + 1 seconds of backoff
+    response = client.post("https://httpbin.org/status/503")
+    assert response.status_code == 503
+    with pytest.raises(primp.ConnectError) as exc_info:
+        client.get("http://127.0.0.1:1/")
+    assert exc_info.value.attempt == 3
+
+
+class TooManyRequestsHandler(BaseHTTPRequestHandler):
+    def do_GET(self):
+        self.send_response(429)
+        self.send_header("Retry-After", "1")
+        self.send_header("Content-Length", "0")
+        self.end_headers()
+
+    def log_message(self, *args):
+        pass
+
+
+def test_client_retries_retry_after():
+    server = HTTPServer(("127.0.0.1", 0), TooManyRequestsHandler)
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    url = f"http://127.0.0.1:{server.server_port}/"
+
+    throttled = []
+    retries = primp.Retry(
+        total=1,
+        status_forcelist=[429],
+        throttle_callback=lambda url, status, seconds: throttled.append((status, seconds)),
+    )
+    client = primp.Client(retries=retries)
+    start = time()
+    assert client.get(url).status_code == 429
+    assert time() - start >= 1
+    assert throttled == [(429, 1.0)]
+
+    retries = primp.Retry(total=1, status_forcelist=[429], retry_after_max=0.5)
+    client = primp.Client(retries=retries)
+    start = time()
+    assert client.get(url).status_code == 429
+    assert time() - start < 1
+    server.shutdown()
+
+
+class RedirectHandler(BaseHTTPRequestHandler):
+    """`/redirect?to=<url>&status=<status>&cookie=<cookie>` redirects to `<url>` (302 by default)
+    setting the cookies, `/echo` answers the method, headers (also as an ordered `header_list`)
+    and body of the request as JSON, other paths answer their path."""
+
+    def read_body(self):
+        if self.headers.get("Transfer-Encoding") != "chunked":
+            return self.rfile.read(int(self.headers.get("Content-Length", 0)))
+        body = b""
+        while size := int(self.rfile.readline().split(b";")[0], 16):
+            body += self.rfile.read(size)
+            self.rfile.readline()
+        self.rfile.readline()
+        return body
+
+    def do_GET(self):
+        request_body = self.read_body()
+        url = urlsplit(self.path)
+        if url.path == "/redirect":
+            query = parse_qs(url.query)
+            self.send_response(int(query.get("status", ["302"])[0]))
+            self.send_header("Location", query["to"][0])
+            for cookie in query.get("cookie", []):
+                self.send_header("Set-Cookie", cookie)
+            body = b""
+        elif url.path == "/echo":
+            self.send_response(200)
+            headers = {k.lower(): v for k, v in self.headers.items()}
+            echo = {
+                "method": self.command,
+                "headers": headers,
+                "header_list": self.headers.items(),
+                "body": request_body.decode(),
+            }
+            body = json.dumps(echo).encode()
+        else:
+            self.send_response(200)
+            body = self.path.encode()
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    do_POST = do_PUT = do_DELETE = do_QUERY = do_PROPFIND = do_GET
+
+    def log_message(self, *args):
+        pass
+
+
+@pytest.fixture
+def redirect_server():
+    server = HTTPServer(("127.0.0.1", 0), RedirectHandler)
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    yield server.server_port
+    server.shutdown()
+
+
+def test_client_redirect_policy(redirect_server):
+    base = f"http://127.0.0.1:{redirect_server}"
+    other = f"http://localhost:{redirect_server}"
+    client = primp.Client(redirect_policy="same_origin")
+    response = client.get(f"{base}/redirect?to=/ok")
+    assert (response.status_code, response.text) == (200, "/ok")
+    response = client.get(f"{base}/redirect?to={other}/ok")
+    assert response.status_code == 302
+    assert response.headers["location"] == f"{other}/ok"
+
+    calls = []
+    client = primp.Client(redirect_policy=["same_host", lambda *args: calls.append(args) or True])
+    response = client.get(f"{base}/redirect?to=/ok")
+    assert response.status_code == 200
+    assert calls == [(f"{base}/redirect?to=/ok", f"{base}/ok", 302)]
+    assert client.get(f"{base}/redirect?to={other}/ok").status_code == 302
+    assert len(calls) == 1
+
+    def deny(from_url, to_url, status):
+        raise ValueError(to_url)
+
+    with pytest.raises(ValueError, match="/ok"):
+        primp.Client(redirect_policy=deny).get(f"{base}/redirect?to=/ok")
+    with pytest.raises(ValueError):
+        primp.Client(redirect_policy="same_site")
+
+
+def test_client_redirect_headers(redirect_server):
+    base = f"http://127.0.0.1:{redirect_server}"
+    other = f"http://localhost:{redirect_server}"
+    headers = {"Authorization": "Bearer t", "X-Api-Key": "k", "X-Other": "o"}
+    client = primp.Client(redirect_strip_headers=["x-api-key"])
+    sent = client.get(f"{base}/redirect?to=/echo", headers=headers).json()["headers"]
+    assert (sent["authorization"], sent["x-api-key"]) == ("Bearer t", "k")
+    assert sent["referer"] == f"{base}/redirect?to=/echo"
+    sent = client.get(f"{base}/redirect?to={other}/echo", headers=headers).json()["headers"]
+    assert "authorization" not in sent and "x-api-key" not in sent
+    assert sent["x-other"] == "o"
+
+    client = primp.Client(redirect_forward_headers=["authorization"])
+    sent = client.get(f"{base}/redirect?to={other}/echo", headers=headers).json()["headers"]
+    assert (sent["authorization"], sent["x-api-key"]) == ("Bearer t", "k")
+
+
+def test_client_redirect_methods(redirect_server, tmp_path):
+    base = f"http://127.0.0.1:{redirect_server}"
+    client = primp.Client()
+    sent = client.post(f"{base}/redirect?to=/echo&status=307", content=b"body").json()
+    assert (sent["method"], sent["body"]) == ("POST", "body")
+    sent = client.post(f"{base}/redirect?to=/echo&status=302", content=b"body").json()
+    assert (sent["method"], sent["body"]) == ("GET", "")
+    sent = client.put(f"{base}/redirect?to=/echo&status=301", content=b"body").json()
+    assert (sent["method"], sent["body"]) == ("PUT", "body")
+    path = tmp_path / "upload.txt"
+    path.write_bytes(b"file content")
+    sent = client.post(f"{base}/redirect?to=/echo&status=308", files={"upload": str(path)}).json()
+    assert sent["method"] == "POST" and sent["body"].count("file content") == 1
+    boundary = sent["headers"]["content-type"].split("boundary=")[1]
+    assert sent["body"].endswith(f"--{boundary}--\r\n")
+    sent = client.post(f"{base}/redirect?to=/echo&status=302", files={"upload": str(path)}).json()
+    assert (sent["method"], sent["body"]) == ("GET", "")
+    to = quote("/redirect?to=/echo&status=307")
+    sent = client.post(f"{base}/redirect?to={to}&status=302", files={"upload": str(path)}).json()
+    assert (sent["method"], sent["body"]) == ("GET", "")
+
+    client = primp.Client(redirect_method_policy="strict")
+    sent = client.post(f"{base}/redirect?to=/echo&status=302", content=b"body").json()
+    assert (sent["method"], sent["body"]) == ("POST", "body")
+    sent = client.post(f"{base}/redirect?to=/echo&status=303", content=b"body").json()
+    assert (sent["method"], sent["body"]) == ("GET", "")
+    with pytest.raises(Exception):
+        primp.Client(redirect_method_policy="rfc")
+
+
+def test_client_request_methods_with_body(redirect_server):
+    url = f"http://127.0.0.1:{redirect_server}/echo"
+    client = primp.Client()
+    sent = client.request("QUERY", url, json={"q": "primp"}).json()
+    assert (sent["method"], json.loads(sent["body"])) == ("QUERY", {"q": "primp"})
+    assert sent["headers"]["content-type"] == "application/json"
+    sent = client.request("PROPFIND", url, content=b"<propfind/>").json()
+    assert (sent["method"], sent["body"]) == ("PROPFIND", "<propfind/>")
+    sent = client.request("DELETE", url, data={"id": "1"}).json()
+    assert (sent["method"], sent["body"]) == ("DELETE", "id=1")
+
+
+def test_client_get_delete_with_body(redirect_server, caplog):
+    caplog.set_level(logging.WARNING, logger="primp.request")
+    url = f"http://127.0.0.1:{redirect_server}/echo"
+    client = primp.Client()
+    sent = client.get(url, json={"query": {"match_all": {}}}).json()
+    assert (sent["method"], json.loads(sent["body"])) == ("GET", {"query": {"match_all": {}}})
+    sent = client.delete(url, content=b"ids=1,2").json()
+    assert (sent["method"], sent["body"]) == ("DELETE", "ids=1,2")
+    warnings = [r.getMessage() for r in caplog.records if r.levelno == logging.WARNING]
+    assert warnings == [
+        f"GET request with a body, which some servers and proxies ignore or reject: {url}",
+        f"DELETE request with a body, which some servers and proxies ignore or reject: {url}",
+    ]
+
+    caplog.clear()
+    client = primp.Client(warn_body_methods=False)
+    assert client.get(url, data={"a": "1"}).json()["body"] == "a=1"
+    assert not [r for r in caplog.records if r.levelno == logging.WARNING]
+
+
+def test_client_impersonate_skip_headers(redirect_server):
+    url = f"http://127.0.0.1:{redirect_server}/echo"
+    headers = primp.Client(impersonate="chrome_131").get(url).json()["headers"]
+    assert "sec-ch-ua" in headers and "Chrome/131" in headers["user-agent"]
+
+    client = primp.Client(
+        impersonate="chrome_131",
+        impersonate_skip_headers=True,
+        impersonate_skip_http2=True,
+        headers={"User-Agent": "my-agent"},
+    )
+    headers = client.get(url, headers={"X-Test": "1"}).json()["headers"]
+    assert headers["user-agent"] == "my-agent"
+    assert headers["x-test"] == "1"
+    assert "sec-ch-ua" not in headers and "sec-fetch-mode" not in headers
+
+
+def test_default_headers(redirect_server):
+    baseline = primp.default_headers("chrome_131")
+    assert baseline["sec-ch-ua-platform"] == '"macOS"'
+    windows = primp.default_headers(impersonate="chrome_131", impersonate_os="windows")
+    assert list(windows) == list(baseline)
+    assert "Windows NT 10.0" in windows["user-agent"]
+    with pytest.raises(Exception):
+        primp.default_headers("safari_18", impersonate_os="linux")
+
+    url = f"http://127.0.0.1:{redirect_server}/echo"
+    client = primp.Client(impersonate="chrome_131", impersonate_os="windows")
+    sent = client.get(url).json()["headers"]
+    assert sent["user-agent"] == windows["user-agent"]
+    assert sent["sec-ch-ua-platform"] == '"Windows"'
+
+
+def test_client_redirect_history(redirect_server):
+    base = f"http://127.0.0.1:{redirect_server}"
+    second = "/redirect?to=/echo%26cookie=step=2"
+    client = primp.Client()
+    response = client.get(f"{base}/redirect?to={second}&status=301&cookie=session=abc")
+    assert sorted(response.json()["headers"]["cookie"].split("; ")) == ["session=abc", "step=2"]
+    assert [(r.status_code, r.url) for r in response.history] == [
+        (301, f"{base}/redirect?to={second}&status=301&cookie=session=abc"),
+        (302, f"{base}{second.replace('%26', '&')}"),
+    ]
+    assert response.history[0].headers.get_all("set-cookie") == ["session=abc"]
+    assert response.history[1].cookies == {"step": "2"}
+    assert response.history[0].content == b""
+    assert response.history[0].history == []
+    assert client.get(f"{base}/echo").history == []
+
+
+@retry()
+def test_client_hpack_never_index():
+    client = primp.Client(impersonate="chrome_131", hpack_never_index=["cookie"])
+    response = client.get("https://httpbin.org/cookies", cookies={"a": "1"})
+    assert response.json()["cookies"] == {"a": "1"}
+    with pytest.raises(Exception):
+        primp.Client(hpack_never_index=["bad header"])
+
+
+@retry()
+def test_client_http2_settings():
+    client = primp.Client(
+        impersonate="chrome_131",
+        http2_settings={"enable_push": None, "max_concurrent_streams": 100, "settings_order": [3]},
+    )
+    fingerprint = "3:100;1:65536;4:6291456;6:262144|15663105|0|m,a,s,p"
+    assert client.expected_fingerprints()["akamai_h2"] == fingerprint
+    response = client.get("https://tls.http.rw/api/all")
+    assert response.json()["http2"]["akamai_fingerprint"] == fingerprint
+
+
+@retry()
+def test_client_http2_priorities():
+    client = primp.Client(
+        impersonate="chrome_131",
+        http2_priorities=[(3, 0, 201, False), (5, 0, 101, False)],
+        http2_headers_priority=(0, 256, True),
+    )
+    assert client.expected_fingerprints()["akamai_h2"].split("|")[2] == "3:0:0:201,5:0:0:101"
+    response = client.get("https://tls.http.rw/api/all")
+    assert response.json()["http2"]["akamai_fingerprint"].split("|")[2] == "3:0:0:201,5:0:0:101"
+
+
+@retry()
+def test_client_impersonate_profile():
+    fingerprint = "1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p"
+    profile = {
+        "headers": {"User-Agent": "primp-profile"},
+        "http2": fingerprint,
+    }
+    client = primp.Client(impersonate_profile=profile)
+    assert client.expected_fingerprints()["akamai_h2"] == fingerprint
+    response = client.get("https://httpbin.org/headers")
+    assert response.json()["headers"]["User-Agent"] == "primp-profile"
+
+
+def test_client_impersonate_profile_file(tmp_path):
+    profile_file = tmp_path / "profile.json"
+    profile_file.write_text('{"impersonate": "chrome_131", "tls": {"curves": ["X25519"]}}')
+    primp.Client(impersonate_profile_file=str(profile_file))
+    profile_file.write_text('{"tls": []}')
+    with pytest.raises(Exception):
+        primp.Client(impersonate_profile_file=str(profile_file))
+
+
+def test_profile_from_har(tmp_path):
+    har_file = tmp_path / "browser.har"
+    har_file.write_text(
+        '{"log": {"entries": [{"request": {"headers": ['
+        '{"name": ":authority", "value": "example.com"},'
+        '{"name": "user-agent", "value": "primp-har"},'
+        '{"name": "cookie", "value": "a=b"},'
+        '{"name": "accept", "value": "*/*"}]}}]}}'
+    )
+    profile = primp.profile_from_har(str(har_file))
+    assert profile["headers"] == {"user-agent": "primp-har", "accept": "*/*"}
+    assert profile["headers_order"] == ["user-agent", "accept"]
+    primp.Client(impersonate_profile=profile)
+
+
+def test_impersonate_aliases():
+    aliases = primp.impersonate_aliases()
+    assert aliases["firefox_esr"] == "firefox_128"
+    for alias in aliases:
+        primp.Client(impersonate=alias)
+
+
+def test_impersonate_list():
+    impersonate_list = primp.impersonate_list()
+    assert "chrome_131" in impersonate_list
+    assert "chrome" not in impersonate_list
+    assert set(primp.impersonate_aliases().values()) <= set(impersonate_list)
+
+
+def test_impersonate_os_list():
+    assert primp.impersonate_os_list() == ["windows", "macos", "linux", "android", "ios"]
+
+
+@retry()
+def test_client_locale():
+    client = primp.Client(impersonate="chrome_131", locale="de-DE")
+    response = client.get("https://httpbin.org/headers")
+    assert response.json()["headers"]["Accept-Language"] == "de-DE,de;q=0.9,en-US;q=0.8,en;q=0.7"
+    with pytest.raises(Exception):
+        primp.Client(locale="german")
+
+
+@retry()
+def test_client_client_hints():
+    client = primp.Client(impersonate="chrome_131", client_hints=True)
+    url = "https://httpbin.org/response-headers?Accept-CH=sec-ch-ua-platform-version"
+    response = client.get(url)
+    assert "Sec-Ch-Ua-Platform-Version" not in response.json()
+    response = client.get("https://httpbin.org/headers")
+    assert response.json()["headers"]["Sec-Ch-Ua-Platform-Version"] == '"14.5.0"'
+    with pytest.raises(Exception):
+        primp.Client(impersonate="firefox_133", client_hints=True)
+
+
+@retry()
+def test_client_mobile():
+    client = primp.Client(impersonate="chrome_131", mobile=True)
+    headers = client.get("https://httpbin.org/headers").json()["headers"]
+    assert "Android" in headers["User-Agent"]
+    assert headers["Sec-Ch-Ua-Mobile"] == "?1"
+    with pytest.raises(Exception):
+        primp.Client(mobile=True)
+
+
+@retry()
+def test_client_request_context():
+    client = primp.Client(impersonate="chrome_131", request_context="fetch")
+    headers = client.get("https://httpbin.org/headers").json()["headers"]
+    assert headers["Accept"] == "*/*"
+    assert headers["Sec-Fetch-Mode"] == "cors"
+    assert "Sec-Fetch-User" not in headers
+    with pytest.raises(Exception):
+        primp.Client(request_context="script")
+
+
+@retry()
+def test_client_track_navigation():
+    client = primp.Client(impersonate="chrome_131", track_navigation=True)
+    headers = client.get("https://httpbin.org/headers").json()["headers"]
+    assert headers["Sec-Fetch-Site"] == "none"
+    headers = client.get("https://httpbin.org/headers").json()["headers"]
+    assert headers["Sec-Fetch-Site"] == "same-origin"
+    assert headers["Referer"] == "https://httpbin.org/headers"
+
+    client = primp.Client(impersonate="chrome_131")
+    client.get("https://httpbin.org/headers")
+    headers = client.get("https://httpbin.org/headers").json()["headers"]
+    assert headers["Sec-Fetch-Site"] == "none"
+    assert "Referer" not in headers
+
+
+def test_client_track_navigation_redirect(redirect_server):
+    base = f"http://127.0.0.1:{redirect_server}"
+    other = f"http://localhost:{redirect_server}"
+    client = primp.Client(impersonate="chrome_131", track_navigation=True)
+    client.get(f"{base}/echo")
+    sent = client.get(f"{base}/redirect?to={other}/echo").json()["headers"]
+    assert sent["sec-fetch-site"] == "cross-site"
+    assert sent["referer"] == f"{base}/"
+    sent = client.get(f"{other}/redirect?to=/echo").json()["headers"]
+    assert sent["sec-fetch-site"] == "same-origin"
+    assert sent["referer"] == f"{other}/echo"